@@ -65,6 +65,53 @@ fn deref_static(b: &mut Bencher) {
     })
 }
 
+#[bench]
+fn deref_inline(b: &mut Bencher) {
+    let buf = Bytes::copy_from_slice(b"hello world");
+
+    b.iter(|| {
+        for _ in 0..1024 {
+            test::black_box(&buf[..]);
+        }
+    })
+}
+
+// Short and long handles interleaved, so the inline check can't be
+// predicted. Run it on a tree without inline storage to compare.
+#[bench]
+fn deref_mixed(b: &mut Bencher) {
+    let long = Bytes::from(vec![0; 1024]);
+    let bufs: Vec<Bytes> = (0..64)
+        .map(|i| {
+            if i % 3 == 0 {
+                Bytes::copy_from_slice(b"hello world")
+            } else {
+                long.clone()
+            }
+        })
+        .collect();
+
+    b.iter(|| {
+        for _ in 0..16 {
+            for buf in &bufs {
+                test::black_box(&buf[..]);
+            }
+        }
+    })
+}
+
+// Baseline for the `deref_*` benches, a plain slice has no inline check.
+#[bench]
+fn deref_vec(b: &mut Bencher) {
+    let buf = vec![0u8; 1024];
+
+    b.iter(|| {
+        for _ in 0..1024 {
+            test::black_box(&buf[..]);
+        }
+    })
+}
+
 #[bench]
 fn clone_static(b: &mut Bencher) {
     let bytes =
@@ -0,0 +1,419 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Derive macros for [`rcbytes::wire`].
+//!
+//! Use them through the `derive` feature of `rcbytes`, which re-exports
+//! them next to the traits they implement. See the documentation of
+//! `rcbytes::wire` for the attributes they understand.
+//!
+//! [`rcbytes::wire`]: https://docs.rs/rcbytes/*/rcbytes/wire/index.html
+
+#![warn(missing_docs, missing_debug_implementations, rust_2018_idioms)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Expr, Fields, Generics,
+    Lit, LitInt, LitStr, Member, Result, Type,
+};
+
+/// Implements `rcbytes::wire::BufEncode`.
+#[proc_macro_derive(BufEncode, attributes(wire))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `rcbytes::wire::BufDecode`.
+#[proc_macro_derive(BufDecode, attributes(wire))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+// ===== attributes =====
+
+/// How a length prefix or tag is written, as given in `len = ".."` or
+/// `tag = ".."`.
+struct Repr {
+    name: LitStr,
+    le: bool,
+}
+
+impl Repr {
+    fn tokens(&self) -> Result<TokenStream2> {
+        let variant = match (self.name.value().as_str(), self.le) {
+            ("u8", _) => "U8",
+            ("u16", false) => "U16",
+            ("u16", true) => "U16Le",
+            ("u32", false) => "U32",
+            ("u32", true) => "U32Le",
+            ("u64", false) => "U64",
+            ("u64", true) => "U64Le",
+            ("varint", false) => "Varint",
+            ("varint", true) => {
+                return Err(Error::new_spanned(
+                    &self.name,
+                    "a varint has no byte order, remove `le`",
+                ))
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    &self.name,
+                    "expected one of \"u8\", \"u16\", \"u32\", \"u64\" or \"varint\"",
+                ))
+            }
+        };
+        let variant = format_ident!("{}", variant);
+        Ok(quote!(::rcbytes::wire::IntRepr::#variant))
+    }
+
+    /// The largest value that can be written.
+    fn max(&self) -> u64 {
+        match self.name.value().as_str() {
+            "u8" => u8::MAX.into(),
+            "u16" => u16::MAX.into(),
+            "u32" => u32::MAX.into(),
+            _ => u64::MAX,
+        }
+    }
+}
+
+fn default_tag_repr() -> Repr {
+    Repr {
+        name: LitStr::new("u8", Span::call_site()),
+        le: false,
+    }
+}
+
+enum Encoding {
+    Default,
+    Le,
+    Varint,
+    Prefixed(TokenStream2),
+    Skip,
+}
+
+fn field_encoding(attrs: &[Attribute]) -> Result<Encoding> {
+    let mut le = false;
+    let mut varint = false;
+    let mut skip = false;
+    let mut len = None;
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("wire")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("le") {
+                le = true;
+            } else if meta.path.is_ident("varint") {
+                varint = true;
+            } else if meta.path.is_ident("skip") {
+                skip = true;
+            } else if meta.path.is_ident("len") {
+                len = Some(meta.value()?.parse::<LitStr>()?);
+            } else {
+                return Err(meta.error("expected `le`, `varint`, `len` or `skip`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let span = attrs
+        .iter()
+        .find(|a| a.path().is_ident("wire"))
+        .map_or_else(Span::call_site, |a| a.span());
+    match (skip, varint, len) {
+        (true, false, None) if !le => Ok(Encoding::Skip),
+        (false, false, Some(name)) => Ok(Encoding::Prefixed(Repr { name, le }.tokens()?)),
+        (false, true, None) if !le => Ok(Encoding::Varint),
+        (false, false, None) if le => Ok(Encoding::Le),
+        (false, false, None) => Ok(Encoding::Default),
+        _ => Err(Error::new(
+            span,
+            "`skip`, `varint`, `len` and `le` cannot be combined, except `len` with `le`",
+        )),
+    }
+}
+
+fn container_tag(attrs: &[Attribute]) -> Result<Repr> {
+    let mut repr = default_tag_repr();
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("wire")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tag") {
+                repr.name = meta.value()?.parse()?;
+            } else if meta.path.is_ident("le") {
+                repr.le = true;
+            } else {
+                return Err(meta.error("expected `tag` or `le`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(repr)
+}
+
+fn variant_tag(attrs: &[Attribute], discriminant: Option<&Expr>) -> Result<Option<u64>> {
+    let mut tag = None;
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("wire")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tag") {
+                tag = Some(meta.value()?.parse::<LitInt>()?.base10_parse::<u64>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `tag`"))
+            }
+        })?;
+    }
+
+    if tag.is_some() {
+        return Ok(tag);
+    }
+
+    match discriminant {
+        None => Ok(None),
+        Some(Expr::Lit(expr)) => match &expr.lit {
+            Lit::Int(lit) => lit.base10_parse::<u64>().map(Some),
+            lit => Err(Error::new_spanned(lit, "expected an integer discriminant")),
+        },
+        Some(expr) => Err(Error::new_spanned(
+            expr,
+            "the tag of a variant with a computed discriminant must be given with `#[wire(tag = ..)]`",
+        )),
+    }
+}
+
+// ===== code generation =====
+
+fn add_bounds(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+fn encode_field(access: TokenStream2, encoding: &Encoding) -> TokenStream2 {
+    match encoding {
+        Encoding::Default => quote!(::rcbytes::wire::BufEncode::encode(#access, __buf);),
+        Encoding::Le => quote!(::rcbytes::wire::LittleEndian::encode_le(#access, __buf);),
+        Encoding::Varint => quote!(::rcbytes::wire::Varint::encode_varint(#access, __buf);),
+        Encoding::Prefixed(repr) => quote!(#repr.put_prefixed(__buf, #access);),
+        Encoding::Skip => quote!(),
+    }
+}
+
+fn decode_field(ty: &Type, encoding: &Encoding) -> TokenStream2 {
+    match encoding {
+        Encoding::Default => quote!(<#ty as ::rcbytes::wire::BufDecode>::decode(__buf)?),
+        Encoding::Le => quote!(<#ty as ::rcbytes::wire::LittleEndian>::decode_le(__buf)?),
+        Encoding::Varint => quote!(<#ty as ::rcbytes::wire::Varint>::decode_varint(__buf)?),
+        Encoding::Prefixed(repr) => quote!(#repr.get_prefixed::<#ty, _>(__buf)?),
+        Encoding::Skip => quote!(::core::default::Default::default()),
+    }
+}
+
+/// Names the fields of a struct or variant when it is destructured.
+fn bindings(fields: &Fields) -> Vec<proc_macro2::Ident> {
+    (0..fields.len())
+        .map(|i| format_ident!("__f{}", i))
+        .collect()
+}
+
+/// Builds `path { a: __f0, .. }`, `path(__f0, ..)` or `path` from the
+/// bindings of `fields`.
+fn construct(path: TokenStream2, fields: &Fields) -> TokenStream2 {
+    let names = bindings(fields);
+    match fields {
+        Fields::Named(named) => {
+            let members = named.named.iter().map(|f| &f.ident);
+            quote!(#path { #(#members: #names),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#names),*)),
+        Fields::Unit => path,
+    }
+}
+
+fn decode_fields(fields: &Fields) -> Result<TokenStream2> {
+    let names = bindings(fields);
+    let mut out = TokenStream2::new();
+    for (field, name) in fields.iter().zip(&names) {
+        let value = decode_field(&field.ty, &field_encoding(&field.attrs)?);
+        out.extend(quote!(let #name = #value;));
+    }
+    Ok(out)
+}
+
+fn enum_tags(data: &syn::DataEnum, repr: &Repr) -> Result<Vec<u64>> {
+    let mut tags: Vec<u64> = Vec::new();
+    let mut next = 0u64;
+    for variant in &data.variants {
+        let tag = variant_tag(
+            &variant.attrs,
+            variant.discriminant.as_ref().map(|(_, e)| e),
+        )?
+        .unwrap_or(next);
+        if tag > repr.max() {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                format!("tag {} does not fit in a {:?} tag", tag, repr.name.value()),
+            ));
+        }
+        if tags.contains(&tag) {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                format!("tag {} is used by more than one variant", tag),
+            ));
+        }
+        tags.push(tag);
+        next = tag.wrapping_add(1);
+    }
+    Ok(tags)
+}
+
+fn expand_encode(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(::rcbytes::wire::BufEncode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let mut out = TokenStream2::new();
+            for (i, field) in data.fields.iter().enumerate() {
+                let member = match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(i.into()),
+                };
+                out.extend(encode_field(
+                    quote!(&self.#member),
+                    &field_encoding(&field.attrs)?,
+                ));
+            }
+            out
+        }
+        Data::Enum(data) => {
+            let tag_repr = container_tag(&input.attrs)?;
+            let repr = tag_repr.tokens()?;
+            let tags = enum_tags(data, &tag_repr)?;
+            let mut arms = TokenStream2::new();
+            for (variant, tag) in data.variants.iter().zip(tags) {
+                let ident = &variant.ident;
+                let pattern = construct(quote!(Self::#ident), &variant.fields);
+                let mut fields = TokenStream2::new();
+                for (field, binding) in variant.fields.iter().zip(bindings(&variant.fields)) {
+                    fields.extend(encode_field(
+                        quote!(#binding),
+                        &field_encoding(&field.attrs)?,
+                    ));
+                }
+                arms.extend(quote! {
+                    #[allow(unused_variables)]
+                    #pattern => {
+                        #repr.put(__buf, #tag);
+                        #fields
+                    }
+                });
+            }
+            quote!(match self { #arms })
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "BufEncode cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::rcbytes::wire::BufEncode for #name #ty_generics #where_clause {
+            fn encode<__B: ::rcbytes::BufMut>(&self, __buf: &mut __B) {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_decode(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(::rcbytes::wire::BufDecode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = decode_fields(&data.fields)?;
+            let value = construct(quote!(Self), &data.fields);
+            quote! {
+                #fields
+                ::core::result::Result::Ok(#value)
+            }
+        }
+        Data::Enum(data) => {
+            let tag_repr = container_tag(&input.attrs)?;
+            let repr = tag_repr.tokens()?;
+            let tags = enum_tags(data, &tag_repr)?;
+            let mut arms = TokenStream2::new();
+            for (variant, tag) in data.variants.iter().zip(tags) {
+                let ident = &variant.ident;
+                let fields = decode_fields(&variant.fields)?;
+                let value = construct(quote!(Self::#ident), &variant.fields);
+                arms.extend(quote! {
+                    #tag => {
+                        #fields
+                        ::core::result::Result::Ok(#value)
+                    }
+                });
+            }
+            quote! {
+                let __tag = #repr.get(__buf)?;
+                match __tag {
+                    #arms
+                    _ => ::core::result::Result::Err(::rcbytes::wire::WireError::InvalidTag(__tag)),
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "BufDecode cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::rcbytes::wire::BufDecode for #name #ty_generics #where_clause {
+            fn decode<__B: ::rcbytes::Buf>(
+                __buf: &mut __B,
+            ) -> ::core::result::Result<Self, ::rcbytes::wire::WireError> {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,312 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use crate::buf::UninitSlice;
+use crate::{Buf, BufMut, Bytes};
+
+use core::mem::MaybeUninit;
+use core::{cmp, hash, ops, ptr, slice};
+
+/// A fixed-capacity buffer stored inline, without any heap allocation.
+///
+/// `ArrayBuf` holds up to `N` bytes. Writing through [`BufMut`] appends after
+/// the filled part, and reading through [`Buf`] consumes from its front. The
+/// filled part is available as a slice through `Deref`.
+///
+/// Space freed by reads is reused: once everything written has been read,
+/// both cursors go back to the start. A write that runs out of room at the
+/// end, or a call to [`chunk_mut`], moves the unread bytes to the front
+/// first, so the free space is always available as one chunk. Writing more
+/// than `N` unread bytes panics, like writing past the end of a `&mut [u8]`.
+///
+/// Converting into [`Bytes`] copies the filled part, which is stored inline
+/// in the `Bytes` when it is small enough.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::buf::ArrayBuf;
+/// use rcbytes::{Buf, BufMut, Bytes};
+///
+/// let mut buf = ArrayBuf::<16>::new();
+/// buf.put_u16(0x0102);
+/// buf.put_slice(b"abc");
+/// assert_eq!(&buf[..], b"\x01\x02abc");
+///
+/// assert_eq!(buf.get_u16(), 0x0102);
+/// assert_eq!(Bytes::from(buf), "abc");
+/// ```
+///
+/// [`chunk_mut`]: BufMut::chunk_mut
+#[derive(Clone)]
+pub struct ArrayBuf<const N: usize> {
+    buf: [MaybeUninit<u8>; N],
+    // Bytes in `start..end` are initialized and unread.
+    start: usize,
+    end: usize,
+}
+
+impl<const N: usize> ArrayBuf<N> {
+    /// Creates an empty buffer.
+    #[inline]
+    pub const fn new() -> ArrayBuf<N> {
+        ArrayBuf {
+            buf: [MaybeUninit::uninit(); N],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Returns the number of bytes the buffer can hold, `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of unread bytes.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if there are no unread bytes.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns `true` if the buffer holds `N` unread bytes.
+    #[inline]
+    pub const fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Discards all unread bytes.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.end = 0;
+    }
+
+    /// Shortens the buffer to its first `len` unread bytes.
+    ///
+    /// Has no effect if `len` is greater than the current length.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.end = self.start + len;
+        }
+    }
+
+    /// Moves the unread bytes to the front of the buffer.
+    fn compact(&mut self) {
+        let len = self.len();
+        // Safety: both ranges are in bounds, and `copy` allows overlap.
+        unsafe {
+            let ptr = self.buf.as_mut_ptr();
+            ptr::copy(ptr.add(self.start), ptr, len);
+        }
+        self.start = 0;
+        self.end = len;
+    }
+}
+
+impl<const N: usize> Default for ArrayBuf<N> {
+    #[inline]
+    fn default() -> ArrayBuf<N> {
+        ArrayBuf::new()
+    }
+}
+
+impl<const N: usize> ops::Deref for ArrayBuf<N> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        // Safety: `start..end` is initialized.
+        unsafe { slice::from_raw_parts(self.buf.as_ptr().add(self.start) as *const u8, self.len()) }
+    }
+}
+
+impl<const N: usize> ops::DerefMut for ArrayBuf<N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        let len = self.len();
+        // Safety: `start..end` is initialized.
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr().add(self.start) as *mut u8, len) }
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for ArrayBuf<N> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<const N: usize> AsMut<[u8]> for ArrayBuf<N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl<const N: usize> Buf for ArrayBuf<N> {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn chunk(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.len(),
+            "cannot advance past `remaining`: {:?} <= {:?}",
+            cnt,
+            self.len(),
+        );
+        self.start += cnt;
+        if self.start == self.end {
+            self.clear();
+        }
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(len <= self.len(), "`len` greater than remaining");
+        let bytes = Bytes::copy_from_slice(&self[..len]);
+        self.advance(len);
+        bytes
+    }
+}
+
+unsafe impl<const N: usize> BufMut for ArrayBuf<N> {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        N - self.len()
+    }
+
+    #[inline]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        // Hand out all of the free space as one chunk, so that `put_with`
+        // can always write in place.
+        if self.start > 0 {
+            self.compact();
+        }
+        // Safety: `end..N` is in bounds and only written through the slice.
+        unsafe {
+            UninitSlice::from_raw_parts_mut(
+                self.buf.as_mut_ptr().add(self.end) as *mut u8,
+                N - self.end,
+            )
+        }
+    }
+
+    #[inline]
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        let remaining = N - self.end;
+        assert!(
+            cnt <= remaining,
+            "cannot advance past `remaining_mut`: {:?} <= {:?}",
+            cnt,
+            remaining
+        );
+        self.end += cnt;
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        assert!(
+            src.len() <= self.remaining_mut(),
+            "buffer overflow; remaining = {}; src = {}",
+            self.remaining_mut(),
+            src.len()
+        );
+        if src.len() > N - self.end {
+            self.compact();
+        }
+        // Safety: there is room for `src` after `end`, and `src` cannot
+        // overlap the exclusively borrowed buffer.
+        unsafe {
+            let dst = self.buf.as_mut_ptr().add(self.end) as *mut u8;
+            ptr::copy_nonoverlapping(src.as_ptr(), dst, src.len());
+        }
+        self.end += src.len();
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for ArrayBuf<N> {
+    /// Creates a full buffer holding `array`.
+    fn from(array: [u8; N]) -> ArrayBuf<N> {
+        let mut buf = ArrayBuf::new();
+        buf.put_slice(&array);
+        buf
+    }
+}
+
+impl<const N: usize> From<ArrayBuf<N>> for Bytes {
+    fn from(buf: ArrayBuf<N>) -> Bytes {
+        Bytes::copy_from_slice(&buf)
+    }
+}
+
+impl<const N: usize> PartialEq for ArrayBuf<N> {
+    fn eq(&self, other: &ArrayBuf<N>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<const N: usize> Eq for ArrayBuf<N> {}
+
+impl<const N: usize> PartialEq<[u8]> for ArrayBuf<N> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self[..] == *other
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8]> for ArrayBuf<N> {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self[..] == **other
+    }
+}
+
+impl<const N: usize> PartialOrd for ArrayBuf<N> {
+    fn partial_cmp(&self, other: &ArrayBuf<N>) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for ArrayBuf<N> {
+    fn cmp(&self, other: &ArrayBuf<N>) -> cmp::Ordering {
+        self[..].cmp(&other[..])
+    }
+}
+
+impl<const N: usize> hash::Hash for ArrayBuf<N> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self[..].hash(state);
+    }
+}
@@ -0,0 +1,484 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use crate::{Buf, BufMut};
+
+use core::{cmp, fmt};
+
+/// The order in which the bits of a byte are read or written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// The most significant bit of each byte comes first, and values are
+    /// read and written starting with their most significant bit.
+    ///
+    /// This is the order used by H.264, HPACK and QPACK.
+    MsbFirst,
+    /// The least significant bit of each byte comes first, and values are
+    /// read and written starting with their least significant bit.
+    ///
+    /// This is the order used by DEFLATE.
+    LsbFirst,
+}
+
+/// The error returned by [`BitReader`] and [`BitWriter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BitError {
+    /// There were not enough bits left to read, or not enough capacity left
+    /// to write.
+    Truncated {
+        /// The number of bits the operation needed.
+        requested: u64,
+        /// The number of bits that were left.
+        available: u64,
+    },
+    /// An Exp-Golomb code encodes a value that does not fit the requested
+    /// integer type.
+    Overflow,
+}
+
+impl fmt::Display for BitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BitError::Truncated {
+                requested,
+                available,
+            } => write!(
+                f,
+                "not enough bits remaining (requested {} but only {} available)",
+                requested, available
+            ),
+            BitError::Overflow => f.write_str("Exp-Golomb code overflows the integer type"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BitError {}
+
+#[inline]
+fn mask(n: u32) -> u64 {
+    if n == 64 {
+        !0
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Reads values of arbitrary bit width from a [`Buf`].
+///
+/// Bytes are pulled from the underlying buffer one at a time as they are
+/// needed, so the buffer may be split into any number of chunks. Reads that
+/// would run past the end of the buffer return [`BitError::Truncated`]
+/// instead of panicking.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::buf::BitReader;
+///
+/// // An H.264 NAL unit header: forbidden_zero_bit, nal_ref_idc, nal_unit_type.
+/// let mut bits = BitReader::new(&b"\x67"[..]);
+///
+/// assert_eq!(bits.read_bit(), Ok(false));
+/// assert_eq!(bits.read_bits(2), Ok(3));
+/// assert_eq!(bits.read_bits(5), Ok(7));
+/// assert!(bits.read_bit().is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitReader<B> {
+    inner: B,
+    order: BitOrder,
+    // The byte currently being read and the number of its bits not yet
+    // consumed.
+    cur: u8,
+    left: u32,
+}
+
+impl<B: Buf> BitReader<B> {
+    /// Creates a reader that reads the most significant bit first.
+    pub fn new(inner: B) -> BitReader<B> {
+        BitReader::with_order(inner, BitOrder::MsbFirst)
+    }
+
+    /// Creates a reader that reads bits in the given order.
+    pub fn with_order(inner: B, order: BitOrder) -> BitReader<B> {
+        BitReader {
+            inner,
+            order,
+            cur: 0,
+            left: 0,
+        }
+    }
+
+    /// Returns the bit order of this reader.
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Gets a reference to the underlying buffer.
+    ///
+    /// The byte currently being read has already been consumed from it.
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying buffer.
+    ///
+    /// The byte currently being read has already been consumed from it.
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Consumes this `BitReader`, returning the underlying buffer.
+    ///
+    /// Unread bits of the byte currently being read are lost.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// Returns the number of bits left to read.
+    pub fn remaining_bits(&self) -> u64 {
+        (self.inner.remaining() as u64)
+            .saturating_mul(8)
+            .saturating_add(self.left as u64)
+    }
+
+    /// Returns `true` if the next bit to read starts a byte.
+    pub fn is_aligned(&self) -> bool {
+        self.left == 0
+    }
+
+    /// Skips the rest of the byte currently being read, if any.
+    pub fn align_to_byte(&mut self) {
+        self.left = 0;
+    }
+
+    /// Reads a single bit.
+    pub fn read_bit(&mut self) -> Result<bool, BitError> {
+        self.read_bits(1).map(|bit| bit == 1)
+    }
+
+    /// Reads an unsigned value of `n` bits.
+    ///
+    /// Nothing is consumed if there are fewer than `n` bits left.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `n` is greater than 64.
+    pub fn read_bits(&mut self, n: u32) -> Result<u64, BitError> {
+        assert!(n <= 64, "cannot read more than 64 bits at once");
+
+        let available = self.remaining_bits();
+        if (n as u64) > available {
+            return Err(BitError::Truncated {
+                requested: n as u64,
+                available,
+            });
+        }
+
+        let mut value = 0;
+        let mut shift = 0;
+        let mut n = n;
+        while n > 0 {
+            if self.left == 0 {
+                self.cur = self.inner.get_u8();
+                self.left = 8;
+            }
+
+            let k = cmp::min(n, self.left);
+            match self.order {
+                BitOrder::MsbFirst => {
+                    let bits = (self.cur >> (self.left - k)) as u64 & mask(k);
+                    value = (value << k) | bits;
+                }
+                BitOrder::LsbFirst => {
+                    let bits = (self.cur >> (8 - self.left)) as u64 & mask(k);
+                    value |= bits << shift;
+                    shift += k;
+                }
+            }
+            self.left -= k;
+            n -= k;
+        }
+
+        Ok(value)
+    }
+
+    /// Reads an unsigned Exp-Golomb code, as used for `ue(v)` fields in
+    /// H.264 and H.265.
+    ///
+    /// If an error is returned, the bits read up to the error are consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::buf::BitReader;
+    ///
+    /// // 1, 010, 011, 00100
+    /// let mut bits = BitReader::new(&b"\xa6\x40"[..]);
+    ///
+    /// assert_eq!(bits.read_exp_golomb(), Ok(0));
+    /// assert_eq!(bits.read_exp_golomb(), Ok(1));
+    /// assert_eq!(bits.read_exp_golomb(), Ok(2));
+    /// assert_eq!(bits.read_exp_golomb(), Ok(3));
+    /// ```
+    pub fn read_exp_golomb(&mut self) -> Result<u64, BitError> {
+        let code = self.read_exp_golomb_code()?;
+        if code > u64::MAX as u128 {
+            return Err(BitError::Overflow);
+        }
+        Ok(code as u64)
+    }
+
+    /// Reads a signed Exp-Golomb code, as used for `se(v)` fields in H.264
+    /// and H.265.
+    ///
+    /// If an error is returned, the bits read up to the error are consumed.
+    pub fn read_signed_exp_golomb(&mut self) -> Result<i64, BitError> {
+        let code = self.read_exp_golomb_code()? as i128;
+        let value = if code % 2 == 1 {
+            (code + 1) / 2
+        } else {
+            -(code / 2)
+        };
+        if value > i64::MAX as i128 || value < i64::MIN as i128 {
+            return Err(BitError::Overflow);
+        }
+        Ok(value as i64)
+    }
+
+    fn read_exp_golomb_code(&mut self) -> Result<u128, BitError> {
+        let mut zeros = 0;
+        while !self.read_bit()? {
+            zeros += 1;
+            if zeros > 64 {
+                return Err(BitError::Overflow);
+            }
+        }
+
+        let suffix = self.read_bits(zeros)? as u128;
+        let suffix = match self.order {
+            BitOrder::MsbFirst => suffix,
+            // The suffix was written starting with its most significant bit.
+            BitOrder::LsbFirst => suffix.reverse_bits().checked_shr(128 - zeros).unwrap_or(0),
+        };
+        Ok(((1 << zeros) | suffix) - 1)
+    }
+}
+
+/// Writes values of arbitrary bit width to a [`BufMut`].
+///
+/// Bits are collected into a byte which is written to the underlying buffer
+/// once it is full. Call [`flush`](BitWriter::flush) to pad and write a
+/// partial final byte. Writes that would exceed the capacity of the buffer
+/// return [`BitError::Truncated`] instead of panicking.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::buf::BitWriter;
+///
+/// let mut bits = BitWriter::new(vec![]);
+/// bits.write_bit(false).unwrap();
+/// bits.write_bits(3, 2).unwrap();
+/// bits.write_bits(7, 5).unwrap();
+/// bits.write_bits(1, 1).unwrap();
+///
+/// assert_eq!(bits.into_inner(), b"\x67\x80");
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitWriter<B> {
+    inner: B,
+    order: BitOrder,
+    // The byte being filled and the number of bits already in it.
+    cur: u8,
+    filled: u32,
+}
+
+impl<B: BufMut> BitWriter<B> {
+    /// Creates a writer that writes the most significant bit first.
+    pub fn new(inner: B) -> BitWriter<B> {
+        BitWriter::with_order(inner, BitOrder::MsbFirst)
+    }
+
+    /// Creates a writer that writes bits in the given order.
+    pub fn with_order(inner: B, order: BitOrder) -> BitWriter<B> {
+        BitWriter {
+            inner,
+            order,
+            cur: 0,
+            filled: 0,
+        }
+    }
+
+    /// Returns the bit order of this writer.
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Gets a reference to the underlying buffer.
+    ///
+    /// The byte currently being filled has not been written to it yet.
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying buffer.
+    ///
+    /// The byte currently being filled has not been written to it yet.
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Flushes the byte currently being filled and returns the underlying
+    /// buffer.
+    pub fn into_inner(mut self) -> B {
+        self.flush();
+        self.inner
+    }
+
+    /// Returns the number of bits that can still be written.
+    pub fn remaining_bits(&self) -> u64 {
+        // The byte being filled already has its place in the buffer.
+        (self.inner.remaining_mut() as u64)
+            .saturating_mul(8)
+            .saturating_sub(self.filled as u64)
+    }
+
+    /// Returns `true` if the next bit written starts a byte.
+    pub fn is_aligned(&self) -> bool {
+        self.filled == 0
+    }
+
+    /// Pads the byte currently being filled with zero bits and writes it to
+    /// the underlying buffer.
+    ///
+    /// Does nothing if the writer is aligned to a byte.
+    pub fn flush(&mut self) {
+        if self.filled > 0 {
+            self.inner.put_u8(self.cur);
+            self.cur = 0;
+            self.filled = 0;
+        }
+    }
+
+    /// Writes a single bit.
+    pub fn write_bit(&mut self, bit: bool) -> Result<(), BitError> {
+        self.write_bits(bit as u64, 1)
+    }
+
+    /// Writes the low `n` bits of `value`.
+    ///
+    /// Nothing is written if there is room for fewer than `n` bits.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `n` is greater than 64.
+    pub fn write_bits(&mut self, value: u64, n: u32) -> Result<(), BitError> {
+        assert!(n <= 64, "cannot write more than 64 bits at once");
+        self.check(n as u64)?;
+        self.put_bits(value, n);
+        Ok(())
+    }
+
+    /// Writes an unsigned Exp-Golomb code, as used for `ue(v)` fields in
+    /// H.264 and H.265.
+    ///
+    /// Nothing is written if there is not enough room for the whole code.
+    pub fn write_exp_golomb(&mut self, value: u64) -> Result<(), BitError> {
+        self.write_exp_golomb_code(value as u128)
+    }
+
+    /// Writes a signed Exp-Golomb code, as used for `se(v)` fields in H.264
+    /// and H.265.
+    ///
+    /// Nothing is written if there is not enough room for the whole code.
+    pub fn write_signed_exp_golomb(&mut self, value: i64) -> Result<(), BitError> {
+        let value = value as i128;
+        let code = if value > 0 { 2 * value - 1 } else { -2 * value };
+        self.write_exp_golomb_code(code as u128)
+    }
+
+    fn write_exp_golomb_code(&mut self, code: u128) -> Result<(), BitError> {
+        let code = code + 1;
+        let zeros = 127 - code.leading_zeros();
+        self.check(2 * zeros as u64 + 1)?;
+
+        self.put_bits(0, zeros);
+        // The code is written starting with its most significant bit, and
+        // may be up to 65 bits long.
+        let len = zeros + 1;
+        let (high, low) = if len > 64 { (len - 64, 64) } else { (0, len) };
+        match self.order {
+            BitOrder::MsbFirst => {
+                self.put_bits((code >> low) as u64, high);
+                self.put_bits(code as u64, low);
+            }
+            BitOrder::LsbFirst => {
+                let reversed = code.reverse_bits() >> (128 - len);
+                self.put_bits(reversed as u64, low);
+                self.put_bits((reversed >> low) as u64, high);
+            }
+        }
+        Ok(())
+    }
+
+    fn check(&self, n: u64) -> Result<(), BitError> {
+        let available = self.remaining_bits();
+        if n > available {
+            return Err(BitError::Truncated {
+                requested: n,
+                available,
+            });
+        }
+        Ok(())
+    }
+
+    fn put_bits(&mut self, value: u64, n: u32) {
+        let mut n = n;
+        let mut value = value & mask(n);
+        while n > 0 {
+            let k = cmp::min(n, 8 - self.filled);
+            match self.order {
+                BitOrder::MsbFirst => {
+                    let bits = (value >> (n - k)) & mask(k);
+                    self.cur |= (bits << (8 - self.filled - k)) as u8;
+                }
+                BitOrder::LsbFirst => {
+                    let bits = value & mask(k);
+                    self.cur |= (bits << self.filled) as u8;
+                    value >>= k;
+                }
+            }
+            self.filled += k;
+            n -= k;
+
+            if self.filled == 8 {
+                self.inner.put_u8(self.cur);
+                self.cur = 0;
+                self.filled = 0;
+            }
+        }
+    }
+}
@@ -25,9 +25,9 @@
 // SOFTWARE.
 #[cfg(feature = "std")]
 use crate::buf::{reader, Reader};
-use crate::buf::{take, Chain, Take};
+use crate::buf::{checksummed, take, tracked, Chain, Checksummed, Take, Tracked};
 
-use core::{cmp, mem, ptr};
+use core::{cmp, fmt, mem, ptr};
 
 #[cfg(feature = "std")]
 use std::io::IoSlice;
@@ -74,6 +74,75 @@ macro_rules! buf_get_impl {
     }};
 }
 
+macro_rules! buf_try_get_impl {
+    ($this:ident, $typ:tt::$conv:tt) => {{
+        const SIZE: usize = mem::size_of::<$typ>();
+        // same trick as in `buf_get_impl`
+        let ret = $this
+            .chunk()
+            .get(..SIZE)
+            .map(|src| unsafe { $typ::$conv(*(src as *const _ as *const [_; SIZE])) });
+
+        if let Some(ret) = ret {
+            $this.advance(SIZE);
+            return Ok(ret);
+        } else {
+            let mut buf = [0; SIZE];
+            $this.try_copy_to_slice(&mut buf)?;
+            return Ok($typ::$conv(buf));
+        }
+    }};
+    (le => $this:ident, $typ:tt, $len_to_read:expr) => {{
+        debug_assert!(mem::size_of::<$typ>() >= $len_to_read);
+
+        let mut buf = [0; (mem::size_of::<$typ>())];
+        $this.try_copy_to_slice(&mut buf[..($len_to_read)])?;
+        return Ok($typ::from_le_bytes(buf));
+    }};
+    (be => $this:ident, $typ:tt, $len_to_read:expr) => {{
+        debug_assert!(mem::size_of::<$typ>() >= $len_to_read);
+
+        let mut buf = [0; (mem::size_of::<$typ>())];
+        $this.try_copy_to_slice(&mut buf[mem::size_of::<$typ>() - ($len_to_read)..])?;
+        return Ok($typ::from_be_bytes(buf));
+    }};
+}
+
+/// The error returned by the `try_get_*` methods of [`Buf`] when there are
+/// not enough bytes remaining.
+///
+/// Nothing is consumed from the buffer when this error is returned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TryGetError {
+    /// The number of bytes the read needed.
+    pub requested: usize,
+    /// The number of bytes that were remaining.
+    pub available: usize,
+    /// The offset at which the read started, if the buffer tracks its
+    /// position.
+    ///
+    /// Set when reading through a [`Tracked`](crate::buf::Tracked) buffer.
+    pub position: Option<usize>,
+}
+
+impl fmt::Display for TryGetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "not enough bytes remaining in buffer to read value (requested {} but only {} available)",
+            self.requested, self.available
+        )?;
+        if let Some(position) = self.position {
+            write!(f, " at offset {}", position)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryGetError {}
+
 /// Read bytes from a buffer.
 ///
 /// A buffer stores bytes in memory such that read operations are infallible.
@@ -823,6 +892,673 @@ pub trait Buf {
         f64::from_bits(Self::get_u64_le(self))
     }
 
+    /// Copies bytes from `self` into `dst`, or returns an error if there are
+    /// not enough remaining bytes to fill it.
+    ///
+    /// On success the cursor is advanced by the number of bytes copied. On
+    /// error nothing is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"hello world"[..];
+    /// let mut dst = [0; 5];
+    ///
+    /// assert_eq!(Ok(()), buf.try_copy_to_slice(&mut dst));
+    /// assert_eq!(&b"hello"[..], &dst);
+    ///
+    /// let mut dst = [0; 7];
+    /// let err = buf.try_copy_to_slice(&mut dst).unwrap_err();
+    /// assert_eq!((err.requested, err.available), (7, 6));
+    /// assert_eq!(6, buf.remaining());
+    /// ```
+    fn try_copy_to_slice(&mut self, dst: &mut [u8]) -> Result<(), TryGetError> {
+        if self.remaining() < dst.len() {
+            return Err(TryGetError {
+                requested: dst.len(),
+                available: self.remaining(),
+                position: None,
+            });
+        }
+
+        self.copy_to_slice(dst);
+        Ok(())
+    }
+
+    /// Gets an unsigned 8 bit integer from `self`, or returns an error if there
+    /// is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 1. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x08 hello"[..];
+    /// assert_eq!(Ok(8), buf.try_get_u8());
+    ///
+    /// let mut buf = &b""[..];
+    /// assert!(buf.try_get_u8().is_err());
+    /// ```
+    fn try_get_u8(&mut self) -> Result<u8, TryGetError> {
+        if let Some(&ret) = self.chunk().first() {
+            self.advance(1);
+            return Ok(ret);
+        }
+        let mut buf = [0; 1];
+        self.try_copy_to_slice(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Gets a signed 8 bit integer from `self`, or returns an error if there is
+    /// not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 1. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x08 hello"[..];
+    /// assert_eq!(Ok(8), buf.try_get_i8());
+    ///
+    /// let mut buf = &b""[..];
+    /// assert!(buf.try_get_i8().is_err());
+    /// ```
+    fn try_get_i8(&mut self) -> Result<i8, TryGetError> {
+        if let Some(&ret) = self.chunk().first() {
+            self.advance(1);
+            return Ok(ret as i8);
+        }
+        let mut buf = [0; 1];
+        self.try_copy_to_slice(&mut buf)?;
+        Ok(buf[0] as i8)
+    }
+
+    /// Gets an unsigned 16 bit integer from `self` in big-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 2. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x08\x09 hello"[..];
+    /// assert_eq!(Ok(0x0809), buf.try_get_u16());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_u16().is_err());
+    /// ```
+    fn try_get_u16(&mut self) -> Result<u16, TryGetError> {
+        buf_try_get_impl!(self, u16::from_be_bytes);
+    }
+
+    /// Gets an unsigned 16 bit integer from `self` in little-endian byte order,
+    /// or returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 2. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x09\x08 hello"[..];
+    /// assert_eq!(Ok(0x0809), buf.try_get_u16_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_u16_le().is_err());
+    /// ```
+    fn try_get_u16_le(&mut self) -> Result<u16, TryGetError> {
+        buf_try_get_impl!(self, u16::from_le_bytes);
+    }
+
+    /// Gets a signed 16 bit integer from `self` in big-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 2. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x08\x09 hello"[..];
+    /// assert_eq!(Ok(0x0809), buf.try_get_i16());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_i16().is_err());
+    /// ```
+    fn try_get_i16(&mut self) -> Result<i16, TryGetError> {
+        buf_try_get_impl!(self, i16::from_be_bytes);
+    }
+
+    /// Gets a signed 16 bit integer from `self` in little-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 2. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x09\x08 hello"[..];
+    /// assert_eq!(Ok(0x0809), buf.try_get_i16_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_i16_le().is_err());
+    /// ```
+    fn try_get_i16_le(&mut self) -> Result<i16, TryGetError> {
+        buf_try_get_impl!(self, i16::from_le_bytes);
+    }
+
+    /// Gets an unsigned 32 bit integer from `self` in the big-endian byte
+    /// order, or returns an error if there is not enough remaining data in
+    /// `self`.
+    ///
+    /// On success the current position is advanced by 4. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x08\x09\xA0\xA1 hello"[..];
+    /// assert_eq!(Ok(0x0809A0A1), buf.try_get_u32());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_u32().is_err());
+    /// ```
+    fn try_get_u32(&mut self) -> Result<u32, TryGetError> {
+        buf_try_get_impl!(self, u32::from_be_bytes);
+    }
+
+    /// Gets an unsigned 32 bit integer from `self` in the little-endian byte
+    /// order, or returns an error if there is not enough remaining data in
+    /// `self`.
+    ///
+    /// On success the current position is advanced by 4. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\xA1\xA0\x09\x08 hello"[..];
+    /// assert_eq!(Ok(0x0809A0A1), buf.try_get_u32_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_u32_le().is_err());
+    /// ```
+    fn try_get_u32_le(&mut self) -> Result<u32, TryGetError> {
+        buf_try_get_impl!(self, u32::from_le_bytes);
+    }
+
+    /// Gets a signed 32 bit integer from `self` in big-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 4. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x08\x09\xA0\xA1 hello"[..];
+    /// assert_eq!(Ok(0x0809A0A1), buf.try_get_i32());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_i32().is_err());
+    /// ```
+    fn try_get_i32(&mut self) -> Result<i32, TryGetError> {
+        buf_try_get_impl!(self, i32::from_be_bytes);
+    }
+
+    /// Gets a signed 32 bit integer from `self` in little-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 4. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\xA1\xA0\x09\x08 hello"[..];
+    /// assert_eq!(Ok(0x0809A0A1), buf.try_get_i32_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_i32_le().is_err());
+    /// ```
+    fn try_get_i32_le(&mut self) -> Result<i32, TryGetError> {
+        buf_try_get_impl!(self, i32::from_le_bytes);
+    }
+
+    /// Gets an unsigned 64 bit integer from `self` in big-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 8. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08 hello"[..];
+    /// assert_eq!(Ok(0x0102030405060708), buf.try_get_u64());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_u64().is_err());
+    /// ```
+    fn try_get_u64(&mut self) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(self, u64::from_be_bytes);
+    }
+
+    /// Gets an unsigned 64 bit integer from `self` in little-endian byte order,
+    /// or returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 8. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x08\x07\x06\x05\x04\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x0102030405060708), buf.try_get_u64_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_u64_le().is_err());
+    /// ```
+    fn try_get_u64_le(&mut self) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(self, u64::from_le_bytes);
+    }
+
+    /// Gets a signed 64 bit integer from `self` in big-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 8. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08 hello"[..];
+    /// assert_eq!(Ok(0x0102030405060708), buf.try_get_i64());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_i64().is_err());
+    /// ```
+    fn try_get_i64(&mut self) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(self, i64::from_be_bytes);
+    }
+
+    /// Gets a signed 64 bit integer from `self` in little-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 8. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x08\x07\x06\x05\x04\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x0102030405060708), buf.try_get_i64_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_i64_le().is_err());
+    /// ```
+    fn try_get_i64_le(&mut self) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(self, i64::from_le_bytes);
+    }
+
+    /// Gets an unsigned 128 bit integer from `self` in big-endian byte order,
+    /// or returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 16. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08\x09\x10\x11\x12\x13\x14\x15\x16 hello"[..];
+    /// assert_eq!(Ok(0x01020304050607080910111213141516), buf.try_get_u128());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_u128().is_err());
+    /// ```
+    fn try_get_u128(&mut self) -> Result<u128, TryGetError> {
+        buf_try_get_impl!(self, u128::from_be_bytes);
+    }
+
+    /// Gets an unsigned 128 bit integer from `self` in little-endian byte
+    /// order, or returns an error if there is not enough remaining data in
+    /// `self`.
+    ///
+    /// On success the current position is advanced by 16. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x16\x15\x14\x13\x12\x11\x10\x09\x08\x07\x06\x05\x04\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x01020304050607080910111213141516), buf.try_get_u128_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_u128_le().is_err());
+    /// ```
+    fn try_get_u128_le(&mut self) -> Result<u128, TryGetError> {
+        buf_try_get_impl!(self, u128::from_le_bytes);
+    }
+
+    /// Gets a signed 128 bit integer from `self` in big-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 16. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08\x09\x10\x11\x12\x13\x14\x15\x16 hello"[..];
+    /// assert_eq!(Ok(0x01020304050607080910111213141516), buf.try_get_i128());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_i128().is_err());
+    /// ```
+    fn try_get_i128(&mut self) -> Result<i128, TryGetError> {
+        buf_try_get_impl!(self, i128::from_be_bytes);
+    }
+
+    /// Gets a signed 128 bit integer from `self` in little-endian byte order,
+    /// or returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 16. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x16\x15\x14\x13\x12\x11\x10\x09\x08\x07\x06\x05\x04\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x01020304050607080910111213141516), buf.try_get_i128_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_i128_le().is_err());
+    /// ```
+    fn try_get_i128_le(&mut self) -> Result<i128, TryGetError> {
+        buf_try_get_impl!(self, i128::from_le_bytes);
+    }
+
+    /// Gets an unsigned n-byte integer from `self` in big-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by `nbytes`. On error
+    /// nothing is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x01\x02\x03 hello"[..];
+    /// assert_eq!(Ok(0x010203), buf.try_get_uint(3));
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_uint(3).is_err());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nbytes` is greater than 8.
+    fn try_get_uint(&mut self, nbytes: usize) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(be => self, u64, nbytes);
+    }
+
+    /// Gets an unsigned n-byte integer from `self` in little-endian byte order,
+    /// or returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by `nbytes`. On error
+    /// nothing is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x010203), buf.try_get_uint_le(3));
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_uint_le(3).is_err());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nbytes` is greater than 8.
+    fn try_get_uint_le(&mut self, nbytes: usize) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(le => self, u64, nbytes);
+    }
+
+    /// Gets a signed n-byte integer from `self` in big-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by `nbytes`. On error
+    /// nothing is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x01\x02\x03 hello"[..];
+    /// assert_eq!(Ok(0x010203), buf.try_get_int(3));
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_int(3).is_err());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nbytes` is greater than 8.
+    fn try_get_int(&mut self, nbytes: usize) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(be => self, i64, nbytes);
+    }
+
+    /// Gets a signed n-byte integer from `self` in little-endian byte order, or
+    /// returns an error if there is not enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by `nbytes`. On error
+    /// nothing is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x010203), buf.try_get_int_le(3));
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_int_le(3).is_err());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nbytes` is greater than 8.
+    fn try_get_int_le(&mut self, nbytes: usize) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(le => self, i64, nbytes);
+    }
+
+    /// Gets an IEEE754 single-precision (4 bytes) floating point number from
+    /// `self` in big-endian byte order, or returns an error if there is not
+    /// enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 4. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x3F\x99\x99\x9A hello"[..];
+    /// assert_eq!(Ok(1.2f32), buf.try_get_f32());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_f32().is_err());
+    /// ```
+    fn try_get_f32(&mut self) -> Result<f32, TryGetError> {
+        Ok(f32::from_bits(Self::try_get_u32(self)?))
+    }
+
+    /// Gets an IEEE754 single-precision (4 bytes) floating point number from
+    /// `self` in little-endian byte order, or returns an error if there is not
+    /// enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 4. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x9A\x99\x99\x3F hello"[..];
+    /// assert_eq!(Ok(1.2f32), buf.try_get_f32_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_f32_le().is_err());
+    /// ```
+    fn try_get_f32_le(&mut self) -> Result<f32, TryGetError> {
+        Ok(f32::from_bits(Self::try_get_u32_le(self)?))
+    }
+
+    /// Gets an IEEE754 double-precision (8 bytes) floating point number from
+    /// `self` in big-endian byte order, or returns an error if there is not
+    /// enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 8. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x3F\xF3\x33\x33\x33\x33\x33\x33 hello"[..];
+    /// assert_eq!(Ok(1.2f64), buf.try_get_f64());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_f64().is_err());
+    /// ```
+    fn try_get_f64(&mut self) -> Result<f64, TryGetError> {
+        Ok(f64::from_bits(Self::try_get_u64(self)?))
+    }
+
+    /// Gets an IEEE754 double-precision (8 bytes) floating point number from
+    /// `self` in little-endian byte order, or returns an error if there is not
+    /// enough remaining data in `self`.
+    ///
+    /// On success the current position is advanced by 8. On error nothing
+    /// is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &b"\x33\x33\x33\x33\x33\x33\xF3\x3F hello"[..];
+    /// assert_eq!(Ok(1.2f64), buf.try_get_f64_le());
+    ///
+    /// let mut buf = &b"\x01"[..];
+    /// assert!(buf.try_get_f64_le().is_err());
+    /// ```
+    fn try_get_f64_le(&mut self) -> Result<f64, TryGetError> {
+        Ok(f64::from_bits(Self::try_get_u64_le(self)?))
+    }
+
+    /// Gets a plain-old-data value from `self`, in its native in-memory
+    /// layout.
+    ///
+    /// The current position is advanced by `size_of::<T>()`. The bytes do
+    /// not need to be aligned for `T`.
+    ///
+    /// This is only available with the `bytemuck` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = &[1, 0, 0, 0, 2, 0, 0, 0, 3][..];
+    /// let pair: [u32; 2] = buf.get_pod();
+    ///
+    /// assert_eq!(pair.map(u32::from_le), [1, 2]);
+    /// assert_eq!(buf, [3]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining data in `self`.
+    #[cfg(feature = "bytemuck")]
+    fn get_pod<T: bytemuck::Pod>(&mut self) -> T
+    where
+        Self: Sized,
+    {
+        let mut value = T::zeroed();
+        self.copy_to_slice(bytemuck::bytes_of_mut(&mut value));
+        value
+    }
+
+    /// Gets a plain-old-data value from `self`, in its native in-memory
+    /// layout, or returns an error if there is not enough remaining data in
+    /// `self`.
+    ///
+    /// On success the current position is advanced by `size_of::<T>()`. On
+    /// error nothing is consumed.
+    ///
+    /// This is only available with the `bytemuck` feature enabled.
+    #[cfg(feature = "bytemuck")]
+    fn try_get_pod<T: bytemuck::Pod>(&mut self) -> Result<T, TryGetError>
+    where
+        Self: Sized,
+    {
+        let mut value = T::zeroed();
+        self.try_copy_to_slice(bytemuck::bytes_of_mut(&mut value))?;
+        Ok(value)
+    }
+
     /// Consumes `len` bytes inside self and returns new instance of `Bytes`
     /// with this data.
     ///
@@ -898,6 +1634,64 @@ pub trait Buf {
         Chain::new(self, next)
     }
 
+    /// Creates an adaptor which feeds every byte consumed from `self` into
+    /// `hasher`.
+    ///
+    /// Bytes are hashed when they are consumed through `advance`,
+    /// `copy_to_slice`, `copy_to_bytes` or the `get_*` methods. See
+    /// [`checksum`](crate::checksum) for the built-in checksums; any
+    /// [`Hasher`](core::hash::Hasher) is accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    /// use rcbytes::checksum::Crc32c;
+    ///
+    /// let mut buf = b"\x00\x09123456789"[..].inspect_with(Crc32c::new());
+    ///
+    /// let len = buf.get_u16() as usize;
+    /// buf.hasher_mut().reset();
+    /// let payload = buf.copy_to_bytes(len);
+    ///
+    /// assert_eq!(payload, &b"123456789"[..]);
+    /// assert_eq!(buf.hasher().sum(), 0xe306_9283);
+    /// ```
+    fn inspect_with<H: core::hash::Hasher>(self, hasher: H) -> Checksummed<Self, H>
+    where
+        Self: Sized,
+    {
+        checksummed::new(self, hasher)
+    }
+
+    /// Creates an adaptor which counts the bytes consumed from `self`.
+    ///
+    /// The count works across adapters such as [`Chain`] and [`Take`], and
+    /// errors returned by the `try_get_*` methods of the adaptor carry the
+    /// offset at which the failed read started. See [`Tracked`] for
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    ///
+    /// let mut buf = b"hello "[..].chain(&b"world"[..]).tracked();
+    ///
+    /// buf.advance(8);
+    /// assert_eq!(buf.position(), 8);
+    ///
+    /// let err = buf.try_get_u32().unwrap_err();
+    /// assert_eq!(err.position, Some(8));
+    /// assert_eq!(err.available, 3);
+    /// ```
+    fn tracked(self) -> Tracked<Self>
+    where
+        Self: Sized,
+    {
+        tracked::new(self)
+    }
+
     /// Creates an adaptor which implements the `Read` trait for `self`.
     ///
     /// This function returns a new value which implements `Read` by adapting
@@ -1029,6 +1823,82 @@ macro_rules! deref_forward_buf {
             (**self).get_int_le(nbytes)
         }
 
+        fn try_copy_to_slice(&mut self, dst: &mut [u8]) -> Result<(), TryGetError> {
+            (**self).try_copy_to_slice(dst)
+        }
+
+        fn try_get_u8(&mut self) -> Result<u8, TryGetError> {
+            (**self).try_get_u8()
+        }
+
+        fn try_get_i8(&mut self) -> Result<i8, TryGetError> {
+            (**self).try_get_i8()
+        }
+
+        fn try_get_u16(&mut self) -> Result<u16, TryGetError> {
+            (**self).try_get_u16()
+        }
+
+        fn try_get_u16_le(&mut self) -> Result<u16, TryGetError> {
+            (**self).try_get_u16_le()
+        }
+
+        fn try_get_i16(&mut self) -> Result<i16, TryGetError> {
+            (**self).try_get_i16()
+        }
+
+        fn try_get_i16_le(&mut self) -> Result<i16, TryGetError> {
+            (**self).try_get_i16_le()
+        }
+
+        fn try_get_u32(&mut self) -> Result<u32, TryGetError> {
+            (**self).try_get_u32()
+        }
+
+        fn try_get_u32_le(&mut self) -> Result<u32, TryGetError> {
+            (**self).try_get_u32_le()
+        }
+
+        fn try_get_i32(&mut self) -> Result<i32, TryGetError> {
+            (**self).try_get_i32()
+        }
+
+        fn try_get_i32_le(&mut self) -> Result<i32, TryGetError> {
+            (**self).try_get_i32_le()
+        }
+
+        fn try_get_u64(&mut self) -> Result<u64, TryGetError> {
+            (**self).try_get_u64()
+        }
+
+        fn try_get_u64_le(&mut self) -> Result<u64, TryGetError> {
+            (**self).try_get_u64_le()
+        }
+
+        fn try_get_i64(&mut self) -> Result<i64, TryGetError> {
+            (**self).try_get_i64()
+        }
+
+        fn try_get_i64_le(&mut self) -> Result<i64, TryGetError> {
+            (**self).try_get_i64_le()
+        }
+
+        fn try_get_uint(&mut self, nbytes: usize) -> Result<u64, TryGetError> {
+            (**self).try_get_uint(nbytes)
+        }
+
+        fn try_get_uint_le(&mut self, nbytes: usize) -> Result<u64, TryGetError> {
+            (**self).try_get_uint_le(nbytes)
+        }
+
+        fn try_get_int(&mut self, nbytes: usize) -> Result<i64, TryGetError> {
+            (**self).try_get_int(nbytes)
+        }
+
+        fn try_get_int_le(&mut self, nbytes: usize) -> Result<i64, TryGetError> {
+            (**self).try_get_int_le(nbytes)
+        }
+
         fn copy_to_bytes(&mut self, len: usize) -> crate::Bytes {
             (**self).copy_to_bytes(len)
         }
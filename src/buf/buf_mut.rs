@@ -23,11 +23,13 @@
 // LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
-use crate::buf::{limit, Chain, Limit, UninitSlice};
+use crate::buf::{checksummed, limit, tracked, Chain, Checksummed, Limit, Tracked, UninitSlice};
 #[cfg(feature = "std")]
 use crate::buf::{writer, Writer};
 
 use core::{cmp, mem, ptr, usize};
+#[cfg(feature = "std")]
+use std::io::IoSliceMut;
 
 use alloc::{boxed::Box, vec::Vec};
 
@@ -199,6 +201,69 @@ pub unsafe trait BufMut {
     #[cfg_attr(docsrs, doc(alias = "bytes_mut"))]
     fn chunk_mut(&mut self) -> &mut UninitSlice;
 
+    /// Fills `dst` with potentially multiple mutable slices starting at
+    /// `self`'s current position, and returns how many entries were written.
+    ///
+    /// If the `BufMut` is backed by disjoint slices of memory,
+    /// `chunks_vectored_mut` hands out more than one of them at once. `dst` is
+    /// a slice of `IoSliceMut`, so it can be passed directly to [`readv`] or
+    /// [`Read::read_vectored`]. The sum of the lengths of all the slices will
+    /// be less than or equal to `BufMut::remaining_mut()`.
+    ///
+    /// The slices come in write order. After filling the first `n` bytes of
+    /// them, as `readv` does, calling `advance_mut(n)` commits exactly those
+    /// bytes, even if `n` stops in the middle of a slice.
+    ///
+    /// `IoSliceMut` must refer to initialized memory, so implementations zero
+    /// any spare capacity that may be uninitialized before handing it out.
+    ///
+    /// The default implementation hands out `chunk_mut()` as a single slice,
+    /// zeroing it on every call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BufMut;
+    /// use std::io::{IoSliceMut, Read};
+    ///
+    /// let mut header = [0; 4];
+    /// let mut body = Vec::with_capacity(64);
+    ///
+    /// let mut buf = (&mut header[..]).chain_mut(&mut body);
+    /// let mut src = &b"HEADbody bytes"[..];
+    ///
+    /// let n = {
+    ///     let mut slices = [IoSliceMut::new(&mut []), IoSliceMut::new(&mut [])];
+    ///     let cnt = buf.chunks_vectored_mut(&mut slices);
+    ///     src.read_vectored(&mut slices[..cnt]).unwrap()
+    /// };
+    /// unsafe { buf.advance_mut(n) };
+    ///
+    /// assert_eq!(&header, b"HEAD");
+    /// assert_eq!(body, b"body bytes");
+    /// ```
+    ///
+    /// # Implementer notes
+    ///
+    /// This function should never panic. Once the end of the buffer is
+    /// reached, i.e., `BufMut::remaining_mut` returns 0, calls to
+    /// `chunks_vectored_mut` must return 0 without mutating `dst`.
+    ///
+    /// Implementations should also take care to properly handle being called
+    /// with `dst` being a zero length slice.
+    ///
+    /// [`readv`]: http://man7.org/linux/man-pages/man2/readv.2.html
+    /// [`Read::read_vectored`]: std::io::Read::read_vectored
+    #[cfg(feature = "std")]
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
+        if dst.is_empty() || !self.has_remaining_mut() {
+            return 0;
+        }
+
+        dst[0] = IoSliceMut::new(self.chunk_mut().zeroed());
+        1
+    }
+
     /// Transfer bytes into `self` from `src` and advance the cursor by the
     /// number of bytes written.
     ///
@@ -321,6 +386,75 @@ pub unsafe trait BufMut {
         }
     }
 
+    /// Writes up to `max_len` bytes to `self` in place.
+    ///
+    /// `f` is handed `max_len` contiguous bytes to write into, and returns how
+    /// many bytes it wrote, counted from the start. `self` is then advanced by
+    /// that many bytes, and the count is returned. This lets encoders that
+    /// write into a slice skip an intermediate buffer without resorting to
+    /// `chunk_mut` and the unsafe `advance_mut`.
+    ///
+    /// The bytes handed to `f` are zeroed first, so reporting more bytes than
+    /// were actually written can't expose uninitialized memory.
+    ///
+    /// If `chunk_mut` is shorter than `max_len`, `f` writes into a temporary
+    /// buffer on the heap instead, which is then copied into `self`. `Vec<u8>`,
+    /// `BytesMut`, `&mut [u8]` and `ArrayBuf` always write in place. The
+    /// temporary buffer is used when the free space is split: a `RingBuf`
+    /// whose free space wraps around, a `Chain` whose first buffer has less
+    /// than `max_len` left, or a `SegmentedBuf` whose current segment does.
+    /// It is also used through `&mut T` and `Box<T>`, which can't forward to
+    /// `T`'s own implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BufMut;
+    ///
+    /// let mut buf = vec![];
+    /// let n = buf.put_with(8, |dst| {
+    ///     dst[..5].copy_from_slice(b"hello");
+    ///     5
+    /// });
+    ///
+    /// assert_eq!(n, 5);
+    /// assert_eq!(buf, b"hello");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is less than `max_len` remaining
+    /// capacity in `self`, or if `f` returns more than `max_len`.
+    fn put_with<F>(&mut self, max_len: usize, f: F) -> usize
+    where
+        F: FnOnce(&mut UninitSlice) -> usize,
+        Self: Sized,
+    {
+        assert!(
+            self.remaining_mut() >= max_len,
+            "buffer overflow; remaining = {}; max_len = {}",
+            self.remaining_mut(),
+            max_len
+        );
+
+        let chunk = self.chunk_mut();
+        if chunk.len() >= max_len {
+            let dst = &mut chunk[..max_len];
+            dst.zeroed();
+            let n = f(dst);
+            assert_written_len(n, max_len);
+            // Safety: the first `max_len` bytes of the chunk are initialized.
+            unsafe { self.advance_mut(n) };
+            n
+        } else {
+            let mut scratch = alloc::vec![0; max_len];
+            let n = f(UninitSlice::from_slice(&mut scratch));
+            assert_written_len(n, max_len);
+            self.put_slice(&scratch[..n]);
+            n
+        }
+    }
+
     /// Writes an unsigned 8 bit integer to `self`.
     ///
     /// The current position is advanced by 1.
@@ -899,6 +1033,37 @@ pub unsafe trait BufMut {
         self.put_u64_le(n.to_bits());
     }
 
+    /// Writes a plain-old-data value to `self`, in its native in-memory
+    /// layout.
+    ///
+    /// The current position is advanced by `size_of::<T>()`.
+    ///
+    /// This is only available with the `bytemuck` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BufMut;
+    ///
+    /// let mut buf = vec![];
+    /// buf.put_pod(&[1u16, 2]);
+    ///
+    /// assert_eq!(buf.len(), 4);
+    /// assert_eq!(buf[..2], 1u16.to_ne_bytes());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `self`.
+    #[cfg(feature = "bytemuck")]
+    fn put_pod<T: bytemuck::Pod>(&mut self, value: &T)
+    where
+        Self: Sized,
+    {
+        self.put_slice(bytemuck::bytes_of(value));
+    }
+
     /// Creates an adaptor which can write at most `limit` bytes to `self`.
     ///
     /// # Examples
@@ -919,6 +1084,59 @@ pub unsafe trait BufMut {
         limit::new(self, limit)
     }
 
+    /// Creates an adaptor which feeds every byte written to `self` into
+    /// `hasher`.
+    ///
+    /// See [`checksum`](crate::checksum) for the built-in checksums; any
+    /// [`Hasher`](core::hash::Hasher) is accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BufMut;
+    /// use rcbytes::checksum::Crc32;
+    ///
+    /// let mut buf = vec![].checksummed(Crc32::new());
+    /// buf.put_slice(b"1234");
+    /// buf.put(&b"56789"[..]);
+    ///
+    /// let crc = buf.hasher().sum();
+    /// let mut frame = buf.into_inner();
+    /// frame.put_u32(crc);
+    ///
+    /// assert_eq!(frame, b"123456789\xcb\xf4\x39\x26");
+    /// ```
+    fn checksummed<H: core::hash::Hasher>(self, hasher: H) -> Checksummed<Self, H>
+    where
+        Self: Sized,
+    {
+        checksummed::new(self, hasher)
+    }
+
+    /// Creates an adaptor which counts the bytes written to `self`.
+    ///
+    /// See [`Tracked`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BufMut;
+    ///
+    /// let mut buf = vec![].tracked_mut();
+    /// buf.put_u8(1);
+    ///
+    /// let mark = buf.mark();
+    /// buf.put_slice(b"payload");
+    /// assert_eq!(buf.since(mark), 7);
+    /// assert_eq!(buf.position(), 8);
+    /// ```
+    fn tracked_mut(self) -> Tracked<Self>
+    where
+        Self: Sized,
+    {
+        tracked::new(self)
+    }
+
     /// Creates an adaptor which implements the `Write` trait for `self`.
     ///
     /// This function returns a new value which implements `Write` by adapting
@@ -991,6 +1209,11 @@ macro_rules! deref_forward_bufmut {
             (**self).advance_mut(cnt)
         }
 
+        #[cfg(feature = "std")]
+        fn chunks_vectored_mut<'b>(&'b mut self, dst: &mut [IoSliceMut<'b>]) -> usize {
+            (**self).chunks_vectored_mut(dst)
+        }
+
         fn put_slice(&mut self, src: &[u8]) {
             (**self).put_slice(src)
         }
@@ -1080,6 +1303,17 @@ unsafe impl BufMut for &mut [u8] {
         *self = b;
     }
 
+    // The slice is initialized already, so there is nothing to zero.
+    #[cfg(feature = "std")]
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
+        if dst.is_empty() || self.is_empty() {
+            return 0;
+        }
+
+        dst[0] = IoSliceMut::new(self);
+        1
+    }
+
     #[inline]
     fn put_slice(&mut self, src: &[u8]) {
         self[..src.len()].copy_from_slice(src);
@@ -1095,6 +1329,24 @@ unsafe impl BufMut for &mut [u8] {
             self.advance_mut(cnt);
         }
     }
+
+    // The slice is initialized already, so there is nothing to zero.
+    fn put_with<F>(&mut self, max_len: usize, f: F) -> usize
+    where
+        F: FnOnce(&mut UninitSlice) -> usize,
+        Self: Sized,
+    {
+        assert!(
+            self.remaining_mut() >= max_len,
+            "buffer overflow; remaining = {}; max_len = {}",
+            self.remaining_mut(),
+            max_len
+        );
+        let n = f(UninitSlice::from_slice(&mut self[..max_len]));
+        assert_written_len(n, max_len);
+        unsafe { self.advance_mut(n) };
+        n
+    }
 }
 
 unsafe impl BufMut for Vec<u8> {
@@ -1164,6 +1416,29 @@ unsafe impl BufMut for Vec<u8> {
         let new_len = self.len().checked_add(cnt).unwrap();
         self.resize(new_len, val);
     }
+
+    fn put_with<F>(&mut self, max_len: usize, f: F) -> usize
+    where
+        F: FnOnce(&mut UninitSlice) -> usize,
+        Self: Sized,
+    {
+        let len = self.len();
+        self.resize(len.checked_add(max_len).unwrap(), 0);
+        let n = f(UninitSlice::from_slice(&mut self[len..]));
+        assert_written_len(n, max_len);
+        self.truncate(len + n);
+        n
+    }
+}
+
+#[inline]
+pub(crate) fn assert_written_len(n: usize, max_len: usize) {
+    assert!(
+        n <= max_len,
+        "closure wrote past `max_len`: {} > {}",
+        n,
+        max_len
+    );
 }
 
 // The existence of this function makes the compiler catch if the BufMut
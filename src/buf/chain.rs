@@ -25,9 +25,10 @@
 // SOFTWARE.
 use crate::buf::{IntoIter, UninitSlice};
 use crate::{Buf, BufMut, Bytes};
+use core::cmp;
 
 #[cfg(feature = "std")]
-use std::io::IoSlice;
+use std::io::{IoSlice, IoSliceMut};
 
 /// A `Chain` sequences two buffers.
 ///
@@ -53,16 +54,24 @@ use std::io::IoSlice;
 /// [`Buf::chain`]: trait.Buf.html#method.chain
 /// [`Buf`]: trait.Buf.html
 /// [`BufMut`]: trait.BufMut.html
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Chain<T, U> {
     a: T,
     b: U,
+    // How many bytes of `a` the last `chunks_vectored_mut` exposed. A
+    // growable `a` reports far more `remaining_mut` than it handed out, so
+    // `advance_mut` splits its count at this instead.
+    a_vectored: usize,
 }
 
 impl<T, U> Chain<T, U> {
     /// Creates a new `Chain` sequencing the provided values.
     pub(crate) fn new(a: T, b: U) -> Chain<T, U> {
-        Chain { a, b }
+        Chain {
+            a,
+            b,
+            a_vectored: usize::MAX,
+        }
     }
 
     /// Gets a reference to the first underlying `Buf`.
@@ -227,6 +236,7 @@ where
     }
 
     fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.a_vectored = usize::MAX;
         if self.a.has_remaining_mut() {
             self.a.chunk_mut()
         } else {
@@ -234,8 +244,17 @@ where
         }
     }
 
+    #[cfg(feature = "std")]
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
+        let mut n = self.a.chunks_vectored_mut(dst);
+        self.a_vectored = dst[..n].iter().map(|s| s.len()).sum();
+        n += self.b.chunks_vectored_mut(&mut dst[n..]);
+        n
+    }
+
     unsafe fn advance_mut(&mut self, mut cnt: usize) {
-        let a_rem = self.a.remaining_mut();
+        let a_rem = cmp::min(self.a.remaining_mut(), self.a_vectored);
+        self.a_vectored = usize::MAX;
 
         if a_rem != 0 {
             if a_rem >= cnt {
@@ -0,0 +1,185 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use crate::buf::UninitSlice;
+use crate::{Buf, BufMut, Bytes};
+
+use core::cmp;
+use core::hash::Hasher;
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+
+/// An adapter which feeds every byte passing through a buffer into a
+/// [`Hasher`].
+///
+/// As a `Buf`, bytes are hashed as they are consumed, whether through
+/// `advance`, `copy_to_slice`, `copy_to_bytes` or any of the `get_*`
+/// methods. Bytes that are only looked at through `chunk` are not hashed.
+///
+/// As a `BufMut`, bytes are hashed as they are written.
+///
+/// This struct is generally created by calling
+/// [`inspect_with()`](trait.Buf.html#method.inspect_with) on `Buf` or
+/// [`checksummed()`](trait.BufMut.html#method.checksummed) on `BufMut`.
+/// [`checksum`](crate::checksum) provides CRC-32, CRC-32C and Adler-32
+/// hashers.
+#[derive(Clone, Debug)]
+pub struct Checksummed<T, H> {
+    inner: T,
+    hasher: H,
+}
+
+pub(super) fn new<T, H>(inner: T, hasher: H) -> Checksummed<T, H> {
+    Checksummed { inner, hasher }
+}
+
+impl<T, H> Checksummed<T, H> {
+    /// Consumes this `Checksummed`, returning the underlying value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Consumes this `Checksummed`, returning the underlying value and the
+    /// hasher.
+    pub fn into_parts(self) -> (T, H) {
+        (self.inner, self.hasher)
+    }
+
+    /// Gets a reference to the underlying buffer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying buffer.
+    ///
+    /// Bytes read from or written to the underlying buffer directly are not
+    /// hashed.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Gets a reference to the hasher.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Gets a mutable reference to the hasher.
+    ///
+    /// This can be used to reset the checksum between frames.
+    pub fn hasher_mut(&mut self) -> &mut H {
+        &mut self.hasher
+    }
+}
+
+impl<T, H: Hasher> Checksummed<T, H> {
+    /// Returns the hash of the bytes seen so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Buf;
+    /// use rcbytes::checksum::Crc32;
+    ///
+    /// let mut buf = b"123456789 trailer"[..].inspect_with(Crc32::new());
+    /// buf.advance(9);
+    ///
+    /// assert_eq!(buf.finish(), 0xcbf4_3926);
+    /// assert_eq!(buf.chunk(), b" trailer");
+    /// ```
+    pub fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+}
+
+impl<T: Buf, H: Hasher> Buf for Checksummed<T, H> {
+    fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.inner.chunk()
+    }
+
+    #[cfg(feature = "std")]
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        self.inner.chunks_vectored(dst)
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        assert!(
+            cnt <= self.inner.remaining(),
+            "cannot advance past `remaining`: {:?} <= {:?}",
+            cnt,
+            self.inner.remaining(),
+        );
+
+        while cnt > 0 {
+            let chunk = self.inner.chunk();
+            let n = cmp::min(chunk.len(), cnt);
+            self.hasher.write(&chunk[..n]);
+            self.inner.advance(n);
+            cnt -= n;
+        }
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        let bytes = self.inner.copy_to_bytes(len);
+        self.hasher.write(&bytes);
+        bytes
+    }
+}
+
+unsafe impl<T: BufMut, H: Hasher> BufMut for Checksummed<T, H> {
+    fn remaining_mut(&self) -> usize {
+        self.inner.remaining_mut()
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.inner.chunk_mut()
+    }
+
+    // `chunks_vectored_mut` is left to the default so that the written bytes
+    // are always at the start of `chunk_mut` when they are committed.
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        if cnt > 0 {
+            let chunk = self.inner.chunk_mut();
+            assert!(
+                cnt <= chunk.len(),
+                "cannot advance past `chunk_mut`: {:?} <= {:?}",
+                cnt,
+                chunk.len(),
+            );
+            // Safety: the caller guarantees the first `cnt` bytes of the
+            // chunk have been initialized.
+            self.hasher
+                .write(core::slice::from_raw_parts(chunk.as_mut_ptr(), cnt));
+        }
+        self.inner.advance_mut(cnt);
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        self.inner.put_slice(src);
+        self.hasher.write(src);
+    }
+}
@@ -27,6 +27,8 @@ use crate::buf::UninitSlice;
 use crate::BufMut;
 
 use core::cmp;
+#[cfg(feature = "std")]
+use std::io::IoSliceMut;
 
 /// A `BufMut` adapter which limits the amount of bytes that can be written
 /// to an underlying buffer.
@@ -92,6 +94,30 @@ unsafe impl<T: BufMut> BufMut for Limit<T> {
         &mut bytes[..end]
     }
 
+    #[cfg(feature = "std")]
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
+        let n = self.inner.chunks_vectored_mut(dst);
+        let mut left = self.limit;
+
+        for (i, slice) in dst[..n].iter_mut().enumerate() {
+            if slice.len() <= left {
+                left -= slice.len();
+                continue;
+            }
+            if left == 0 {
+                return i;
+            }
+            // Shorten the slice that crosses the limit.
+            let ptr = slice.as_mut_ptr();
+            // Safety: `slice` borrows this memory for `'a` and is replaced by
+            // a prefix of itself.
+            *slice = IoSliceMut::new(unsafe { core::slice::from_raw_parts_mut(ptr, left) });
+            return i + 1;
+        }
+
+        n
+    }
+
     unsafe fn advance_mut(&mut self, cnt: usize) {
         assert!(cnt <= self.limit);
         self.inner.advance_mut(cnt);
@@ -41,25 +41,38 @@
 //! [`Buf`]: trait.Buf.html
 //! [`BufMut`]: trait.BufMut.html
 
+mod array_buf;
+mod bits;
 mod buf_impl;
 mod buf_mut;
 mod chain;
+mod checksummed;
 mod iter;
 mod limit;
 #[cfg(feature = "std")]
 mod reader;
+mod ring_buf;
+mod segmented;
 mod take;
+mod tracked;
 mod uninit_slice;
 mod vec_deque;
 #[cfg(feature = "std")]
 mod writer;
 
-pub use self::buf_impl::Buf;
+pub use self::array_buf::ArrayBuf;
+pub use self::bits::{BitError, BitOrder, BitReader, BitWriter};
+pub use self::buf_impl::{Buf, TryGetError};
 pub use self::buf_mut::BufMut;
+pub(crate) use self::buf_mut::assert_written_len;
 pub use self::chain::Chain;
+pub use self::checksummed::Checksummed;
 pub use self::iter::IntoIter;
 pub use self::limit::Limit;
+pub use self::ring_buf::RingBuf;
+pub use self::segmented::SegmentedBuf;
 pub use self::take::Take;
+pub use self::tracked::{Mark, Tracked};
 pub use self::uninit_slice::UninitSlice;
 
 #[cfg(feature = "std")]
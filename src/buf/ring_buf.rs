@@ -0,0 +1,303 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use crate::buf::UninitSlice;
+use crate::{Buf, BufMut, Bytes, BytesMut};
+
+use alloc::{boxed::Box, vec::Vec};
+use core::mem::MaybeUninit;
+use core::{fmt, ptr, slice};
+#[cfg(feature = "std")]
+use std::io::{IoSlice, IoSliceMut};
+
+/// A fixed-capacity circular byte buffer.
+///
+/// `RingBuf` is written through [`BufMut`] and read through [`Buf`]. Reads
+/// free space that later writes reuse, without moving any bytes, so it suits
+/// streaming parsers that consume input as it arrives.
+///
+/// Both the unread bytes and the free space may wrap around the end of the
+/// storage, in which case they are made of two chunks. [`chunks_vectored`]
+/// and [`chunks_vectored_mut`] hand out both, and [`make_contiguous`]
+/// rearranges the unread bytes into a single slice when a parser needs one.
+///
+/// [`chunks_vectored`]: Buf::chunks_vectored
+/// [`chunks_vectored_mut`]: BufMut::chunks_vectored_mut
+/// [`make_contiguous`]: RingBuf::make_contiguous
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::buf::RingBuf;
+/// use rcbytes::{Buf, BufMut};
+///
+/// let mut buf = RingBuf::with_capacity(8);
+/// buf.put_slice(b"abcdef");
+/// buf.advance(4);
+/// buf.put_slice(b"ghijkl");
+///
+/// // The unread bytes wrap around the end of the storage.
+/// assert_eq!(buf.as_slices(), (&b"efgh"[..], &b"ijkl"[..]));
+/// assert_eq!(buf.make_contiguous(), b"efghijkl");
+/// ```
+#[derive(Clone)]
+pub struct RingBuf {
+    buf: Box<[MaybeUninit<u8>]>,
+    // The `len` bytes starting at `head`, wrapping around, are initialized
+    // and unread.
+    head: usize,
+    len: usize,
+}
+
+impl RingBuf {
+    /// Creates an empty buffer that holds up to `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> RingBuf {
+        let mut buf = Vec::with_capacity(capacity);
+        // Safety: `MaybeUninit` needs no initialization.
+        unsafe { buf.set_len(capacity) };
+        RingBuf {
+            buf: buf.into_boxed_slice(),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of bytes the buffer can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the number of unread bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no unread bytes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if there is no room for more bytes.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Discards all unread bytes.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Maps a position in `0..2 * capacity` back into the storage.
+    #[inline]
+    fn wrap(&self, pos: usize) -> usize {
+        if pos >= self.capacity() {
+            pos - self.capacity()
+        } else {
+            pos
+        }
+    }
+
+    /// Returns the unread bytes as two slices, in order. The second slice is
+    /// empty unless the bytes wrap around the end of the storage.
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        let first = self.len.min(self.capacity() - self.head);
+        let ptr = self.buf.as_ptr() as *const u8;
+        // Safety: both ranges are in bounds and initialized.
+        unsafe {
+            (
+                slice::from_raw_parts(ptr.add(self.head), first),
+                slice::from_raw_parts(ptr, self.len - first),
+            )
+        }
+    }
+
+    /// Returns the unread bytes as two mutable slices, in order.
+    pub fn as_mut_slices(&mut self) -> (&mut [u8], &mut [u8]) {
+        let first = self.len.min(self.capacity() - self.head);
+        let ptr = self.buf.as_mut_ptr() as *mut u8;
+        // Safety: both ranges are in bounds, initialized, and disjoint.
+        unsafe {
+            (
+                slice::from_raw_parts_mut(ptr.add(self.head), first),
+                slice::from_raw_parts_mut(ptr, self.len - first),
+            )
+        }
+    }
+
+    /// Rearranges the unread bytes so they are contiguous, and returns them.
+    ///
+    /// This does nothing if they already are, and rotates the storage in
+    /// place otherwise, without allocating.
+    pub fn make_contiguous(&mut self) -> &mut [u8] {
+        if self.head + self.len > self.capacity() {
+            self.buf.rotate_left(self.head);
+            self.head = 0;
+        }
+        self.as_mut_slices().0
+    }
+
+    /// Returns the free space as two ranges of the storage, in write order.
+    fn free(&self) -> ((usize, usize), (usize, usize)) {
+        let tail = self.wrap(self.head + self.len);
+        let free = self.capacity() - self.len;
+        let first = free.min(self.capacity() - tail);
+        ((tail, first), (0, free - first))
+    }
+}
+
+impl fmt::Debug for RingBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingBuf")
+            .field("len", &self.len)
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl Buf for RingBuf {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn chunk(&self) -> &[u8] {
+        self.as_slices().0
+    }
+
+    #[cfg(feature = "std")]
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let (a, b) = self.as_slices();
+        let mut n = 0;
+        for (slot, half) in dst.iter_mut().zip([a, b].iter()) {
+            if half.is_empty() {
+                break;
+            }
+            *slot = IoSlice::new(half);
+            n += 1;
+        }
+        n
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.len,
+            "cannot advance past `remaining`: {:?} <= {:?}",
+            cnt,
+            self.len,
+        );
+        self.len -= cnt;
+        // Start over at the front once drained, which keeps the next writes
+        // contiguous for as long as possible.
+        self.head = if self.len == 0 {
+            0
+        } else {
+            self.wrap(self.head + cnt)
+        };
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(len <= self.len, "`len` greater than remaining");
+        let (a, b) = self.as_slices();
+        let bytes = if len <= a.len() {
+            Bytes::copy_from_slice(&a[..len])
+        } else {
+            let mut ret = BytesMut::with_capacity(len);
+            ret.extend_from_slice(a);
+            ret.extend_from_slice(&b[..len - a.len()]);
+            ret.freeze()
+        };
+        self.advance(len);
+        bytes
+    }
+}
+
+unsafe impl BufMut for RingBuf {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.capacity() - self.len
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        let ((start, len), _) = self.free();
+        // Safety: the range is in bounds and not part of the unread bytes.
+        unsafe { UninitSlice::from_raw_parts_mut(self.buf.as_mut_ptr().add(start) as *mut u8, len) }
+    }
+
+    #[cfg(feature = "std")]
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
+        let ranges = self.free();
+        let ptr = self.buf.as_mut_ptr() as *mut u8;
+        let mut n = 0;
+        for (slot, &(start, len)) in dst.iter_mut().zip([ranges.0, ranges.1].iter()) {
+            if len == 0 {
+                break;
+            }
+            // Safety: the ranges are in bounds, disjoint, and not part of
+            // the unread bytes. They are zeroed before being handed out.
+            let free = unsafe {
+                ptr::write_bytes(ptr.add(start), 0, len);
+                slice::from_raw_parts_mut(ptr.add(start), len)
+            };
+            *slot = IoSliceMut::new(free);
+            n += 1;
+        }
+        n
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        let remaining = self.remaining_mut();
+        assert!(
+            cnt <= remaining,
+            "cannot advance past `remaining_mut`: {:?} <= {:?}",
+            cnt,
+            remaining
+        );
+        self.len += cnt;
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        assert!(
+            src.len() <= self.remaining_mut(),
+            "buffer overflow; remaining = {}; src = {}",
+            self.remaining_mut(),
+            src.len()
+        );
+        let ((start, len), _) = self.free();
+        let first = src.len().min(len);
+        let ptr = self.buf.as_mut_ptr() as *mut u8;
+        // Safety: the free space has room for `src`, in at most two parts,
+        // and `src` cannot overlap the exclusively borrowed storage.
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), ptr.add(start), first);
+            ptr::copy_nonoverlapping(src.as_ptr().add(first), ptr, src.len() - first);
+        }
+        self.len += src.len();
+    }
+}
@@ -0,0 +1,246 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use crate::buf::UninitSlice;
+use crate::{Buf, BufMut, Bytes, BytesMut};
+
+use alloc::collections::VecDeque;
+#[cfg(feature = "std")]
+use std::io::{IoSlice, IoSliceMut};
+
+/// A growable buffer made of fixed-size segments.
+///
+/// Writes fill one segment after the other and allocate a new segment when
+/// they run out of room, so bytes already written are never moved or copied.
+/// Reads consume from the front and free each segment once it has been read.
+///
+/// [`reserve`] allocates segments ahead of time, and
+/// [`chunks_vectored_mut`] hands out the spare room of all of them at once,
+/// so a single `readv` can fill several segments.
+///
+/// [`reserve`]: SegmentedBuf::reserve
+/// [`chunks_vectored_mut`]: BufMut::chunks_vectored_mut
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::buf::SegmentedBuf;
+/// use rcbytes::{Buf, BufMut};
+///
+/// let mut buf = SegmentedBuf::new(4);
+/// buf.put(&b"hello world"[..]);
+/// assert_eq!(buf.len(), 11);
+/// assert_eq!(buf.chunk(), b"hell");
+///
+/// assert_eq!(buf.copy_to_bytes(6), "hello ");
+/// assert_eq!(buf.copy_to_bytes(5), "world");
+/// ```
+#[derive(Debug)]
+pub struct SegmentedBuf {
+    segments: VecDeque<BytesMut>,
+    // Index of the segment being written to. Segments before it are full,
+    // segments after it are empty.
+    write: usize,
+    segment_size: usize,
+    len: usize,
+}
+
+impl SegmentedBuf {
+    /// Creates an empty buffer that allocates `segment_size` bytes at a time.
+    ///
+    /// Nothing is allocated until the first write.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segment_size` is 0.
+    pub fn new(segment_size: usize) -> SegmentedBuf {
+        assert!(segment_size > 0, "segment size must be non-zero");
+        SegmentedBuf {
+            segments: VecDeque::new(),
+            write: 0,
+            segment_size,
+            len: 0,
+        }
+    }
+
+    /// Returns the size of the segments this buffer allocates.
+    pub fn segment_size(&self) -> usize {
+        self.segment_size
+    }
+
+    /// Returns the number of bytes written and not yet read.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no bytes to read.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bytes that can be written without allocating.
+    pub fn spare_capacity(&self) -> usize {
+        self.segments
+            .iter()
+            .skip(self.write)
+            .map(|segment| segment.capacity() - segment.len())
+            .sum()
+    }
+
+    /// Allocates segments until at least `additional` bytes can be written
+    /// without allocating.
+    pub fn reserve(&mut self, additional: usize) {
+        let mut spare = self.spare_capacity();
+        while spare < additional {
+            self.segments
+                .push_back(BytesMut::with_capacity(self.segment_size));
+            spare += self.segment_size;
+        }
+    }
+
+    /// Makes sure there is a segment to write to.
+    fn write_segment(&mut self) -> &mut BytesMut {
+        if self.write == self.segments.len() {
+            self.segments
+                .push_back(BytesMut::with_capacity(self.segment_size));
+        }
+        &mut self.segments[self.write]
+    }
+
+    /// Frees the front segment if it is both full and fully read.
+    fn pop_read(&mut self) {
+        if self.write > 0 && self.segments[0].is_empty() {
+            self.segments.pop_front();
+            self.write -= 1;
+        }
+    }
+}
+
+impl Buf for SegmentedBuf {
+    fn remaining(&self) -> usize {
+        self.len
+    }
+
+    fn chunk(&self) -> &[u8] {
+        // Only the segment being written to can be empty and still be
+        // kept, and everything after it is empty as well.
+        match self.segments.front() {
+            Some(segment) => segment,
+            None => &[],
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let segments = self.segments.iter().filter(|segment| !segment.is_empty());
+        let mut n = 0;
+        for (slot, segment) in dst.iter_mut().zip(segments) {
+            *slot = IoSlice::new(segment);
+            n += 1;
+        }
+        n
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        assert!(
+            cnt <= self.len,
+            "cannot advance past `remaining`: {:?} <= {:?}",
+            cnt,
+            self.len,
+        );
+        self.len -= cnt;
+
+        while cnt > 0 {
+            let segment = &mut self.segments[0];
+            let n = cnt.min(segment.len());
+            segment.advance(n);
+            cnt -= n;
+            self.pop_read();
+        }
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        match self.segments.front_mut() {
+            // Hand out the front segment's storage if it covers `len`.
+            Some(segment) if segment.len() >= len => {
+                let bytes = segment.split_to(len).freeze();
+                self.len -= len;
+                self.pop_read();
+                bytes
+            }
+            _ => {
+                assert!(len <= self.remaining(), "`len` greater than remaining");
+                let mut ret = BytesMut::with_capacity(len);
+                ret.put(self.take(len));
+                ret.freeze()
+            }
+        }
+    }
+}
+
+unsafe impl BufMut for SegmentedBuf {
+    fn remaining_mut(&self) -> usize {
+        usize::MAX - self.len
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        // The write segment always has spare capacity, so this never grows
+        // it.
+        self.write_segment().chunk_mut()
+    }
+
+    #[cfg(feature = "std")]
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
+        if dst.is_empty() {
+            return 0;
+        }
+        self.write_segment();
+
+        let mut n = 0;
+        let segments = self.segments.iter_mut().skip(self.write);
+        for (slot, segment) in dst.iter_mut().zip(segments) {
+            *slot = IoSliceMut::new(segment.chunk_mut().zeroed());
+            n += 1;
+        }
+        n
+    }
+
+    unsafe fn advance_mut(&mut self, mut cnt: usize) {
+        self.len += cnt;
+
+        while cnt > 0 {
+            assert!(
+                self.write < self.segments.len(),
+                "cannot advance past the chunks handed out"
+            );
+            let segment = &mut self.segments[self.write];
+            let spare = segment.capacity() - segment.len();
+            let n = cnt.min(spare);
+            segment.advance_mut(n);
+            cnt -= n;
+            if n == spare {
+                self.write += 1;
+            }
+        }
+    }
+}
@@ -31,7 +31,7 @@ use core::cmp;
 ///
 /// This struct is generally created by calling `take()` on `Buf`. See
 /// documentation of [`take()`](trait.Buf.html#method.take) for more details.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Take<T> {
     inner: T,
     limit: usize,
@@ -0,0 +1,194 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use crate::buf::{TryGetError, UninitSlice};
+use crate::{Buf, BufMut, Bytes};
+
+#[cfg(feature = "std")]
+use std::io::{IoSlice, IoSliceMut};
+
+/// An adapter which counts the bytes passing through a buffer.
+///
+/// As a `Buf`, [`position`](Tracked::position) is the number of bytes
+/// consumed since the adapter was created. As a `BufMut`, it is the number
+/// of bytes written. Bytes that are only looked at through `chunk` are not
+/// counted.
+///
+/// Errors returned by the `try_get_*` methods carry the position at which
+/// the failed read started, which makes it possible to report where a
+/// malformed message went wrong. When adapters are nested, the outermost
+/// `Tracked` sets the position.
+///
+/// This struct is generally created by calling
+/// [`tracked()`](trait.Buf.html#method.tracked) on `Buf` or
+/// [`tracked_mut()`](trait.BufMut.html#method.tracked_mut) on `BufMut`.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::Buf;
+///
+/// let mut buf = b"\x00\x05hello\x00\x09"[..].tracked();
+///
+/// let len = buf.get_u16() as usize;
+/// let mark = buf.mark();
+/// buf.advance(len);
+/// assert_eq!(buf.since(mark), 5);
+///
+/// let err = buf.try_get_u32().unwrap_err();
+/// assert_eq!(err.position, Some(7));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tracked<T> {
+    inner: T,
+    pos: usize,
+}
+
+/// A position recorded by [`Tracked::mark`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mark {
+    pos: usize,
+}
+
+impl Mark {
+    /// Returns the position at which the mark was taken.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+pub(super) fn new<T>(inner: T) -> Tracked<T> {
+    Tracked { inner, pos: 0 }
+}
+
+impl<T> Tracked<T> {
+    /// Consumes this `Tracked`, returning the underlying value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying buffer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying buffer.
+    ///
+    /// Bytes read from or written to the underlying buffer directly are not
+    /// counted.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the number of bytes consumed or written so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Records the current position.
+    ///
+    /// Pass the mark to [`since`](Tracked::since) later on to find out how
+    /// many bytes went through in between, e.g. the length of a nested
+    /// message.
+    pub fn mark(&self) -> Mark {
+        Mark { pos: self.pos }
+    }
+
+    /// Returns the number of bytes consumed or written since `mark` was
+    /// taken.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `mark` is ahead of the current position,
+    /// which can only happen if it was taken from another `Tracked`.
+    pub fn since(&self, mark: Mark) -> usize {
+        assert!(
+            mark.pos <= self.pos,
+            "mark is ahead of the current position: {:?} <= {:?}",
+            mark.pos,
+            self.pos,
+        );
+        self.pos - mark.pos
+    }
+}
+
+impl<T: Buf> Buf for Tracked<T> {
+    fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.inner.chunk()
+    }
+
+    #[cfg(feature = "std")]
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        self.inner.chunks_vectored(dst)
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.inner.advance(cnt);
+        self.pos += cnt;
+    }
+
+    fn try_copy_to_slice(&mut self, dst: &mut [u8]) -> Result<(), TryGetError> {
+        let pos = self.pos;
+        self.inner.try_copy_to_slice(dst).map_err(|mut err| {
+            err.position = Some(pos);
+            err
+        })?;
+        self.pos += dst.len();
+        Ok(())
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        let bytes = self.inner.copy_to_bytes(len);
+        self.pos += len;
+        bytes
+    }
+}
+
+unsafe impl<T: BufMut> BufMut for Tracked<T> {
+    fn remaining_mut(&self) -> usize {
+        self.inner.remaining_mut()
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.inner.chunk_mut()
+    }
+
+    #[cfg(feature = "std")]
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
+        self.inner.chunks_vectored_mut(dst)
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.inner.advance_mut(cnt);
+        self.pos += cnt;
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        self.inner.put_slice(src);
+        self.pos += src.len();
+    }
+}
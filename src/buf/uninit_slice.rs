@@ -72,6 +72,13 @@ impl UninitSlice {
         &mut *(maybe_init as *mut [MaybeUninit<u8>] as *mut UninitSlice)
     }
 
+    // Initialized bytes are valid uninitialized memory, and an `UninitSlice`
+    // can only be used to write initialized bytes back.
+    #[inline]
+    pub(crate) fn from_slice(slice: &mut [u8]) -> &mut UninitSlice {
+        unsafe { UninitSlice::from_raw_parts_mut(slice.as_mut_ptr(), slice.len()) }
+    }
+
     /// Write a single byte at the specified offset.
     ///
     /// # Panics
@@ -175,6 +182,18 @@ impl UninitSlice {
         &mut *(self as *mut _ as *mut [MaybeUninit<u8>])
     }
 
+    /// Fills the slice with zeros and returns it as initialized bytes.
+    pub(crate) fn zeroed(&mut self) -> &mut [u8] {
+        let len = self.len();
+        let ptr = self.as_mut_ptr();
+        // Safety: the memory is valid for writes of `len` bytes, and is
+        // initialized once it has been filled.
+        unsafe {
+            core::ptr::write_bytes(ptr, 0, len);
+            core::slice::from_raw_parts_mut(ptr, len)
+        }
+    }
+
     /// Returns the number of bytes in the slice.
     ///
     /// # Examples
@@ -24,8 +24,11 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 use alloc::collections::VecDeque;
+#[cfg(feature = "std")]
+use std::io::IoSlice;
 
 use super::Buf;
+use crate::{Bytes, BytesMut};
 
 impl Buf for VecDeque<u8> {
     fn remaining(&self) -> usize {
@@ -41,7 +44,34 @@ impl Buf for VecDeque<u8> {
         }
     }
 
+    #[cfg(feature = "std")]
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let (s1, s2) = self.as_slices();
+        let halves = [s1, s2];
+        let mut n = 0;
+        for (slot, half) in dst.iter_mut().zip(halves.iter().filter(|h| !h.is_empty())) {
+            *slot = IoSlice::new(half);
+            n += 1;
+        }
+        n
+    }
+
     fn advance(&mut self, cnt: usize) {
         self.drain(..cnt);
     }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(len <= self.len(), "`len` greater than remaining");
+        let (s1, s2) = self.as_slices();
+        let bytes = if len <= s1.len() {
+            Bytes::copy_from_slice(&s1[..len])
+        } else {
+            let mut ret = BytesMut::with_capacity(len);
+            ret.extend_from_slice(s1);
+            ret.extend_from_slice(&s2[..len - s1.len()]);
+            ret.freeze()
+        };
+        self.advance(len);
+        bytes
+    }
 }
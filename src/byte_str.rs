@@ -0,0 +1,536 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use alloc::{borrow::Borrow, string::String, vec::Vec};
+use core::convert::TryFrom;
+use core::ops::{Deref, RangeBounds};
+use core::{cmp, fmt, hash, str};
+
+use crate::Bytes;
+
+/// A cheaply cloneable and sliceable UTF-8 string backed by [`Bytes`].
+///
+/// `ByteStr` is a `Bytes` that is known to hold valid UTF-8. The check is
+/// done once when the `ByteStr` is created, after which it dereferences to
+/// `str` for free. Slicing and splitting share the underlying memory just like
+/// they do for `Bytes`, but they additionally check that the split points lie
+/// on `char` boundaries.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::{ByteStr, Bytes};
+/// use std::convert::TryFrom;
+///
+/// let mut path = ByteStr::try_from(Bytes::from("/index.html")).unwrap();
+/// let slash = path.split_to(1);
+///
+/// assert_eq!(slash, "/");
+/// assert!(path.ends_with(".html"));
+/// ```
+#[derive(Clone, Default)]
+pub struct ByteStr {
+    // Invariant: `bytes` holds valid UTF-8.
+    bytes: Bytes,
+}
+
+/// The error returned when bytes that are not valid UTF-8 are converted into
+/// a [`ByteStr`] or a [`ByteString`].
+///
+/// The original buffer can be recovered with [`into_bytes`].
+///
+/// [`into_bytes`]: FromUtf8Error::into_bytes
+/// [`ByteString`]: crate::ByteString
+pub struct FromUtf8Error<B = Bytes> {
+    bytes: B,
+    error: str::Utf8Error,
+}
+
+impl ByteStr {
+    /// Creates a new empty `ByteStr`.
+    ///
+    /// This will not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteStr;
+    ///
+    /// let s = ByteStr::new();
+    /// assert_eq!(s, "");
+    /// ```
+    #[inline]
+    pub const fn new() -> ByteStr {
+        ByteStr {
+            bytes: Bytes::new(),
+        }
+    }
+
+    /// Creates a new `ByteStr` from a static string.
+    ///
+    /// The returned `ByteStr` will point directly to the static string. There
+    /// is no allocating or copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteStr;
+    ///
+    /// let s = ByteStr::from_static("hello");
+    /// assert_eq!(s, "hello");
+    /// ```
+    #[inline]
+    pub const fn from_static(s: &'static str) -> ByteStr {
+        ByteStr {
+            bytes: Bytes::from_static(s.as_bytes()),
+        }
+    }
+
+    /// Converts `bytes` into a `ByteStr` if it holds valid UTF-8.
+    ///
+    /// This does not copy; the `ByteStr` shares the memory of `bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::{ByteStr, Bytes};
+    ///
+    /// let s = ByteStr::from_utf8(Bytes::from("héllo")).unwrap();
+    /// assert_eq!(s, "héllo");
+    ///
+    /// let err = ByteStr::from_utf8(Bytes::from(&b"\xff"[..])).unwrap_err();
+    /// assert_eq!(err.into_bytes(), &b"\xff"[..]);
+    /// ```
+    pub fn from_utf8(bytes: Bytes) -> Result<ByteStr, FromUtf8Error> {
+        match str::from_utf8(&bytes) {
+            Ok(_) => Ok(ByteStr { bytes }),
+            Err(error) => Err(FromUtf8Error::new(bytes, error)),
+        }
+    }
+
+    /// Converts `bytes` into a `ByteStr` without checking that it holds valid
+    /// UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be valid UTF-8.
+    #[inline]
+    pub unsafe fn from_utf8_unchecked(bytes: Bytes) -> ByteStr {
+        ByteStr { bytes }
+    }
+
+    /// Returns the contents as a `str`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /// Returns the underlying `Bytes`.
+    #[inline]
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Converts the `ByteStr` into the underlying `Bytes`.
+    #[inline]
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+
+    /// Returns a slice of self for the provided byte range.
+    ///
+    /// This will increment the reference count for the underlying memory and
+    /// return a new `ByteStr` handle set to the slice.
+    ///
+    /// This operation is `O(1)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteStr;
+    ///
+    /// let a = ByteStr::from("hello world");
+    /// let b = a.slice(2..5);
+    ///
+    /// assert_eq!(b, "llo");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Requires that `begin <= end` and `end <= self.len()`, and that both
+    /// lie on `char` boundaries, otherwise slicing will panic.
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> ByteStr {
+        use core::ops::Bound;
+
+        let begin = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).expect("out of range"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len(),
+        };
+
+        self.assert_char_boundary(begin);
+        self.assert_char_boundary(end);
+
+        ByteStr {
+            bytes: self.bytes.slice(begin..end),
+        }
+    }
+
+    /// Splits the string into two at the given byte index.
+    ///
+    /// Afterwards `self` contains elements `[0, at)`, and the returned
+    /// `ByteStr` contains elements `[at, len)`.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count and
+    /// sets a few indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteStr;
+    ///
+    /// let mut a = ByteStr::from("hello world");
+    /// let b = a.split_off(5);
+    ///
+    /// assert_eq!(a, "hello");
+    /// assert_eq!(b, " world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len` or if `at` is not on a `char` boundary.
+    #[must_use = "consider ByteStr::truncate if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn split_off(&mut self, at: usize) -> ByteStr {
+        self.assert_char_boundary(at);
+
+        ByteStr {
+            bytes: self.bytes.split_off(at),
+        }
+    }
+
+    /// Splits the string into two at the given byte index.
+    ///
+    /// Afterwards `self` contains elements `[at, len)`, and the returned
+    /// `ByteStr` contains elements `[0, at)`.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count and
+    /// sets a few indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteStr;
+    ///
+    /// let mut a = ByteStr::from("hello world");
+    /// let b = a.split_to(5);
+    ///
+    /// assert_eq!(a, " world");
+    /// assert_eq!(b, "hello");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len` or if `at` is not on a `char` boundary.
+    #[must_use = "consider slicing with ByteStr::slice if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn split_to(&mut self, at: usize) -> ByteStr {
+        self.assert_char_boundary(at);
+
+        ByteStr {
+            bytes: self.bytes.split_to(at),
+        }
+    }
+
+    /// Shortens the string to `len` bytes.
+    ///
+    /// If `len` is greater than the string's current length, this has no
+    /// effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteStr;
+    ///
+    /// let mut s = ByteStr::from("hello world");
+    /// s.truncate(5);
+    /// assert_eq!(s, "hello");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not on a `char` boundary.
+    #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.assert_char_boundary(len);
+            self.bytes.truncate(len);
+        }
+    }
+
+    /// Clears the string, removing all data.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    #[inline]
+    #[track_caller]
+    fn assert_char_boundary(&self, at: usize) {
+        assert!(
+            at > self.len() || self.is_char_boundary(at),
+            "byte index {:?} is not a char boundary",
+            at,
+        );
+    }
+}
+
+impl Deref for ByteStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ByteStr {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for ByteStr {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.bytes.as_ref()
+    }
+}
+
+impl Borrow<str> for ByteStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl hash::Hash for ByteStr {
+    // Must hash like `str` to uphold the `Borrow<str>` contract.
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        self.as_str().hash(state);
+    }
+}
+
+impl fmt::Debug for ByteStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for ByteStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for ByteStr {
+    fn eq(&self, other: &ByteStr) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialOrd for ByteStr {
+    fn partial_cmp(&self, other: &ByteStr) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByteStr {
+    fn cmp(&self, other: &ByteStr) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Eq for ByteStr {}
+
+impl PartialEq<str> for ByteStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialOrd<str> for ByteStr {
+    fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other)
+    }
+}
+
+impl PartialEq<ByteStr> for str {
+    fn eq(&self, other: &ByteStr) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialOrd<ByteStr> for str {
+    fn partial_cmp(&self, other: &ByteStr) -> Option<cmp::Ordering> {
+        self.partial_cmp(other.as_str())
+    }
+}
+
+impl PartialEq<String> for ByteStr {
+    fn eq(&self, other: &String) -> bool {
+        *self == other[..]
+    }
+}
+
+impl PartialOrd<String> for ByteStr {
+    fn partial_cmp(&self, other: &String) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
+impl PartialEq<ByteStr> for String {
+    fn eq(&self, other: &ByteStr) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialOrd<ByteStr> for String {
+    fn partial_cmp(&self, other: &ByteStr) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
+impl PartialEq<ByteStr> for &str {
+    fn eq(&self, other: &ByteStr) -> bool {
+        *other == **self
+    }
+}
+
+impl PartialOrd<ByteStr> for &str {
+    fn partial_cmp(&self, other: &ByteStr) -> Option<cmp::Ordering> {
+        (**self).partial_cmp(other.as_str())
+    }
+}
+
+impl<'a, T: ?Sized> PartialEq<&'a T> for ByteStr
+where
+    ByteStr: PartialEq<T>,
+{
+    fn eq(&self, other: &&'a T) -> bool {
+        *self == **other
+    }
+}
+
+impl<'a, T: ?Sized> PartialOrd<&'a T> for ByteStr
+where
+    ByteStr: PartialOrd<T>,
+{
+    fn partial_cmp(&self, other: &&'a T) -> Option<cmp::Ordering> {
+        self.partial_cmp(&**other)
+    }
+}
+
+// impl From
+
+impl From<&'static str> for ByteStr {
+    fn from(s: &'static str) -> ByteStr {
+        ByteStr::from_static(s)
+    }
+}
+
+impl From<String> for ByteStr {
+    fn from(s: String) -> ByteStr {
+        ByteStr {
+            bytes: Bytes::from(s),
+        }
+    }
+}
+
+impl TryFrom<Bytes> for ByteStr {
+    type Error = FromUtf8Error;
+
+    fn try_from(bytes: Bytes) -> Result<ByteStr, FromUtf8Error> {
+        ByteStr::from_utf8(bytes)
+    }
+}
+
+impl From<ByteStr> for Bytes {
+    fn from(s: ByteStr) -> Bytes {
+        s.bytes
+    }
+}
+
+impl From<ByteStr> for String {
+    fn from(s: ByteStr) -> String {
+        let vec = Vec::from(s.bytes);
+        unsafe { String::from_utf8_unchecked(vec) }
+    }
+}
+
+// ===== impl FromUtf8Error =====
+
+impl<B> FromUtf8Error<B> {
+    pub(crate) fn new(bytes: B, error: str::Utf8Error) -> FromUtf8Error<B> {
+        FromUtf8Error { bytes, error }
+    }
+
+    /// Returns the buffer that failed to convert.
+    pub fn into_bytes(self) -> B {
+        self.bytes
+    }
+
+    /// Returns the underlying UTF-8 validation error, which tells where the
+    /// invalid sequence starts.
+    pub fn utf8_error(&self) -> str::Utf8Error {
+        self.error
+    }
+}
+
+impl<B> fmt::Debug for FromUtf8Error<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromUtf8Error")
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<B> fmt::Display for FromUtf8Error<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<B> std::error::Error for FromUtf8Error<B> {}
@@ -0,0 +1,593 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use alloc::{borrow::Borrow, string::String, vec::Vec};
+use core::convert::TryFrom;
+use core::iter::FromIterator;
+use core::ops::{Deref, DerefMut};
+use core::{cmp, fmt, hash, str};
+
+use crate::{ByteStr, BytesMut, FromUtf8Error};
+
+/// A growable UTF-8 string backed by [`BytesMut`].
+///
+/// `ByteString` is the mutable counterpart of [`ByteStr`]. Text is appended
+/// with [`push_str`], [`push`] or `write!`, and the finished string is handed
+/// out with [`freeze`] or one of the `split` methods without copying and
+/// without validating it again.
+///
+/// Growing and splitting go through the same machinery as `BytesMut`, so a
+/// single allocation can back many `ByteStr` handles.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::ByteString;
+/// use std::fmt::Write;
+///
+/// let mut buf = ByteString::with_capacity(64);
+/// buf.push_str("GET ");
+/// write!(buf, "/items/{}", 42).unwrap();
+///
+/// let line = buf.split().freeze();
+/// assert_eq!(line, "GET /items/42");
+/// ```
+///
+/// [`push_str`]: ByteString::push_str
+/// [`push`]: ByteString::push
+/// [`freeze`]: ByteString::freeze
+#[derive(Clone, Default)]
+pub struct ByteString {
+    // Invariant: `bytes` holds valid UTF-8.
+    bytes: BytesMut,
+}
+
+impl ByteString {
+    /// Creates a new `ByteString` with the specified capacity in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteString;
+    ///
+    /// let s = ByteString::with_capacity(64);
+    /// assert!(s.is_empty());
+    /// assert!(s.capacity() >= 64);
+    /// ```
+    #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn with_capacity(capacity: usize) -> ByteString {
+        ByteString {
+            bytes: BytesMut::with_capacity(capacity),
+        }
+    }
+
+    /// Creates a new empty `ByteString`.
+    ///
+    /// This function does not allocate.
+    #[inline]
+    pub fn new() -> ByteString {
+        ByteString {
+            bytes: BytesMut::new(),
+        }
+    }
+
+    /// Converts `bytes` into a `ByteString` if it holds valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::{ByteString, BytesMut};
+    ///
+    /// let s = ByteString::from_utf8(BytesMut::from("héllo")).unwrap();
+    /// assert_eq!(s, "héllo");
+    ///
+    /// assert!(ByteString::from_utf8(BytesMut::from(&b"\xff"[..])).is_err());
+    /// ```
+    pub fn from_utf8(bytes: BytesMut) -> Result<ByteString, FromUtf8Error<BytesMut>> {
+        match str::from_utf8(&bytes) {
+            Ok(_) => Ok(ByteString { bytes }),
+            Err(error) => Err(FromUtf8Error::new(bytes, error)),
+        }
+    }
+
+    /// Converts `bytes` into a `ByteString` without checking that it holds
+    /// valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be valid UTF-8.
+    #[inline]
+    pub unsafe fn from_utf8_unchecked(bytes: BytesMut) -> ByteString {
+        ByteString { bytes }
+    }
+
+    /// Returns the number of bytes the string can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more bytes.
+    ///
+    /// See [`BytesMut::reserve`] for how the capacity is reclaimed or grown.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.bytes.reserve(additional);
+    }
+
+    /// Returns the contents as a `str`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /// Returns the contents as a mutable `str`.
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.bytes) }
+    }
+
+    /// Returns the underlying `BytesMut`.
+    #[inline]
+    pub fn as_bytes(&self) -> &BytesMut {
+        &self.bytes
+    }
+
+    /// Converts the `ByteString` into the underlying `BytesMut`.
+    #[inline]
+    pub fn into_bytes(self) -> BytesMut {
+        self.bytes
+    }
+
+    /// Appends `s` to the end of the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteString;
+    ///
+    /// let mut s = ByteString::new();
+    /// s.push_str("hello");
+    /// s.push_str(" world");
+    /// assert_eq!(s, "hello world");
+    /// ```
+    #[inline]
+    pub fn push_str(&mut self, s: &str) {
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+
+    /// Appends `ch` to the end of the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteString;
+    ///
+    /// let mut s = ByteString::from("caf");
+    /// s.push('é');
+    /// assert_eq!(s, "café");
+    /// ```
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Converts `self` into an immutable [`ByteStr`].
+    ///
+    /// The contents are not validated again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteString;
+    ///
+    /// let mut s = ByteString::new();
+    /// s.push_str("hello");
+    ///
+    /// let s = s.freeze();
+    /// assert_eq!(s, "hello");
+    /// ```
+    #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn freeze(self) -> ByteStr {
+        unsafe { ByteStr::from_utf8_unchecked(self.bytes.freeze()) }
+    }
+
+    /// Splits the string into two at the given byte index.
+    ///
+    /// Afterwards `self` contains elements `[0, at)`, and the returned
+    /// `ByteString` contains elements `[at, capacity)`.
+    ///
+    /// This is an `O(1)` operation, see [`BytesMut::split_off`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteString;
+    ///
+    /// let mut a = ByteString::from("héllo world");
+    /// let b = a.split_off(6);
+    ///
+    /// assert_eq!(a, "héllo");
+    /// assert_eq!(b, " world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > capacity` or if `at` is not on a `char` boundary.
+    #[must_use = "consider ByteString::truncate if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn split_off(&mut self, at: usize) -> ByteString {
+        self.assert_char_boundary(at);
+
+        ByteString {
+            bytes: self.bytes.split_off(at),
+        }
+    }
+
+    /// Removes the string from `self` and returns it.
+    ///
+    /// Afterwards `self` is empty but keeps the remaining capacity, see
+    /// [`BytesMut::split`].
+    #[must_use = "consider ByteString::clear if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn split(&mut self) -> ByteString {
+        ByteString {
+            bytes: self.bytes.split(),
+        }
+    }
+
+    /// Splits the string into two at the given byte index.
+    ///
+    /// Afterwards `self` contains elements `[at, len)`, and the returned
+    /// `ByteString` contains elements `[0, at)`.
+    ///
+    /// This is an `O(1)` operation, see [`BytesMut::split_to`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteString;
+    ///
+    /// let mut a = ByteString::from("héllo world");
+    /// let b = a.split_to(6);
+    ///
+    /// assert_eq!(a, " world");
+    /// assert_eq!(b, "héllo");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len` or if `at` is not on a `char` boundary.
+    #[must_use = "consider slicing with ByteString::split if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn split_to(&mut self, at: usize) -> ByteString {
+        self.assert_char_boundary(at);
+
+        ByteString {
+            bytes: self.bytes.split_to(at),
+        }
+    }
+
+    /// Shortens the string to `len` bytes.
+    ///
+    /// If `len` is greater than the string's current length, this has no
+    /// effect. The capacity is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::ByteString;
+    ///
+    /// let mut s = ByteString::from("héllo");
+    /// s.truncate(3);
+    /// assert_eq!(s, "hé");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not on a `char` boundary.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.assert_char_boundary(len);
+            self.bytes.truncate(len);
+        }
+    }
+
+    /// Clears the string, removing all data. Existing capacity is preserved.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    #[inline]
+    #[track_caller]
+    fn assert_char_boundary(&self, at: usize) {
+        assert!(
+            at > self.len() || self.is_char_boundary(at),
+            "byte index {:?} is not a char boundary",
+            at,
+        );
+    }
+}
+
+impl Deref for ByteString {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl DerefMut for ByteString {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl AsRef<str> for ByteString {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for ByteString {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.bytes.as_ref()
+    }
+}
+
+impl Borrow<str> for ByteString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl hash::Hash for ByteString {
+    // Must hash like `str` to uphold the `Borrow<str>` contract.
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        self.as_str().hash(state);
+    }
+}
+
+impl fmt::Debug for ByteString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for ByteString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Write for ByteString {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        self.push(ch);
+        Ok(())
+    }
+}
+
+impl Extend<char> for ByteString {
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = char>,
+    {
+        let iter = iter.into_iter();
+
+        let (lower, _) = iter.size_hint();
+        self.reserve(lower);
+
+        for ch in iter {
+            self.push(ch);
+        }
+    }
+}
+
+impl<'a> Extend<&'a str> for ByteString {
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = &'a str>,
+    {
+        for s in iter {
+            self.push_str(s);
+        }
+    }
+}
+
+impl FromIterator<char> for ByteString {
+    fn from_iter<T: IntoIterator<Item = char>>(into_iter: T) -> Self {
+        let mut s = ByteString::new();
+        s.extend(into_iter);
+        s
+    }
+}
+
+impl<'a> FromIterator<&'a str> for ByteString {
+    fn from_iter<T: IntoIterator<Item = &'a str>>(into_iter: T) -> Self {
+        let mut s = ByteString::new();
+        s.extend(into_iter);
+        s
+    }
+}
+
+impl PartialEq for ByteString {
+    fn eq(&self, other: &ByteString) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialOrd for ByteString {
+    fn partial_cmp(&self, other: &ByteString) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByteString {
+    fn cmp(&self, other: &ByteString) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Eq for ByteString {}
+
+impl PartialEq<str> for ByteString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialOrd<str> for ByteString {
+    fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other)
+    }
+}
+
+impl PartialEq<ByteString> for str {
+    fn eq(&self, other: &ByteString) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialOrd<ByteString> for str {
+    fn partial_cmp(&self, other: &ByteString) -> Option<cmp::Ordering> {
+        self.partial_cmp(other.as_str())
+    }
+}
+
+impl PartialEq<String> for ByteString {
+    fn eq(&self, other: &String) -> bool {
+        *self == other[..]
+    }
+}
+
+impl PartialOrd<String> for ByteString {
+    fn partial_cmp(&self, other: &String) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
+impl PartialEq<ByteString> for String {
+    fn eq(&self, other: &ByteString) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialOrd<ByteString> for String {
+    fn partial_cmp(&self, other: &ByteString) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
+impl PartialEq<ByteString> for &str {
+    fn eq(&self, other: &ByteString) -> bool {
+        *other == **self
+    }
+}
+
+impl PartialOrd<ByteString> for &str {
+    fn partial_cmp(&self, other: &ByteString) -> Option<cmp::Ordering> {
+        (**self).partial_cmp(other.as_str())
+    }
+}
+
+impl<'a, T: ?Sized> PartialEq<&'a T> for ByteString
+where
+    ByteString: PartialEq<T>,
+{
+    fn eq(&self, other: &&'a T) -> bool {
+        *self == **other
+    }
+}
+
+impl<'a, T: ?Sized> PartialOrd<&'a T> for ByteString
+where
+    ByteString: PartialOrd<T>,
+{
+    fn partial_cmp(&self, other: &&'a T) -> Option<cmp::Ordering> {
+        self.partial_cmp(&**other)
+    }
+}
+
+// impl From
+
+impl<'a> From<&'a str> for ByteString {
+    fn from(s: &'a str) -> ByteString {
+        ByteString {
+            bytes: BytesMut::from(s),
+        }
+    }
+}
+
+impl From<String> for ByteString {
+    fn from(s: String) -> ByteString {
+        ByteString {
+            bytes: BytesMut::from_vec(s.into_bytes()),
+        }
+    }
+}
+
+impl TryFrom<BytesMut> for ByteString {
+    type Error = FromUtf8Error<BytesMut>;
+
+    fn try_from(bytes: BytesMut) -> Result<ByteString, FromUtf8Error<BytesMut>> {
+        ByteString::from_utf8(bytes)
+    }
+}
+
+impl From<ByteString> for BytesMut {
+    fn from(s: ByteString) -> BytesMut {
+        s.bytes
+    }
+}
+
+impl From<ByteString> for ByteStr {
+    #[cfg_attr(feature = "track", track_caller)]
+    fn from(s: ByteString) -> ByteStr {
+        s.freeze()
+    }
+}
+
+impl From<ByteString> for String {
+    fn from(s: ByteString) -> String {
+        let vec = Vec::from(s.bytes);
+        unsafe { String::from_utf8_unchecked(vec) }
+    }
+}
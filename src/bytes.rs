@@ -120,11 +120,32 @@ use crate::Buf;
 /// │ Rc │     │           │               │     │
 /// └────┴─────┴───────────┴───────────────┴─────┘
 /// ```
+///
+/// # Inline storage
+///
+/// Short buffers of up to `3 * size_of::<usize>() - 1` bytes are stored
+/// inside the `Bytes` struct itself, in the space otherwise taken by the
+/// pointer, the shared state and the length. Such a `Bytes` needs no
+/// allocation, and cloning it is a plain copy. [`Bytes::copy_from_slice`] and
+/// `From<Vec<u8>>` pick the inline representation for short inputs, and
+/// [`Bytes::slice`] does when the alternative would be to allocate the shared
+/// state of a buffer that is still held as a `Vec`.
+///
+/// `Bytes` stays 4 words wide either way.
+// An inline buffer sets the top bit of `len`, which a heap length never has
+// since it can't exceed `isize::MAX`, and keeps its length in the remaining
+// bits of the most significant byte. The contents fill the other bytes of
+// `ptr`, `data` and `len`; the fields are ordered so that those bytes are
+// contiguous whatever the endianness.
+#[repr(C)]
 pub struct Bytes {
-    ptr: *const u8,
+    #[cfg(target_endian = "big")]
     len: usize,
+    ptr: *const u8,
     // inlined "trait object"
     data: UnsafeCell<*mut ()>,
+    #[cfg(target_endian = "little")]
+    len: usize,
     vtable: &'static Vtable,
 }
 
@@ -195,7 +216,11 @@ impl Bytes {
     /// ```
     #[inline]
     pub const fn len(&self) -> usize {
-        self.len
+        if self.len & INLINE_TAG == 0 {
+            self.len
+        } else {
+            (self.len >> INLINE_LEN_SHIFT) & INLINE_LEN_MASK
+        }
     }
 
     /// Returns true if the `Bytes` has a length of 0.
@@ -210,11 +235,17 @@ impl Bytes {
     /// ```
     #[inline]
     pub const fn is_empty(&self) -> bool {
-        self.len == 0
+        self.len() == 0
     }
 
     /// Creates `Bytes` instance from slice, by copying it.
+    ///
+    /// Slices short enough to be stored inline do not allocate.
     pub fn copy_from_slice(data: &[u8]) -> Self {
+        if data.len() <= INLINE_CAP {
+            return Bytes::inline(data);
+        }
+
         data.to_vec().into()
     }
 
@@ -240,6 +271,7 @@ impl Bytes {
     ///
     /// Requires that `begin <= end` and `end <= self.len()`, otherwise slicing
     /// will panic.
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn slice(&self, range: impl RangeBounds<usize>) -> Bytes {
         use core::ops::Bound;
 
@@ -274,10 +306,16 @@ impl Bytes {
             return Bytes::new();
         }
 
+        // A buffer still held as a `Vec` would need its shared state
+        // allocated to be cloned, copying a short range out is cheaper.
+        if end - begin <= INLINE_CAP && (self.is_inline() || self.is_unshared_vec()) {
+            return Bytes::inline(&self.as_slice()[begin..end]);
+        }
+
         let mut ret = self.clone();
 
-        ret.len = end - begin;
-        ret.ptr = unsafe { ret.ptr.add(begin) };
+        ret.set_len(end);
+        unsafe { ret.inc_start(begin) };
 
         ret
     }
@@ -307,6 +345,7 @@ impl Bytes {
     ///
     /// Requires that the given `sub` slice is in fact contained within the
     /// `Bytes` buffer; otherwise this function will panic.
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn slice_ref(&self, subset: &[u8]) -> Bytes {
         // Empty slice and empty Bytes may have their pointers reset
         // so explicitly allow empty slice to be a subslice of any slice.
@@ -364,6 +403,7 @@ impl Bytes {
     ///
     /// Panics if `at > len`.
     #[must_use = "consider Bytes::truncate if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn split_off(&mut self, at: usize) -> Bytes {
         assert!(
             at <= self.len(),
@@ -382,7 +422,7 @@ impl Bytes {
 
         let mut ret = self.clone();
 
-        self.len = at;
+        self.set_len(at);
 
         unsafe { ret.inc_start(at) };
 
@@ -413,6 +453,7 @@ impl Bytes {
     ///
     /// Panics if `at > len`.
     #[must_use = "consider Bytes::advance if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn split_to(&mut self, at: usize) -> Bytes {
         assert!(
             at <= self.len(),
@@ -433,7 +474,7 @@ impl Bytes {
 
         unsafe { self.inc_start(at) };
 
-        ret.len = at;
+        ret.set_len(at);
         ret
     }
 
@@ -458,17 +499,16 @@ impl Bytes {
     ///
     /// [`split_off`]: #method.split_off
     #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn truncate(&mut self, len: usize) {
-        if len < self.len {
+        if len < self.len() {
             // The Vec "promotable" vtables do not store the capacity,
             // so we cannot truncate while using this repr. We *have* to
             // promote using `split_off` so the capacity can be stored.
-            if self.vtable as *const Vtable == &PROMOTABLE_EVEN_VTABLE
-                || self.vtable as *const Vtable == &PROMOTABLE_ODD_VTABLE
-            {
+            if self.is_promotable() {
                 drop(self.split_off(len));
             } else {
-                self.len = len;
+                self.set_len(len);
             }
         }
     }
@@ -504,19 +544,115 @@ impl Bytes {
         }
     }
 
+    /// Creates a `Bytes` that keeps `owner` alive until the last handle
+    /// referencing its bytes is dropped.
+    ///
+    /// `owner.as_ref()` must return the same slice every time it is called
+    /// and the slice must not move while `owner` is alive, since handles
+    /// keep pointing into it after the owner has been boxed.
+    #[cfg(any(all(feature = "mmap", unix), feature = "bytemuck"))]
+    pub(crate) fn from_owner<T>(owner: T) -> Bytes
+    where
+        T: AsRef<[u8]> + 'static,
+    {
+        let owned = Box::into_raw(Box::new(Owned {
+            lifetime: OwnedLifetime {
+                ref_cnt: Cell::new(1),
+                drop: owned_box_and_drop::<T>,
+            },
+            owner,
+        }));
+
+        let buf = unsafe { (*owned).owner.as_ref() };
+        Bytes {
+            ptr: buf.as_ptr(),
+            len: buf.len(),
+            data: UnsafeCell::new(owned.cast()),
+            vtable: &OWNED_VTABLE,
+        }
+    }
+
     // private
 
     #[inline]
     fn as_slice(&self) -> &[u8] {
-        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+        // Test the tag once, so that `Deref` of an out-of-line buffer only
+        // pays for a well predicted branch.
+        unsafe {
+            if self.is_inline() {
+                slice::from_raw_parts(self.inline_ptr(), self.len())
+            } else {
+                slice::from_raw_parts(self.ptr, self.len)
+            }
+        }
     }
 
     #[inline]
     unsafe fn inc_start(&mut self, by: usize) {
         // should already be asserted, but debug assert for tests
-        debug_assert!(self.len >= by, "internal: inc_start out of bounds");
-        self.len -= by;
-        self.ptr = self.ptr.add(by);
+        debug_assert!(self.len() >= by, "internal: inc_start out of bounds");
+        let len = self.len() - by;
+        if self.is_inline() {
+            let buf = self.inline_mut_ptr();
+            ptr::copy(buf.add(by), buf, len);
+        } else {
+            self.ptr = self.ptr.add(by);
+        }
+        self.set_len(len);
+    }
+
+    /// Sets the length, keeping the inline tag of an inline buffer.
+    #[inline]
+    fn set_len(&mut self, len: usize) {
+        if self.is_inline() {
+            debug_assert!(len <= INLINE_CAP, "internal: inline overflow");
+            self.len = (self.len & !(INLINE_LEN_MASK << INLINE_LEN_SHIFT))
+                | (len << INLINE_LEN_SHIFT);
+        } else {
+            self.len = len;
+        }
+    }
+
+    fn inline(src: &[u8]) -> Bytes {
+        debug_assert!(src.len() <= INLINE_CAP, "internal: inline overflow");
+        let mut bytes = Bytes {
+            ptr: ptr::null(),
+            data: UnsafeCell::new(ptr::null_mut()),
+            len: INLINE_TAG | (src.len() << INLINE_LEN_SHIFT),
+            vtable: &INLINE_VTABLE,
+        };
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), bytes.inline_mut_ptr(), src.len());
+        }
+        bytes
+    }
+
+    #[inline]
+    pub(crate) fn is_inline(&self) -> bool {
+        self.len & INLINE_TAG != 0
+    }
+
+    #[inline]
+    fn inline_ptr(&self) -> *const u8 {
+        unsafe { (self as *const Bytes as *const u8).add(INLINE_OFFSET) }
+    }
+
+    #[inline]
+    fn inline_mut_ptr(&mut self) -> *mut u8 {
+        unsafe { (self as *mut Bytes as *mut u8).add(INLINE_OFFSET) }
+    }
+
+    #[inline]
+    fn is_promotable(&self) -> bool {
+        ptr::eq(self.vtable, &PROMOTABLE_EVEN_VTABLE)
+            || ptr::eq(self.vtable, &PROMOTABLE_ODD_VTABLE)
+    }
+
+    /// Returns true if `self` holds a `Vec` that has not been promoted to
+    /// shared storage yet.
+    #[inline]
+    fn is_unshared_vec(&self) -> bool {
+        self.is_promotable() && unsafe { *self.data.get() } as usize & KIND_MASK == KIND_VEC
     }
 }
 
@@ -529,7 +665,11 @@ impl Drop for Bytes {
 
 impl Clone for Bytes {
     #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
     fn clone(&self) -> Bytes {
+        #[cfg(feature = "track")]
+        let _caller = crate::track::enter(core::panic::Location::caller());
+
         unsafe { (self.vtable.clone)(&self.data, self.ptr, self.len) }
     }
 }
@@ -559,6 +699,7 @@ impl Buf for Bytes {
         }
     }
 
+    #[cfg_attr(feature = "track", track_caller)]
     fn copy_to_bytes(&mut self, len: usize) -> crate::Bytes {
         if len == self.remaining() {
             core::mem::replace(self, Bytes::new())
@@ -808,6 +949,10 @@ impl From<&'static str> for Bytes {
 
 impl From<Vec<u8>> for Bytes {
     fn from(vec: Vec<u8>) -> Bytes {
+        if !vec.is_empty() && vec.len() <= INLINE_CAP {
+            return Bytes::inline(&vec);
+        }
+
         let slice = vec.into_boxed_slice();
         slice.into()
     }
@@ -890,6 +1035,51 @@ unsafe fn static_drop(_: &mut UnsafeCell<*mut ()>, _: *const u8, _: usize) {
     // nothing to drop for &'static [u8]
 }
 
+// ===== impl InlineVtable =====
+
+/// The number of bytes that fit in `ptr`, `data` and `len`, less the byte
+/// holding the tag and the length.
+const INLINE_CAP: usize = 3 * mem::size_of::<usize>() - 1;
+
+/// Set in `len` for an inline buffer.
+const INLINE_TAG: usize = !(usize::MAX >> 1);
+
+/// Position and width of the inline length within `len`, below the tag.
+const INLINE_LEN_SHIFT: usize = (mem::size_of::<usize>() - 1) * 8;
+const INLINE_LEN_MASK: usize = 0x7f;
+
+/// Offset of the inline contents from the start of `Bytes`; on big endian
+/// targets the tag byte comes first.
+#[cfg(target_endian = "little")]
+const INLINE_OFFSET: usize = 0;
+#[cfg(target_endian = "big")]
+const INLINE_OFFSET: usize = 1;
+
+static INLINE_VTABLE: Vtable = Vtable {
+    clone: inline_clone,
+    to_vec: inline_to_vec,
+    drop: inline_drop,
+};
+
+// The `ptr`, `data` and `len` arguments carry the inline contents, rebuilding
+// the struct from them copies the contents along.
+unsafe fn inline_clone(data: &UnsafeCell<*mut ()>, ptr: *const u8, len: usize) -> Bytes {
+    Bytes {
+        ptr,
+        data: UnsafeCell::new(*data.get()),
+        len,
+        vtable: &INLINE_VTABLE,
+    }
+}
+
+unsafe fn inline_to_vec(data: &UnsafeCell<*mut ()>, ptr: *const u8, len: usize) -> Vec<u8> {
+    inline_clone(data, ptr, len).as_slice().to_vec()
+}
+
+unsafe fn inline_drop(_: &mut UnsafeCell<*mut ()>, _: *const u8, _: usize) {
+    // the contents live in the handle itself
+}
+
 // ===== impl PromotableVtable =====
 
 static PROMOTABLE_EVEN_VTABLE: Vtable = Vtable {
@@ -936,6 +1126,9 @@ unsafe fn promotable_to_vec(
 
         let cap = (ptr as usize - buf as usize) + len;
 
+        #[cfg(feature = "track")]
+        crate::track::release(buf);
+
         // Copy back buffer
         ptr::copy(ptr, buf, len);
 
@@ -993,6 +1186,10 @@ unsafe fn promotable_odd_drop(data: &mut UnsafeCell<*mut ()>, ptr: *const u8, le
 
 unsafe fn free_boxed_slice(buf: *mut u8, offset: *const u8, len: usize) {
     let cap = (offset as usize - buf as usize) + len;
+
+    #[cfg(feature = "track")]
+    crate::track::release(buf);
+
     dealloc(buf, Layout::from_size_align(cap, 1).unwrap())
 }
 
@@ -1045,6 +1242,9 @@ unsafe fn shared_to_vec_impl(shared: *mut Shared, ptr: *const u8, len: usize) ->
         let buf = (*shared).buf;
         let cap = (*shared).cap;
 
+        #[cfg(feature = "track")]
+        crate::track::release(buf);
+
         // Deallocate Shared
         drop(Box::from_raw(shared as *mut mem::ManuallyDrop<Shared>));
 
@@ -1120,6 +1320,9 @@ unsafe fn shallow_clone_vec(
         "internal: Box<Shared> should have an aligned pointer",
     );
 
+    #[cfg(feature = "track")]
+    crate::track::promote(buf, (*shared).cap, Some(&(*shared).ref_cnt));
+
     
     // Try compare & swapping the pointer into the `rc` field.
     // `Release` is used synchronize with other threads that
@@ -1143,10 +1346,87 @@ unsafe fn release_shared(ptr: *mut Shared) {
         return;
     }
 
+    #[cfg(feature = "track")]
+    crate::track::release((*ptr).buf);
+
     // Drop the data
     drop(Box::from_raw(ptr));
 }
 
+// ===== impl OwnedVtable =====
+
+// `Owned<T>` is `#[repr(C)]` so that the vtable can reach the header without
+// knowing `T`; the type-specific part is only needed to free the box.
+#[cfg(any(all(feature = "mmap", unix), feature = "bytemuck"))]
+#[repr(C)]
+struct OwnedLifetime {
+    ref_cnt: Cell<usize>,
+    drop: unsafe fn(*mut ()),
+}
+
+#[cfg(any(all(feature = "mmap", unix), feature = "bytemuck"))]
+#[repr(C)]
+struct Owned<T> {
+    lifetime: OwnedLifetime,
+    owner: T,
+}
+
+#[cfg(any(all(feature = "mmap", unix), feature = "bytemuck"))]
+unsafe fn owned_box_and_drop<T>(ptr: *mut ()) {
+    drop(Box::from_raw(ptr as *mut Owned<T>));
+}
+
+#[cfg(any(all(feature = "mmap", unix), feature = "bytemuck"))]
+static OWNED_VTABLE: Vtable = Vtable {
+    clone: owned_clone,
+    to_vec: owned_to_vec,
+    drop: owned_drop,
+};
+
+#[cfg(any(all(feature = "mmap", unix), feature = "bytemuck"))]
+unsafe fn owned_clone(data: &UnsafeCell<*mut ()>, ptr: *const u8, len: usize) -> Bytes {
+    let owned = *data.get();
+    let lifetime = &*(owned as *const OwnedLifetime);
+    let old_size = lifetime.ref_cnt.get();
+    lifetime.ref_cnt.set(old_size + 1);
+
+    if old_size > usize::MAX >> 1 {
+        crate::abort();
+    }
+    Bytes {
+        ptr,
+        len,
+        data: UnsafeCell::new(owned),
+        vtable: &OWNED_VTABLE,
+    }
+}
+
+#[cfg(any(all(feature = "mmap", unix), feature = "bytemuck"))]
+unsafe fn owned_to_vec(data: &UnsafeCell<*mut ()>, ptr: *const u8, len: usize) -> Vec<u8> {
+    // The owner's memory can't be handed to a `Vec`, so always copy.
+    let v = slice::from_raw_parts(ptr, len).to_vec();
+    release_owned(*data.get());
+    v
+}
+
+#[cfg(any(all(feature = "mmap", unix), feature = "bytemuck"))]
+unsafe fn owned_drop(data: &mut UnsafeCell<*mut ()>, _ptr: *const u8, _len: usize) {
+    release_owned(*data.get());
+}
+
+#[cfg(any(all(feature = "mmap", unix), feature = "bytemuck"))]
+unsafe fn release_owned(owned: *mut ()) {
+    let lifetime = &*(owned as *const OwnedLifetime);
+    let refs = lifetime.ref_cnt.get();
+    lifetime.ref_cnt.set(refs - 1);
+
+    if refs != 1 {
+        return;
+    }
+
+    (lifetime.drop)(owned);
+}
+
 // Ideally we would always use this version of `ptr_map` since it is strict
 // provenance compatible, but it results in worse codegen. We will however still
 // use it on miri because it gives better diagnostics for people who test bytes
@@ -1204,7 +1484,7 @@ mod fuzz {
 
     #[test]
     fn bytes_cloning_vec() {
-        let a = Bytes::from(b"abcdefgh".to_vec());
+        let a = Bytes::from(b"abcdefghijklmnopqrstuvwxyz".to_vec());
         let addr = a.as_ptr() as usize;
 
         // test the Bytes::clone is Sync by putting it in an Arc
@@ -25,8 +25,8 @@
 // SOFTWARE.
 use core::cell::{Cell, UnsafeCell};
 use core::iter::{FromIterator, Iterator};
-use core::mem::{self, ManuallyDrop};
-use core::ops::{Deref, DerefMut};
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::{Bound, Deref, DerefMut, RangeBounds};
 use core::ptr::{self, NonNull};
 use core::{cmp, fmt, hash, isize, slice, usize};
 
@@ -38,7 +38,7 @@ use alloc::{
     vec::Vec,
 };
 
-use crate::buf::{IntoIter, UninitSlice};
+use crate::buf::{assert_written_len, IntoIter, UninitSlice};
 use crate::bytes::Vtable;
 use crate::{Buf, BufMut, Bytes};
 
@@ -99,10 +99,59 @@ pub struct BytesMut {
 // other shenanigans to make it work.
 struct Shared {
     vec: Vec<u8>,
-    original_capacity_repr: usize,
+    original_capacity: usize,
+    growth: GrowthPolicy,
     ref_count: Cell<usize>,
 }
 
+/// Decides how much capacity a [`BytesMut`] allocates when it has to grow.
+///
+/// The policy is chosen when the buffer is created with
+/// [`BytesMut::with_growth_policy`] and is inherited by every handle split
+/// off from it. It only decides the size of new allocations; reclaiming space
+/// in a uniquely owned buffer (see [`BytesMut::reserve`]) happens regardless
+/// of the policy.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::{BytesMut, GrowthPolicy};
+///
+/// let mut buf = BytesMut::with_growth_policy(0, GrowthPolicy::Doubling);
+/// buf.extend_from_slice(&[0; 100]);
+/// assert_eq!(buf.capacity(), 128);
+///
+/// let other = buf.split_off(50);
+/// assert_eq!(other.growth_policy(), GrowthPolicy::Doubling);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GrowthPolicy {
+    /// Allocate exactly the requested capacity, like `Vec::reserve_exact`.
+    ///
+    /// This avoids over-allocation for large buffers whose final size is
+    /// known, at the cost of reallocating on every growing `reserve`.
+    Exact,
+    /// Round every new allocation up to the next power of two.
+    ///
+    /// Capacities move through power-of-two size classes, which suits
+    /// buffers that are handed back to a pool, and repeated growth at least
+    /// doubles the capacity.
+    Doubling,
+    /// Double a uniquely owned buffer, and give a buffer that has to be
+    /// copied out of shared storage at least the capacity it was originally
+    /// created with.
+    ///
+    /// The original capacity only counts if it is at least `min` bytes, and
+    /// it is capped at `max` bytes. This is the default policy, with `min`
+    /// of 1 KiB and `max` of 64 KiB.
+    OriginalCapacity {
+        /// Original capacities below this are ignored.
+        min: usize,
+        /// Original capacities above this are capped to it.
+        max: usize,
+    },
+}
+
 // Buffer storage strategy flags.
 const KIND_ARC: usize = 0b0;
 const KIND_VEC: usize = 0b1;
@@ -162,8 +211,14 @@ impl BytesMut {
     /// assert_eq!(&bytes[..], b"hello world");
     /// ```
     #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn with_capacity(capacity: usize) -> BytesMut {
-        BytesMut::from_vec(Vec::with_capacity(capacity))
+        let buf = BytesMut::from_vec(Vec::with_capacity(capacity));
+
+        #[cfg(feature = "track")]
+        crate::track::create(buf.ptr.as_ptr(), buf.cap);
+
+        buf
     }
 
     /// Creates a new `BytesMut` with default capacity.
@@ -190,6 +245,60 @@ impl BytesMut {
         BytesMut::with_capacity(0)
     }
 
+    /// Creates a new `BytesMut` with the specified capacity and growth
+    /// policy.
+    ///
+    /// The policy decides how much capacity is allocated whenever the buffer
+    /// has to grow, and is inherited by every `BytesMut` split off from the
+    /// returned one. [`BytesMut::with_capacity`] uses
+    /// `GrowthPolicy::default()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::{BytesMut, GrowthPolicy};
+    ///
+    /// let mut buf = BytesMut::with_growth_policy(16, GrowthPolicy::Exact);
+    /// buf.extend_from_slice(&[0; 16]);
+    ///
+    /// buf.reserve(100);
+    /// assert_eq!(buf.capacity(), 116);
+    /// ```
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn with_growth_policy(capacity: usize, policy: GrowthPolicy) -> BytesMut {
+        let mut buf = BytesMut::with_capacity(capacity);
+
+        if policy != GrowthPolicy::default() {
+            // The vec representation has no room to store a policy, so a
+            // buffer with a custom one always lives in `Shared` storage.
+            unsafe {
+                buf.promote_to_shared(/*ref_count = */ 1);
+                (*buf.data).original_capacity = capacity;
+                (*buf.data).growth = policy;
+            }
+        }
+
+        buf
+    }
+
+    /// Returns the growth policy of this `BytesMut`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::{BytesMut, GrowthPolicy};
+    ///
+    /// let buf = BytesMut::with_capacity(64);
+    /// assert_eq!(buf.growth_policy(), GrowthPolicy::default());
+    /// ```
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        if self.kind() == KIND_VEC {
+            GrowthPolicy::default()
+        } else {
+            unsafe { (*self.data).growth }
+        }
+    }
+
     /// Returns the number of bytes contained in this `BytesMut`.
     ///
     /// # Examples
@@ -257,6 +366,7 @@ impl BytesMut {
     /// 
     /// ```
     #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn freeze(mut self) -> Bytes {
         if self.kind() == KIND_VEC {
             // Just re-use `Bytes` internal Vec vtable
@@ -264,13 +374,37 @@ impl BytesMut {
                 let (off, _) = self.get_vec_pos();
                 let vec = rebuild_vec(self.ptr.as_ptr(), self.len, self.cap, off);
                 mem::forget(self);
-                let mut b: Bytes = vec.into();
+
+                #[cfg(feature = "track")]
+                let (old, size) = (vec.as_ptr(), vec.len());
+
+                // Skip the inline representation, freezing must not copy.
+                let mut b: Bytes = vec.into_boxed_slice().into();
+
+                #[cfg(feature = "track")]
+                {
+                    // Converting to a boxed slice may have shrunk the
+                    // allocation in place or moved it.
+                    crate::track::moved(old, b.as_ptr(), size);
+                    crate::track::promote(b.as_ptr(), size, None);
+                }
+
                 b.advance(off);
                 b
             }
         } else {
             debug_assert_eq!(self.kind(), KIND_ARC);
 
+            #[cfg(feature = "track")]
+            unsafe {
+                let shared = &*self.data;
+                crate::track::promote(
+                    shared.vec.as_ptr(),
+                    shared.vec.capacity(),
+                    Some(&shared.ref_count),
+                );
+            }
+
             let ptr = self.ptr.as_ptr();
             let len = self.len;
             let data = UnsafeCell::new(self.data.cast());
@@ -322,6 +456,7 @@ impl BytesMut {
     ///
     /// Panics if `at > capacity`.
     #[must_use = "consider BytesMut::truncate if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn split_off(&mut self, at: usize) -> BytesMut {
         assert!(
             at <= self.capacity(),
@@ -363,6 +498,7 @@ impl BytesMut {
     /// assert_eq!(other, b"hello world"[..]);
     /// ```
     #[must_use = "consider BytesMut::advance(len()) if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn split(&mut self) -> BytesMut {
         let len = self.len();
         self.split_to(len)
@@ -395,6 +531,7 @@ impl BytesMut {
     ///
     /// Panics if `at > len`.
     #[must_use = "consider BytesMut::advance if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn split_to(&mut self, at: usize) -> BytesMut {
         assert!(
             at <= self.len(),
@@ -528,7 +665,8 @@ impl BytesMut {
     /// into the given `BytesMut`.
     ///
     /// More than `additional` bytes may be reserved in order to avoid frequent
-    /// reallocations. A call to `reserve` may result in an allocation.
+    /// reallocations; how many is decided by the buffer's [`GrowthPolicy`]. A
+    /// call to `reserve` may result in an allocation.
     ///
     /// Before allocating new buffer space, the function will attempt to reclaim
     /// space in the existing buffer. If the current handle references a view
@@ -583,6 +721,7 @@ impl BytesMut {
     ///
     /// Panics if the new capacity overflows `usize`.
     #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
     pub fn reserve(&mut self, additional: usize) {
         let len = self.len();
         let rem = self.capacity() - len;
@@ -593,12 +732,126 @@ impl BytesMut {
             return;
         }
 
-        self.reserve_inner(additional);
+        self.reserve_inner(additional, false);
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more bytes to
+    /// be inserted into the given `BytesMut`.
+    ///
+    /// This behaves like [`reserve`], including reclaiming space in a buffer
+    /// that is no longer shared, except that a new allocation is sized for
+    /// exactly the requested capacity instead of following the buffer's
+    /// [`GrowthPolicy`]. If the current handle shares its storage with other
+    /// handles, the data is copied into a new allocation of its own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"hello"[..]);
+    /// buf.reserve_exact(64);
+    /// assert!(buf.capacity() >= 69);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// [`reserve`]: #method.reserve
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        if additional <= self.capacity() - self.len() {
+            return;
+        }
+
+        self.reserve_inner(additional, true);
+    }
+
+    /// Shrinks the capacity of the buffer as much as possible.
+    ///
+    /// If this handle is the only one referencing its storage, the contents
+    /// are moved to the front of the allocation and the allocation is shrunk
+    /// to fit them, giving back the space before and after the current view.
+    /// Storage that is still shared with other handles can't be resized, so
+    /// in that case this does nothing; the memory is freed once the last
+    /// handle is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::with_capacity(1024);
+    /// buf.extend_from_slice(b"hello world");
+    ///
+    /// buf.shrink_to_fit();
+    /// assert_eq!(buf.capacity(), 11);
+    /// assert_eq!(&buf[..], b"hello world");
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let len = self.len();
+
+        if self.kind() == KIND_VEC {
+            unsafe {
+                let (off, prev) = self.get_vec_pos();
+                if off == 0 && self.cap == len {
+                    return;
+                }
+
+                let base_ptr = self.ptr.as_ptr().offset(-(off as isize));
+                ptr::copy(self.ptr.as_ptr(), base_ptr, len);
+
+                let mut v = ManuallyDrop::new(Vec::from_raw_parts(base_ptr, len, self.cap + off));
+
+                #[cfg(feature = "track")]
+                let old = v.as_ptr();
+
+                v.shrink_to_fit();
+
+                #[cfg(feature = "track")]
+                crate::track::moved(old, v.as_ptr(), v.capacity());
+
+                self.ptr = vptr(v.as_mut_ptr());
+                self.cap = v.capacity();
+                self.set_vec_pos(0, prev);
+            }
+            return;
+        }
+
+        debug_assert_eq!(self.kind(), KIND_ARC);
+
+        unsafe {
+            let shared = self.data;
+            if !(*shared).is_unique() {
+                return;
+            }
+
+            let v = &mut (*shared).vec;
+            if v.as_ptr() == self.ptr.as_ptr() && v.capacity() == len {
+                return;
+            }
+
+            ptr::copy(self.ptr.as_ptr(), v.as_mut_ptr(), len);
+            v.set_len(len);
+
+            #[cfg(feature = "track")]
+            let old = v.as_ptr();
+
+            v.shrink_to_fit();
+
+            #[cfg(feature = "track")]
+            crate::track::moved(old, v.as_ptr(), v.capacity());
+
+            self.ptr = vptr(v.as_mut_ptr());
+            self.cap = v.capacity();
+        }
     }
 
     // In separate function to allow the short-circuits in `reserve` to
     // be inline-able. Significant helps performance.
-    fn reserve_inner(&mut self, additional: usize) {
+    #[cfg_attr(feature = "track", track_caller)]
+    fn reserve_inner(&mut self, additional: usize, exact: bool) {
         let len = self.len();
         let kind = self.kind();
 
@@ -647,7 +900,18 @@ impl BytesMut {
                     // allocate more space!
                     let mut v =
                         ManuallyDrop::new(rebuild_vec(self.ptr.as_ptr(), self.len, self.cap, off));
-                    v.reserve(additional);
+
+                    #[cfg(feature = "track")]
+                    let old = v.as_ptr();
+
+                    if exact {
+                        v.reserve_exact(additional);
+                    } else {
+                        v.reserve(additional);
+                    }
+
+                    #[cfg(feature = "track")]
+                    crate::track::moved(old, v.as_ptr(), v.capacity());
 
                     // Update the info
                     self.ptr = vptr(v.as_mut_ptr().add(off));
@@ -669,11 +933,12 @@ impl BytesMut {
         let mut new_cap = len.checked_add(additional).expect("overflow");
 
         let original_capacity;
-        let original_capacity_repr;
+        let growth;
 
         unsafe {
-            original_capacity_repr = (*shared).original_capacity_repr;
-            original_capacity = original_capacity_from_repr(original_capacity_repr);
+            original_capacity = (*shared).original_capacity;
+            growth = (*shared).growth;
+            let policy = if exact { GrowthPolicy::Exact } else { growth };
 
             // First, try to reclaim the buffer. This is possible if the current
             // handle is the only outstanding handle pointing to the buffer.
@@ -713,19 +978,22 @@ impl BytesMut {
                     new_cap = new_cap.checked_add(off).expect("overflow");
 
                     // The vector capacity is not sufficient. The reserve request is
-                    // asking for more than the initial buffer capacity. Allocate more
-                    // than requested if `new_cap` is not much bigger than the current
-                    // capacity.
-                    //
-                    // There are some situations, using `reserve_exact` that the
-                    // buffer capacity could be below `original_capacity`, so do a
-                    // check.
-                    let double = v.capacity().checked_shl(1).unwrap_or(new_cap);
+                    // asking for more than the initial buffer capacity. Let the
+                    // growth policy decide how much more to allocate.
+                    new_cap = policy.grown_capacity(v.capacity(), new_cap);
 
-                    new_cap = cmp::max(double, new_cap);
+                    #[cfg(feature = "track")]
+                    let old = v.as_ptr();
+
+                    // The length of `Shared::vec` isn't kept up to date, but
+                    // reallocating only preserves the first `v.len()` bytes.
+                    v.set_len(off + len);
 
                     // No space - allocate more
-                    v.reserve(new_cap - v.len());
+                    v.reserve_exact(new_cap - v.len());
+
+                    #[cfg(feature = "track")]
+                    crate::track::moved(old, v.as_ptr(), v.capacity());
 
                     // Update the info
                     self.ptr = vptr(v.as_mut_ptr().add(off));
@@ -734,7 +1002,7 @@ impl BytesMut {
 
                 return;
             } else {
-                new_cap = cmp::max(new_cap, original_capacity);
+                new_cap = policy.fresh_capacity(new_cap, original_capacity);
             }
         }
 
@@ -749,11 +1017,37 @@ impl BytesMut {
         unsafe { release_shared(shared) };
 
         // Update self
-        let data = (original_capacity_repr << ORIGINAL_CAPACITY_OFFSET) | KIND_VEC;
-        self.data = invalid_ptr(data);
         self.ptr = vptr(v.as_mut_ptr());
         self.len = v.len();
         self.cap = v.capacity();
+
+        if growth == GrowthPolicy::default() {
+            #[cfg(feature = "track")]
+            crate::track::create(v.as_ptr(), v.capacity());
+
+            let original_capacity_repr = original_capacity_to_repr(original_capacity);
+            let data = (original_capacity_repr << ORIGINAL_CAPACITY_OFFSET) | KIND_VEC;
+            self.data = invalid_ptr(data);
+        } else {
+            // Keep a custom policy around for the new storage.
+            let shared = Box::into_raw(Box::new(Shared {
+                vec: ManuallyDrop::into_inner(v),
+                original_capacity,
+                growth,
+                ref_count: Cell::new(1),
+            }));
+
+            #[cfg(feature = "track")]
+            unsafe {
+                crate::track::promote(
+                    (*shared).vec.as_ptr(),
+                    (*shared).vec.capacity(),
+                    Some(&(*shared).ref_count),
+                );
+            }
+
+            self.data = shared;
+        }
     }
 
     /// Appends given bytes to this `BytesMut`.
@@ -789,6 +1083,113 @@ impl BytesMut {
         }
     }
 
+    /// Writes up to `max_len` bytes in place, at the end of the buffer.
+    ///
+    /// The buffer first reserves room for `max_len` more bytes. `f` is then
+    /// handed those bytes, zeroed, and returns how many of them it wrote,
+    /// counted from the start. The length of the buffer grows by that many
+    /// bytes, and the count is returned.
+    ///
+    /// This is the safe counterpart of writing into
+    /// [`spare_capacity_mut`] and calling [`commit`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    /// use std::io::Write;
+    ///
+    /// let mut buf = BytesMut::new();
+    /// buf.write_with(16, |mut dst| {
+    ///     let len = dst.len();
+    ///     write!(dst, "{}-{}", 12, 34).unwrap();
+    ///     len - dst.len()
+    /// });
+    ///
+    /// assert_eq!(&buf[..], b"12-34");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns more than `max_len`.
+    ///
+    /// [`spare_capacity_mut`]: #method.spare_capacity_mut
+    /// [`commit`]: #method.commit
+    pub fn write_with<F>(&mut self, max_len: usize, f: F) -> usize
+    where
+        F: FnOnce(&mut [u8]) -> usize,
+    {
+        self.reserve(max_len);
+        let n = f(self.uninit_slice()[..max_len].zeroed());
+        assert_written_len(n, max_len);
+        // Safety: the first `max_len` bytes of the spare capacity were zeroed.
+        unsafe { self.set_len(self.len + n) };
+        n
+    }
+
+    /// Returns the spare capacity of the buffer, the bytes between its length
+    /// and its capacity, as a slice of `MaybeUninit<u8>`.
+    ///
+    /// The returned slice can be filled with data and then marked as part of
+    /// the buffer with [`commit`]. Call [`reserve`] first to make sure there
+    /// is enough spare capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// let mut buf = BytesMut::with_capacity(8);
+    ///
+    /// let spare = buf.spare_capacity_mut();
+    /// for (dst, src) in spare.iter_mut().zip(b"hi") {
+    ///     *dst = MaybeUninit::new(*src);
+    /// }
+    ///
+    /// unsafe { buf.commit(2) };
+    /// assert_eq!(&buf[..], b"hi");
+    /// ```
+    ///
+    /// [`commit`]: #method.commit
+    /// [`reserve`]: #method.reserve
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        // Safety: writing uninitialized bytes into the spare capacity is fine,
+        // only `commit` makes them part of the buffer.
+        unsafe { self.uninit_slice().as_uninit_slice_mut() }
+    }
+
+    /// Appends the first `n` bytes of the spare capacity to the buffer.
+    ///
+    /// Unlike [`set_len`], this checks that `n` fits into the spare capacity.
+    /// [`write_with`] does the same without `unsafe`, at the cost of zeroing
+    /// the bytes it hands out.
+    ///
+    /// # Safety
+    ///
+    /// The first `n` bytes of [`spare_capacity_mut`] must have been
+    /// initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the spare capacity.
+    ///
+    /// [`set_len`]: #method.set_len
+    /// [`write_with`]: #method.write_with
+    /// [`spare_capacity_mut`]: #method.spare_capacity_mut
+    #[inline]
+    pub unsafe fn commit(&mut self, n: usize) {
+        let spare = self.cap - self.len;
+        assert!(
+            n <= spare,
+            "cannot commit past the spare capacity: {:?} <= {:?}",
+            n,
+            spare
+        );
+        self.len += n;
+    }
+
     /// Absorbs a `BytesMut` that was previously split off.
     ///
     /// If the two `BytesMut` objects were previously contiguous and not mutated
@@ -824,6 +1225,198 @@ impl BytesMut {
         }
     }
 
+    /// Inserts `src` at position `at`, shifting all bytes after it to the
+    /// right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"hello world"[..]);
+    /// buf.insert_slice(5, b",");
+    /// assert_eq!(&buf[..], b"hello, world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn insert_slice(&mut self, at: usize, src: &[u8]) {
+        let len = self.len();
+        assert!(
+            at <= len,
+            "insertion index out of bounds: {:?} <= {:?}",
+            at,
+            len,
+        );
+
+        let cnt = src.len();
+        self.reserve(cnt);
+
+        unsafe {
+            let dst = self.ptr.as_ptr().add(at);
+            // Reserved above, the shifted tail and `src` fit.
+            ptr::copy(dst, dst.add(cnt), len - at);
+            ptr::copy_nonoverlapping(src.as_ptr(), dst, cnt);
+            self.set_len(len + cnt);
+        }
+    }
+
+    /// Removes the bytes in `range`, shifting all bytes after it to the left.
+    ///
+    /// Existing capacity is preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"hello, world"[..]);
+    /// buf.remove_range(5..7);
+    /// assert_eq!(&buf[..], b"helloworld");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is greater than its
+    /// end.
+    pub fn remove_range(&mut self, range: impl RangeBounds<usize>) {
+        let len = self.len();
+        let (begin, end) = range_bounds(range, len);
+
+        self.as_slice_mut().copy_within(end.., begin);
+        self.truncate(len - (end - begin));
+    }
+
+    /// Removes the bytes in `range` and returns them as a `Bytes`.
+    ///
+    /// The removed bytes are copied out, so the buffer keeps its
+    /// representation and capacity. Use [`split_to`] or [`split_off`] to
+    /// remove the front or the back without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"GET /index.html HTTP/1.1"[..]);
+    /// let path = buf.drain(4..15);
+    ///
+    /// assert_eq!(path, "/index.html");
+    /// assert_eq!(&buf[..], b"GET  HTTP/1.1");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is greater than its
+    /// end.
+    ///
+    /// [`split_to`]: #method.split_to
+    /// [`split_off`]: #method.split_off
+    pub fn drain(&mut self, range: impl RangeBounds<usize>) -> Bytes {
+        let (begin, end) = range_bounds(range, self.len());
+
+        let removed = Bytes::copy_from_slice(&self[begin..end]);
+        self.remove_range(begin..end);
+        removed
+    }
+
+    /// Replaces the bytes in `range` with `src`, which may have a different
+    /// length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"Host: example.com\r\n"[..]);
+    /// buf.splice(6..17, b"example.org:8080");
+    /// assert_eq!(&buf[..], b"Host: example.org:8080\r\n");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is greater than its
+    /// end.
+    pub fn splice(&mut self, range: impl RangeBounds<usize>, src: &[u8]) {
+        let len = self.len();
+        let (begin, end) = range_bounds(range, len);
+        let new_len = len - (end - begin) + src.len();
+
+        if new_len > len {
+            self.reserve(new_len - len);
+        }
+
+        unsafe {
+            let ptr = self.ptr.as_ptr();
+            // Reserved above, the shifted tail and `src` fit.
+            ptr::copy(ptr.add(end), ptr.add(begin + src.len()), len - end);
+            ptr::copy_nonoverlapping(src.as_ptr(), ptr.add(begin), src.len());
+            self.set_len(new_len);
+        }
+    }
+
+    /// Retains only the bytes for which `f` returns `true`, keeping their
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"a-b-c"[..]);
+    /// buf.retain(|&b| b != b'-');
+    /// assert_eq!(&buf[..], b"abc");
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&u8) -> bool,
+    {
+        let buf = self.as_slice_mut();
+        let mut kept = 0;
+
+        for i in 0..buf.len() {
+            let b = buf[i];
+            if f(&b) {
+                buf[kept] = b;
+                kept += 1;
+            }
+        }
+
+        self.truncate(kept);
+    }
+
+    /// Appends a copy of the bytes in `src` to the end of the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"abc-"[..]);
+    /// buf.extend_from_within(..3);
+    /// assert_eq!(&buf[..], b"abc-abc");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is greater than its
+    /// end.
+    pub fn extend_from_within(&mut self, src: impl RangeBounds<usize>) {
+        let len = self.len();
+        let (begin, end) = range_bounds(src, len);
+        let cnt = end - begin;
+
+        self.reserve(cnt);
+
+        unsafe {
+            let ptr = self.ptr.as_ptr();
+            // Reserved above, and the copy goes past the end of `src`.
+            ptr::copy_nonoverlapping(ptr.add(begin), ptr.add(len), cnt);
+            self.set_len(len + cnt);
+        }
+    }
+
     // private
 
     // For now, use a `Vec` to manage the memory for us, but we may want to
@@ -937,6 +1530,7 @@ impl BytesMut {
         self.data as usize & KIND_MASK
     }
 
+    #[cfg_attr(feature = "track", track_caller)]
     unsafe fn promote_to_shared(&mut self, ref_cnt: usize) {
         debug_assert_eq!(self.kind(), KIND_VEC);
         debug_assert!(ref_cnt == 1 || ref_cnt == 2);
@@ -957,7 +1551,8 @@ impl BytesMut {
         // vector.
         let shared = Box::new(Shared {
             vec: rebuild_vec(self.ptr.as_ptr(), self.len, self.cap, off),
-            original_capacity_repr,
+            original_capacity: original_capacity_from_repr(original_capacity_repr),
+            growth: GrowthPolicy::default(),
             ref_count: Cell::new(ref_cnt),
         });
 
@@ -967,6 +1562,13 @@ impl BytesMut {
         // always succeed.
         debug_assert_eq!(shared as usize & KIND_MASK, KIND_ARC);
 
+        #[cfg(feature = "track")]
+        crate::track::promote(
+            (*shared).vec.as_ptr(),
+            (*shared).vec.capacity(),
+            Some(&(*shared).ref_count),
+        );
+
         self.data = shared;
     }
 
@@ -977,6 +1579,7 @@ impl BytesMut {
     /// be sure the returned value to the user doesn't allow
     /// two views into the same range.
     #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
     unsafe fn shallow_clone(&mut self) -> BytesMut {
         if self.kind() == KIND_ARC {
             increment_shared(self.data);
@@ -1003,8 +1606,67 @@ impl BytesMut {
         self.data = invalid_ptr((pos << VEC_POS_OFFSET) | (prev & NOT_VEC_POS_MASK));
     }
 
+    // Makes room for `additional` more bytes without allocating, by taking
+    // back space in front of and behind the current view of a uniquely owned
+    // allocation. Returns `false`, leaving `self` unchanged, if that isn't
+    // enough.
+    pub(crate) fn try_reclaim(&mut self, additional: usize) -> bool {
+        let len = self.len();
+        if additional <= self.capacity() - len {
+            return true;
+        }
+
+        let needed = match len.checked_add(additional) {
+            Some(needed) => needed,
+            None => return false,
+        };
+
+        if self.kind() == KIND_VEC {
+            unsafe {
+                let (off, prev) = self.get_vec_pos();
+                if self.cap + off < needed {
+                    return false;
+                }
+
+                let base_ptr = self.ptr.as_ptr().offset(-(off as isize));
+                ptr::copy(self.ptr.as_ptr(), base_ptr, len);
+                self.ptr = vptr(base_ptr);
+                self.set_vec_pos(0, prev);
+                self.cap += off;
+            }
+            return true;
+        }
+
+        debug_assert_eq!(self.kind(), KIND_ARC);
+
+        unsafe {
+            let shared = self.data;
+            if !(*shared).is_unique() {
+                return false;
+            }
+
+            let v = &mut (*shared).vec;
+            let v_capacity = v.capacity();
+            if v_capacity < needed {
+                return false;
+            }
+
+            let ptr = v.as_mut_ptr();
+            let offset = offset_from(self.ptr.as_ptr(), ptr);
+            if v_capacity - offset >= needed {
+                // The space behind the view is enough.
+                self.cap = v_capacity - offset;
+            } else {
+                ptr::copy(self.ptr.as_ptr(), ptr, len);
+                self.ptr = vptr(ptr);
+                self.cap = v_capacity;
+            }
+        }
+        true
+    }
+
     #[inline]
-    fn uninit_slice(&mut self) -> &mut UninitSlice {
+    pub(crate) fn uninit_slice(&mut self) -> &mut UninitSlice {
         unsafe {
             let ptr = self.ptr.as_ptr().add(self.len);
             let len = self.cap - self.len;
@@ -1022,6 +1684,9 @@ impl Drop for BytesMut {
             unsafe {
                 let (off, _) = self.get_vec_pos();
 
+                #[cfg(feature = "track")]
+                crate::track::release(self.ptr.as_ptr().offset(-(off as isize)));
+
                 // Vector storage, free the vector
                 let _ = rebuild_vec(self.ptr.as_ptr(), self.len, self.cap, off);
             }
@@ -1055,6 +1720,7 @@ impl Buf for BytesMut {
         }
     }
 
+    #[cfg_attr(feature = "track", track_caller)]
     fn copy_to_bytes(&mut self, len: usize) -> crate::Bytes {
         self.split_to(len).freeze()
     }
@@ -1105,6 +1771,14 @@ unsafe impl BufMut for BytesMut {
         self.extend_from_slice(src);
     }
 
+    fn put_with<F>(&mut self, max_len: usize, f: F) -> usize
+    where
+        F: FnOnce(&mut UninitSlice) -> usize,
+        Self: Sized,
+    {
+        self.write_with(max_len, |dst| f(UninitSlice::from_slice(dst)))
+    }
+
     fn put_bytes(&mut self, val: u8, cnt: usize) {
         self.reserve(cnt);
         unsafe {
@@ -1332,6 +2006,9 @@ unsafe fn release_shared(ptr: *mut Shared) {
         return;
     }
 
+    #[cfg(feature = "track")]
+    crate::track::release((*ptr).vec.as_ptr());
+
     // Drop the data
     drop(Box::from_raw(ptr));
 }
@@ -1352,6 +2029,45 @@ impl Shared {
     }
 }
 
+impl Default for GrowthPolicy {
+    fn default() -> GrowthPolicy {
+        GrowthPolicy::OriginalCapacity {
+            min: 1 << MIN_ORIGINAL_CAPACITY_WIDTH,
+            max: 1 << (MAX_ORIGINAL_CAPACITY_WIDTH - 1),
+        }
+    }
+}
+
+impl GrowthPolicy {
+    // The capacity of a new allocation that `required` bytes get copied into
+    // when the current storage can't be grown in place.
+    fn fresh_capacity(self, required: usize, original_capacity: usize) -> usize {
+        match self {
+            GrowthPolicy::Exact => required,
+            GrowthPolicy::Doubling => required.checked_next_power_of_two().unwrap_or(required),
+            GrowthPolicy::OriginalCapacity { min, max } => {
+                if original_capacity < min {
+                    required
+                } else {
+                    cmp::max(required, cmp::min(original_capacity, max))
+                }
+            }
+        }
+    }
+
+    // The new capacity of a uniquely owned `Vec` of `capacity` bytes that
+    // needs to hold at least `required` bytes.
+    fn grown_capacity(self, capacity: usize, required: usize) -> usize {
+        match self {
+            GrowthPolicy::Exact => required,
+            GrowthPolicy::Doubling => required.checked_next_power_of_two().unwrap_or(required),
+            GrowthPolicy::OriginalCapacity { .. } => {
+                cmp::max(capacity.checked_mul(2).unwrap_or(required), required)
+            }
+        }
+    }
+}
+
 #[inline]
 fn original_capacity_to_repr(cap: usize) -> usize {
     let width = PTR_WIDTH - ((cap >> MIN_ORIGINAL_CAPACITY_WIDTH).leading_zeros() as usize);
@@ -1583,7 +2299,12 @@ impl From<BytesMut> for Vec<u8> {
         let mut vec = if kind == KIND_VEC {
             unsafe {
                 let (off, _) = bytes.get_vec_pos();
-                rebuild_vec(bytes.ptr.as_ptr(), bytes.len, bytes.cap, off)
+                let vec = rebuild_vec(bytes.ptr.as_ptr(), bytes.len, bytes.cap, off);
+
+                #[cfg(feature = "track")]
+                crate::track::release(vec.as_ptr());
+
+                vec
             }
         } else if kind == KIND_ARC {
             let shared = bytes.data as *mut Shared;
@@ -1591,6 +2312,9 @@ impl From<BytesMut> for Vec<u8> {
             if unsafe { (*shared).is_unique() } {
                 let vec = mem::replace(unsafe { &mut (*shared).vec }, Vec::new());
 
+                #[cfg(feature = "track")]
+                crate::track::release(vec.as_ptr());
+
                 unsafe { release_shared(shared) };
 
                 vec
@@ -1614,6 +2338,35 @@ impl From<BytesMut> for Vec<u8> {
     }
 }
 
+fn range_bounds(range: impl RangeBounds<usize>, len: usize) -> (usize, usize) {
+    let begin = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.checked_add(1).expect("out of range"),
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n.checked_add(1).expect("out of range"),
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => len,
+    };
+
+    assert!(
+        begin <= end,
+        "range start must not be greater than end: {:?} <= {:?}",
+        begin,
+        end,
+    );
+    assert!(
+        end <= len,
+        "range end out of bounds: {:?} <= {:?}",
+        end,
+        len,
+    );
+
+    (begin, end)
+}
+
 #[inline]
 fn vptr(ptr: *mut u8) -> NonNull<u8> {
     if cfg!(debug_assertions) {
@@ -1684,6 +2437,10 @@ unsafe fn shared_v_to_vec(data: &UnsafeCell<*mut ()>, ptr: *const u8, len: usize
 
         // Drop shared
         let mut vec = mem::replace(&mut shared.vec, Vec::new());
+
+        #[cfg(feature = "track")]
+        crate::track::release(vec.as_ptr());
+
         release_shared(shared);
 
         // Copy back buffer
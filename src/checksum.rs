@@ -0,0 +1,246 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Checksums for verifying data.
+//!
+//! [`Crc32`], [`Crc32c`] and [`Adler32`] work without the standard library
+//! and implement [`Hasher`], so they plug into
+//! [`Buf::inspect_with`] and [`BufMut::checksummed`] to check bytes as they
+//! pass through a buffer. Any other `Hasher` works there too.
+//!
+//! Only [`Hasher::write`] feeds raw bytes. The integer methods such as
+//! [`Hasher::write_u32`] hash the native-endian representation of the value.
+//!
+//! # Examples
+//!
+//! ```
+//! use rcbytes::checksum::Crc32;
+//!
+//! let mut crc = Crc32::new();
+//! crc.update(b"1234");
+//! crc.update(b"56789");
+//! assert_eq!(crc.sum(), 0xcbf4_3926);
+//! assert_eq!(Crc32::checksum(b"123456789"), 0xcbf4_3926);
+//! ```
+//!
+//! [`Buf::inspect_with`]: crate::Buf::inspect_with
+//! [`BufMut::checksummed`]: crate::BufMut::checksummed
+
+use core::hash::Hasher;
+
+/// Builds the byte-at-a-time lookup table of a reflected CRC-32.
+const fn crc_table(poly: u32) -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+macro_rules! crc {
+    ($(#[$attr:meta])* $name:ident, $table:ident, $poly:expr, $example:expr) => {
+        static $table: [u32; 256] = crc_table($poly);
+
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct $name {
+            crc: u32,
+        }
+
+        impl $name {
+            /// Creates a checksum over no data.
+            #[inline]
+            pub const fn new() -> Self {
+                $name { crc: !0 }
+            }
+
+            /// Computes the checksum of `data` in one go.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            #[doc = concat!("use rcbytes::checksum::", stringify!($name), ";")]
+            ///
+            #[doc = concat!("assert_eq!(", stringify!($name), "::checksum(b\"123456789\"), ", $example, ");")]
+            /// ```
+            pub fn checksum(data: &[u8]) -> u32 {
+                let mut crc = Self::new();
+                crc.update(data);
+                crc.sum()
+            }
+
+            /// Feeds `data` into the checksum.
+            pub fn update(&mut self, data: &[u8]) {
+                let mut crc = self.crc;
+                for &b in data {
+                    crc = $table[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8);
+                }
+                self.crc = crc;
+            }
+
+            /// Returns the checksum of the data fed so far.
+            #[inline]
+            pub const fn sum(&self) -> u32 {
+                !self.crc
+            }
+
+            /// Resets the checksum to its initial state.
+            #[inline]
+            pub fn reset(&mut self) {
+                *self = Self::new();
+            }
+        }
+
+        impl Default for $name {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl Hasher for $name {
+            #[inline]
+            fn write(&mut self, bytes: &[u8]) {
+                self.update(bytes);
+            }
+
+            #[inline]
+            fn finish(&self) -> u64 {
+                self.sum() as u64
+            }
+        }
+    };
+}
+
+crc!(
+    /// CRC-32 as used by Ethernet, zlib, gzip and PNG (polynomial
+    /// `0x04c11db7`, reflected).
+    Crc32,
+    CRC32_TABLE,
+    0xedb8_8320,
+    "0xcbf4_3926"
+);
+
+crc!(
+    /// CRC-32C (Castagnoli) as used by iSCSI, SCTP and ext4 (polynomial
+    /// `0x1edc6f41`, reflected).
+    Crc32c,
+    CRC32C_TABLE,
+    0x82f6_3b78,
+    "0xe306_9283"
+);
+
+/// The largest number of bytes that can be summed before the running sums
+/// must be reduced modulo `ADLER_MOD` to stay within a `u32`.
+const ADLER_NMAX: usize = 5552;
+const ADLER_MOD: u32 = 65521;
+
+/// Adler-32 as used by zlib.
+///
+/// Faster than a CRC but weaker, especially on short inputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    /// Creates a checksum over no data.
+    #[inline]
+    pub const fn new() -> Self {
+        Adler32 { a: 1, b: 0 }
+    }
+
+    /// Computes the checksum of `data` in one go.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::checksum::Adler32;
+    ///
+    /// assert_eq!(Adler32::checksum(b"Wikipedia"), 0x11e6_0398);
+    /// ```
+    pub fn checksum(data: &[u8]) -> u32 {
+        let mut adler = Self::new();
+        adler.update(data);
+        adler.sum()
+    }
+
+    /// Feeds `data` into the checksum.
+    pub fn update(&mut self, data: &[u8]) {
+        let (mut a, mut b) = (self.a, self.b);
+        for chunk in data.chunks(ADLER_NMAX) {
+            for &byte in chunk {
+                a += byte as u32;
+                b += a;
+            }
+            a %= ADLER_MOD;
+            b %= ADLER_MOD;
+        }
+        self.a = a;
+        self.b = b;
+    }
+
+    /// Returns the checksum of the data fed so far.
+    #[inline]
+    pub const fn sum(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+
+    /// Resets the checksum to its initial state.
+    #[inline]
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for Adler32 {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Adler32 {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.sum() as u64
+    }
+}
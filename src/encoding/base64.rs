@@ -0,0 +1,338 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Base64 encoding, as specified in [RFC 4648].
+//!
+//! A [`Config`] selects the alphabet and whether output is padded with `=`.
+//! The four common variants are provided as constants.
+//!
+//! # Examples
+//!
+//! ```
+//! use rcbytes::encoding::base64::{STANDARD, URL_SAFE_NO_PAD};
+//! use rcbytes::{Buf, BytesMut};
+//!
+//! let data = (&b"\xfb\xff"[..]).chain(&b"\xbf"[..]);
+//!
+//! let mut text = BytesMut::new();
+//! STANDARD.encode_to(data, &mut text);
+//! assert_eq!(text, "+/+/");
+//!
+//! assert_eq!(URL_SAFE_NO_PAD.encode(&b"\xfb\xff\xbf"[..]), "-_-_");
+//! assert_eq!(STANDARD.decode(b"aGk=").unwrap(), "hi");
+//! ```
+//!
+//! [RFC 4648]: https://tools.ietf.org/html/rfc4648
+
+use core::fmt;
+
+use super::DecodeError;
+use crate::{Buf, BufMut, Bytes, BytesMut};
+
+const STANDARD_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+const INVALID: u8 = 0xff;
+const PAD: u8 = b'=';
+
+const STANDARD_DECODE: [u8; 256] = decode_table(STANDARD_ALPHABET);
+const URL_SAFE_DECODE: [u8; 256] = decode_table(URL_SAFE_ALPHABET);
+
+/// The standard alphabet with padding.
+pub const STANDARD: Config = Config {
+    encode: STANDARD_ALPHABET,
+    decode: &STANDARD_DECODE,
+    pad: true,
+};
+
+/// The standard alphabet without padding.
+pub const STANDARD_NO_PAD: Config = Config {
+    encode: STANDARD_ALPHABET,
+    decode: &STANDARD_DECODE,
+    pad: false,
+};
+
+/// The URL and filename safe alphabet with padding.
+pub const URL_SAFE: Config = Config {
+    encode: URL_SAFE_ALPHABET,
+    decode: &URL_SAFE_DECODE,
+    pad: true,
+};
+
+/// The URL and filename safe alphabet without padding.
+pub const URL_SAFE_NO_PAD: Config = Config {
+    encode: URL_SAFE_ALPHABET,
+    decode: &URL_SAFE_DECODE,
+    pad: false,
+};
+
+/// A base64 variant: an alphabet and a padding mode.
+///
+/// Decoding with a padded config requires the input to be padded, decoding
+/// with an unpadded one rejects `=`. In both cases the unused bits of the
+/// last symbol must be zero, so every input has exactly one encoding.
+#[derive(Clone, Copy)]
+pub struct Config {
+    encode: &'static [u8; 64],
+    decode: &'static [u8; 256],
+    pad: bool,
+}
+
+impl Config {
+    /// Returns true if encoded output is padded with `=`.
+    pub fn is_padded(&self) -> bool {
+        self.pad
+    }
+
+    /// Returns the number of bytes `len` input bytes encode to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::encoding::base64::{STANDARD, STANDARD_NO_PAD};
+    ///
+    /// assert_eq!(STANDARD.encoded_len(4), 8);
+    /// assert_eq!(STANDARD_NO_PAD.encoded_len(4), 6);
+    /// ```
+    pub fn encoded_len(&self, len: usize) -> usize {
+        let full = len / 3 * 4;
+        match len % 3 {
+            0 => full,
+            _ if self.pad => full + 4,
+            rem => full + rem + 1,
+        }
+    }
+
+    /// Writes the encoding of `src` into `dst`.
+    ///
+    /// `src` is consumed; pass `&mut buf` to keep the buffer, or a clone of
+    /// it to keep its position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` does not have room for
+    /// `self.encoded_len(src.remaining())` bytes.
+    pub fn encode_to<B: Buf, M: BufMut>(&self, mut src: B, dst: &mut M) {
+        let mut out = [0; 128];
+        let mut filled = 0;
+        // Input bytes carried over from the end of the previous chunk.
+        let mut carry = [0; 3];
+        let mut carried = 0;
+
+        while src.has_remaining() {
+            let chunk = src.chunk();
+            let len = chunk.len();
+
+            for &byte in chunk {
+                carry[carried] = byte;
+                carried += 1;
+
+                if carried == 3 {
+                    self.encode_block(&carry, &mut out[filled..filled + 4]);
+                    carried = 0;
+                    filled += 4;
+                    if filled == out.len() {
+                        dst.put_slice(&out);
+                        filled = 0;
+                    }
+                }
+            }
+
+            src.advance(len);
+        }
+
+        if carried > 0 {
+            let mut last = [0; 4];
+            carry[carried..].iter_mut().for_each(|b| *b = 0);
+            self.encode_block(&carry, &mut last);
+
+            let used = carried + 1;
+            if self.pad {
+                last[used..].iter_mut().for_each(|b| *b = PAD);
+                out[filled..filled + 4].copy_from_slice(&last);
+                filled += 4;
+            } else {
+                out[filled..filled + used].copy_from_slice(&last[..used]);
+                filled += used;
+            }
+        }
+
+        dst.put_slice(&out[..filled]);
+    }
+
+    /// Returns the encoding of `src`.
+    pub fn encode<B: Buf>(&self, src: B) -> Bytes {
+        let mut dst = BytesMut::with_capacity(self.encoded_len(src.remaining()));
+        self.encode_to(src, &mut dst);
+        dst.freeze()
+    }
+
+    /// Decodes `src` into `dst`.
+    ///
+    /// On error, `dst` holds the bytes decoded before the offending input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` does not have room for the decoded bytes.
+    pub fn decode_to<B: Buf, M: BufMut>(&self, mut src: B, dst: &mut M) -> Result<(), DecodeError> {
+        let mut out = [0; 96];
+        let mut filled = 0;
+        let mut quad = [0; 4];
+        let mut symbols = 0;
+        let mut pads = 0;
+        let mut index = 0;
+        let mut last = 0;
+
+        let result = 'outer: loop {
+            if !src.has_remaining() {
+                break Ok(());
+            }
+
+            let chunk = src.chunk();
+            let len = chunk.len();
+
+            for &byte in chunk {
+                if byte == PAD {
+                    // Padding may only complete a block that has at least
+                    // two symbols.
+                    if !self.pad || symbols < 2 || symbols + pads == 4 {
+                        break 'outer Err(DecodeError::InvalidPadding);
+                    }
+                    pads += 1;
+                } else {
+                    let value = self.decode[byte as usize];
+                    if value == INVALID || pads > 0 {
+                        break 'outer Err(DecodeError::InvalidByte { index, byte });
+                    }
+
+                    quad[symbols] = value;
+                    symbols += 1;
+                    last = index;
+
+                    if symbols == 4 {
+                        decode_block(&quad, &mut out[filled..filled + 3]);
+                        symbols = 0;
+                        filled += 3;
+                        if filled == out.len() {
+                            dst.put_slice(&out);
+                            filled = 0;
+                        }
+                    }
+                }
+                index += 1;
+            }
+
+            src.advance(len);
+        };
+
+        let result = result.and_then(|()| {
+            if symbols == 0 {
+                return Ok(());
+            }
+            if symbols == 1 {
+                return Err(DecodeError::InvalidLength { len: index });
+            }
+            if self.pad && symbols + pads != 4 {
+                return Err(DecodeError::InvalidPadding);
+            }
+
+            // The bits of the last symbol beyond the final byte must be
+            // zero.
+            let unused = if symbols == 2 { 0x0f } else { 0x03 };
+            if quad[symbols - 1] & unused != 0 {
+                let byte = self.encode[quad[symbols - 1] as usize];
+                return Err(DecodeError::InvalidByte { index: last, byte });
+            }
+
+            quad[symbols..].iter_mut().for_each(|v| *v = 0);
+            let mut tail = [0; 3];
+            decode_block(&quad, &mut tail);
+            out[filled..filled + symbols - 1].copy_from_slice(&tail[..symbols - 1]);
+            filled += symbols - 1;
+            Ok(())
+        });
+
+        dst.put_slice(&out[..filled]);
+        result
+    }
+
+    /// Decodes `src`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::encoding::base64::{STANDARD, URL_SAFE_NO_PAD};
+    /// use rcbytes::encoding::DecodeError;
+    ///
+    /// assert_eq!(URL_SAFE_NO_PAD.decode(b"-_8").unwrap(), &b"\xfb\xff"[..]);
+    /// assert_eq!(STANDARD.decode(b"-_8="), Err(DecodeError::InvalidByte { index: 0, byte: b'-' }));
+    /// ```
+    pub fn decode(&self, src: &[u8]) -> Result<Bytes, DecodeError> {
+        let mut dst = BytesMut::with_capacity(src.len() / 4 * 3 + 2);
+        self.decode_to(src, &mut dst)?;
+        Ok(dst.freeze())
+    }
+
+    #[inline]
+    fn encode_block(&self, src: &[u8; 3], dst: &mut [u8]) {
+        let n = (src[0] as u32) << 16 | (src[1] as u32) << 8 | src[2] as u32;
+        dst[0] = self.encode[(n >> 18) as usize & 0x3f];
+        dst[1] = self.encode[(n >> 12) as usize & 0x3f];
+        dst[2] = self.encode[(n >> 6) as usize & 0x3f];
+        dst[3] = self.encode[n as usize & 0x3f];
+    }
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let alphabet = if self.encode == URL_SAFE_ALPHABET {
+            "url-safe"
+        } else {
+            "standard"
+        };
+        f.debug_struct("Config")
+            .field("alphabet", &alphabet)
+            .field("pad", &self.pad)
+            .finish()
+    }
+}
+
+#[inline]
+fn decode_block(src: &[u8; 4], dst: &mut [u8]) {
+    let n = (src[0] as u32) << 18 | (src[1] as u32) << 12 | (src[2] as u32) << 6 | src[3] as u32;
+    dst[0] = (n >> 16) as u8;
+    dst[1] = (n >> 8) as u8;
+    dst[2] = n as u8;
+}
+
+const fn decode_table(alphabet: &[u8; 64]) -> [u8; 256] {
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+    while i < alphabet.len() {
+        table[alphabet[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
@@ -0,0 +1,282 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Hexadecimal encoding.
+//!
+//! # Examples
+//!
+//! ```
+//! use rcbytes::encoding::hex;
+//! use rcbytes::{Buf, BytesMut};
+//!
+//! let data = (&b"\xde\xad"[..]).chain(&b"\xbe\xef"[..]);
+//!
+//! let mut text = BytesMut::new();
+//! hex::encode_to(data, &mut text);
+//! assert_eq!(text, "deadbeef");
+//!
+//! let bytes = hex::decode(&text).unwrap();
+//! assert_eq!(bytes, rcbytes::hex!("de ad be ef"));
+//! ```
+
+use super::DecodeError;
+use crate::{Buf, BufMut, Bytes, BytesMut};
+
+const LOWER: &[u8; 16] = b"0123456789abcdef";
+const UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Writes the lowercase hex encoding of `src` into `dst`.
+///
+/// `src` is consumed; pass `&mut buf` to keep the buffer, or a clone of it
+/// to keep its position.
+///
+/// # Panics
+///
+/// Panics if `dst` does not have room for `2 * src.remaining()` bytes.
+pub fn encode_to<B: Buf, M: BufMut>(src: B, dst: &mut M) {
+    encode_with(src, dst, LOWER);
+}
+
+/// Writes the uppercase hex encoding of `src` into `dst`.
+///
+/// # Panics
+///
+/// Panics if `dst` does not have room for `2 * src.remaining()` bytes.
+pub fn encode_upper_to<B: Buf, M: BufMut>(src: B, dst: &mut M) {
+    encode_with(src, dst, UPPER);
+}
+
+/// Returns the lowercase hex encoding of `src`.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::encoding::hex;
+///
+/// assert_eq!(hex::encode(&b"\x01\xab"[..]), "01ab");
+/// ```
+pub fn encode<B: Buf>(src: B) -> Bytes {
+    let mut dst = BytesMut::with_capacity(src.remaining() * 2);
+    encode_to(src, &mut dst);
+    dst.freeze()
+}
+
+fn encode_with<B: Buf, M: BufMut>(mut src: B, dst: &mut M, digits: &[u8; 16]) {
+    let mut out = [0; 128];
+
+    while src.has_remaining() {
+        let chunk = src.chunk();
+        let n = core::cmp::min(chunk.len(), out.len() / 2);
+
+        for (pair, &b) in out.chunks_exact_mut(2).zip(&chunk[..n]) {
+            pair[0] = digits[(b >> 4) as usize];
+            pair[1] = digits[(b & 0x0f) as usize];
+        }
+
+        dst.put_slice(&out[..2 * n]);
+        src.advance(n);
+    }
+}
+
+/// Decodes the hex digits of `src` into `dst`.
+///
+/// Both lowercase and uppercase digits are accepted.
+///
+/// On error, `dst` holds the bytes decoded before the offending input.
+///
+/// # Panics
+///
+/// Panics if `dst` does not have room for the decoded bytes.
+pub fn decode_to<B: Buf, M: BufMut>(mut src: B, dst: &mut M) -> Result<(), DecodeError> {
+    let mut out = [0; 64];
+    let mut filled = 0;
+    let mut high = None;
+    let mut index = 0;
+
+    while src.has_remaining() {
+        let chunk = src.chunk();
+        let len = chunk.len();
+
+        for &byte in chunk {
+            let nibble = match digit(byte) {
+                Some(nibble) => nibble,
+                None => {
+                    dst.put_slice(&out[..filled]);
+                    return Err(DecodeError::InvalidByte { index, byte });
+                }
+            };
+
+            match high.take() {
+                None => high = Some(nibble),
+                Some(high) => {
+                    out[filled] = high << 4 | nibble;
+                    filled += 1;
+                    if filled == out.len() {
+                        dst.put_slice(&out);
+                        filled = 0;
+                    }
+                }
+            }
+            index += 1;
+        }
+
+        src.advance(len);
+    }
+
+    dst.put_slice(&out[..filled]);
+
+    if high.is_some() {
+        return Err(DecodeError::InvalidLength { len: index });
+    }
+
+    Ok(())
+}
+
+/// Decodes the hex digits of `src`.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::encoding::{hex, DecodeError};
+///
+/// assert_eq!(hex::decode(b"01AB").unwrap(), &b"\x01\xab"[..]);
+/// assert_eq!(
+///     hex::decode(b"0g"),
+///     Err(DecodeError::InvalidByte { index: 1, byte: b'g' }),
+/// );
+/// ```
+pub fn decode(src: &[u8]) -> Result<Bytes, DecodeError> {
+    let mut dst = BytesMut::with_capacity(src.len() / 2);
+    decode_to(src, &mut dst)?;
+    Ok(dst.freeze())
+}
+
+#[inline]
+const fn digit(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[inline]
+const fn is_separator(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'_')
+}
+
+// Backs the `hex!` macro, not public API. `panic!` in a const fn needs Rust
+// 1.57, so malformed input indexes past a one element array holding the
+// message instead. That fails const evaluation, and the error points at the
+// message.
+
+#[doc(hidden)]
+pub const fn const_decoded_len(src: &[u8]) -> usize {
+    let mut digits = 0;
+    let mut i = 0;
+    while i < src.len() {
+        if !is_separator(src[i]) {
+            digits += 1;
+        }
+        i += 1;
+    }
+
+    let _ = ["hex! needs an even number of hex digits"][digits % 2];
+    digits / 2
+}
+
+/// Decodes the next byte of `src` from `pos` on, and returns it along with
+/// the position after its second digit.
+#[doc(hidden)]
+pub const fn const_decode_next(src: &[u8], mut pos: usize) -> (u8, usize) {
+    let mut byte = 0;
+    let mut digits = 0;
+    while digits < 2 {
+        let c = src[pos];
+        pos += 1;
+
+        if is_separator(c) {
+            continue;
+        }
+
+        let (nibble, invalid) = match digit(c) {
+            Some(nibble) => (nibble, 0),
+            None => (0, 1),
+        };
+        let _ = ["hex! found a byte that is not a hex digit"][invalid];
+
+        byte = byte << 4 | nibble;
+        digits += 1;
+    }
+    (byte, pos)
+}
+
+/// Decodes a string literal of hex digits into a [`Bytes`] at compile time.
+///
+/// Whitespace and `_` may be used to group the digits. The bytes live in a
+/// `static`, so the returned `Bytes` is created with [`Bytes::from_static`]
+/// and never allocates. Malformed input fails to compile.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::hex;
+///
+/// let vector = hex!("
+///     00010203 04050607
+///     deadbeef
+/// ");
+/// assert_eq!(vector, &b"\x00\x01\x02\x03\x04\x05\x06\x07\xde\xad\xbe\xef"[..]);
+/// ```
+///
+/// ```compile_fail
+/// let _ = rcbytes::hex!("abc");
+/// ```
+///
+/// ```compile_fail
+/// let _ = rcbytes::hex!("0g");
+/// ```
+///
+/// [`Bytes`]: crate::Bytes
+/// [`Bytes::from_static`]: crate::Bytes::from_static
+#[macro_export]
+macro_rules! hex {
+    ($s:expr) => {{
+        const __HEX_SRC: &[u8] = $s.as_bytes();
+        const __HEX_LEN: usize = $crate::encoding::hex::const_decoded_len(__HEX_SRC);
+        static __HEX_BYTES: [u8; __HEX_LEN] = {
+            let mut out = [0; __HEX_LEN];
+            let mut pos = 0;
+            let mut i = 0;
+            while i < __HEX_LEN {
+                let (byte, next) = $crate::encoding::hex::const_decode_next(__HEX_SRC, pos);
+                out[i] = byte;
+                pos = next;
+                i += 1;
+            }
+            out
+        };
+        $crate::Bytes::from_static(&__HEX_BYTES)
+    }};
+}
@@ -0,0 +1,81 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Encodings for binary data.
+//!
+//! [`hex`] and [`base64`] turn binary data into text, and [`varint`] packs
+//! integers into as few bytes as their value needs.
+//!
+//! The encoders read from any [`Buf`] and write into any [`BufMut`], one
+//! chunk at a time, so non-contiguous buffers such as [`Chain`] are encoded
+//! without being flattened first. The decoders do the same in the other
+//! direction and also have shorthands that return a [`Bytes`].
+//!
+//! [`Buf`]: crate::Buf
+//! [`BufMut`]: crate::BufMut
+//! [`Chain`]: crate::buf::Chain
+//! [`Bytes`]: crate::Bytes
+
+use core::fmt;
+
+pub mod base64;
+pub mod hex;
+pub mod varint;
+
+/// The error returned when decoding malformed [`hex`] or [`base64`] input.
+///
+/// Indices count bytes from the start of the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The byte at `index` is not part of the alphabet, or is a symbol
+    /// that cannot end the input.
+    InvalidByte {
+        /// Position of the byte in the input.
+        index: usize,
+        /// The offending byte.
+        byte: u8,
+    },
+    /// The input ended in the middle of an encoded unit.
+    InvalidLength {
+        /// Length of the input.
+        len: usize,
+    },
+    /// Padding is missing, misplaced, or not allowed.
+    InvalidPadding,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DecodeError::InvalidByte { index, byte } => {
+                write!(f, "invalid byte {:#04x} at index {}", byte, index)
+            }
+            DecodeError::InvalidLength { len } => write!(f, "invalid input length {}", len),
+            DecodeError::InvalidPadding => f.write_str("invalid padding"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
@@ -0,0 +1,135 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Variable-length integers.
+//!
+//! Unsigned integers use LEB128: seven bits per byte, least significant group
+//! first, with the high bit set on every byte but the last. Small values take
+//! a single byte and a `u64` takes at most [`MAX_LEN`] bytes. Signed integers
+//! are zigzag mapped first, so small negative values stay short as well.
+//!
+//! # Examples
+//!
+//! ```
+//! use rcbytes::encoding::varint;
+//! use rcbytes::BytesMut;
+//!
+//! let mut buf = BytesMut::new();
+//! varint::encode_to(300, &mut buf);
+//! varint::encode_to(varint::zigzag_encode(-2), &mut buf);
+//! assert_eq!(buf, &b"\xac\x02\x03"[..]);
+//!
+//! let mut src = &buf[..];
+//! assert_eq!(varint::decode(&mut src), Ok(300));
+//! assert_eq!(varint::decode(&mut src).map(varint::zigzag_decode), Ok(-2));
+//! ```
+
+use crate::{Buf, BufMut};
+use core::fmt;
+
+/// The error returned by [`decode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VarintError {
+    /// The input ended in the middle of a varint.
+    UnexpectedEof,
+    /// A varint does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for VarintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            VarintError::UnexpectedEof => f.write_str("unexpected end of input"),
+            VarintError::Overflow => f.write_str("varint overflows a u64"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VarintError {}
+
+/// The longest encoding of a `u64`, in bytes.
+pub const MAX_LEN: usize = 10;
+
+/// Returns the number of bytes [`encode_to`] writes for `value`.
+pub const fn encoded_len(value: u64) -> usize {
+    // One byte per started group of seven bits, and one for zero.
+    let bits = 64 - (value | 1).leading_zeros() as usize;
+    (bits + 6) / 7
+}
+
+/// Writes `value` into `dst`.
+///
+/// # Panics
+///
+/// Panics if `dst` does not have room for [`encoded_len(value)`] bytes.
+///
+/// [`encoded_len(value)`]: encoded_len
+pub fn encode_to<M: BufMut>(mut value: u64, dst: &mut M) {
+    let mut buf = [0u8; MAX_LEN];
+    let mut n = 0;
+    while value >= 0x80 {
+        buf[n] = value as u8 | 0x80;
+        value >>= 7;
+        n += 1;
+    }
+    buf[n] = value as u8;
+    dst.put_slice(&buf[..=n]);
+}
+
+/// Reads a value from the front of `src`, advancing past it.
+///
+/// Returns [`VarintError::UnexpectedEof`] if `src` ends inside the value and
+/// [`VarintError::Overflow`] if it does not fit in a `u64`. `src` may have
+/// been partially advanced when an error is returned.
+pub fn decode<B: Buf>(src: &mut B) -> Result<u64, VarintError> {
+    let mut value = 0;
+    for i in 0..MAX_LEN {
+        if !src.has_remaining() {
+            return Err(VarintError::UnexpectedEof);
+        }
+        let byte = src.get_u8();
+        // The tenth byte only has room for the top bit of a `u64`.
+        if i == MAX_LEN - 1 && byte > 1 {
+            return Err(VarintError::Overflow);
+        }
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte < 0x80 {
+            return Ok(value);
+        }
+    }
+    Err(VarintError::Overflow)
+}
+
+/// Maps a signed integer onto an unsigned one, so that values close to zero
+/// in either direction encode to few bytes: 0, -1, 1, -2, ... become
+/// 0, 1, 2, 3, ...
+pub const fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Reverses [`zigzag_encode`].
+pub const fn zigzag_decode(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
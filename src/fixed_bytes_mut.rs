@@ -0,0 +1,449 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use crate::buf::UninitSlice;
+use crate::{Buf, BufMut, Bytes, BytesMut};
+
+use core::ops::{Deref, DerefMut};
+use core::{fmt, ptr};
+
+/// The error returned by the fallible methods of [`FixedBytesMut`] when there
+/// is not enough spare capacity.
+///
+/// Nothing is written to the buffer when this error is returned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CapacityError {
+    /// The number of bytes the write needed.
+    pub requested: usize,
+    /// The number of bytes of spare capacity that were available.
+    pub available: usize,
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "not enough capacity in fixed buffer (requested {} but only {} available)",
+            self.requested, self.available
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {}
+
+/// A unique reference to a contiguous slice of memory whose capacity is fixed
+/// at creation.
+///
+/// `FixedBytesMut` works like a [`BytesMut`] that never reallocates. Its
+/// [`remaining_mut`] is the spare capacity that is actually left, and writing
+/// more than that through [`BufMut`] panics, just like writing past the end
+/// of a `&mut [u8]`. The `try_put_*` methods return a [`CapacityError`]
+/// instead.
+///
+/// Reading through [`Buf`] or splitting off the front of the buffer uses up
+/// the space in front of the remaining bytes. It becomes writable again
+/// through [`try_reserve_in_place`] once no other handle refers to it.
+///
+/// [`remaining_mut`]: BufMut::remaining_mut
+/// [`try_reserve_in_place`]: FixedBytesMut::try_reserve_in_place
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::{BufMut, FixedBytesMut};
+///
+/// let mut buf = FixedBytesMut::with_capacity(8);
+/// buf.put_u32(0x0102_0304);
+/// assert_eq!(buf.remaining_mut(), 4);
+///
+/// assert!(buf.try_put_u64(0).is_err());
+/// assert_eq!(buf.capacity(), 8);
+///
+/// let frame = buf.split().freeze();
+/// assert_eq!(frame, &b"\x01\x02\x03\x04"[..]);
+/// ```
+pub struct FixedBytesMut {
+    inner: BytesMut,
+}
+
+macro_rules! try_put_impl {
+    ($($name:ident, $put:ident($ty:ty) => $conv:ident;)*) => {$(
+        #[doc = concat!(
+            "Like [`BufMut::", stringify!($put), "`], but returns an error ",
+            "instead of panicking if there is not enough spare capacity.",
+        )]
+        #[inline]
+        pub fn $name(&mut self, n: $ty) -> Result<(), CapacityError> {
+            self.try_put_slice(&n.$conv())
+        }
+    )*};
+}
+
+impl FixedBytesMut {
+    /// Creates an empty `FixedBytesMut` that can hold exactly `capacity`
+    /// bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::FixedBytesMut;
+    ///
+    /// let buf = FixedBytesMut::with_capacity(64);
+    /// assert_eq!(buf.capacity(), 64);
+    /// assert!(buf.is_empty());
+    /// ```
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn with_capacity(capacity: usize) -> FixedBytesMut {
+        FixedBytesMut {
+            inner: BytesMut::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of bytes contained in this `FixedBytesMut`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the `FixedBytesMut` has a length of 0.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns `true` if there is no spare capacity left.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.inner.len() == self.inner.capacity()
+    }
+
+    /// Returns the number of bytes the `FixedBytesMut` can hold without
+    /// reclaiming space.
+    ///
+    /// This starts out as the capacity the buffer was created with, and
+    /// shrinks as the front of the buffer is consumed or split off.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Shortens the buffer, keeping the first `len` bytes and dropping the
+    /// rest.
+    ///
+    /// If `len` is greater than the buffer's current length, this has no
+    /// effect.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.inner.truncate(len);
+    }
+
+    /// Clears the buffer, removing all data. Existing capacity is preserved.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    /// Makes sure that at least `additional` more bytes can be written
+    /// without allocating.
+    ///
+    /// If the spare capacity is too small, the space in front of the current
+    /// contents that has already been consumed is reclaimed, by moving the
+    /// contents to the start of the allocation. That is only possible once
+    /// every other handle referring to the allocation, e.g. a frozen frame
+    /// that was split off, has been dropped. If there still isn't enough
+    /// room, an error is returned and the buffer is left unchanged. This
+    /// never allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::{BufMut, FixedBytesMut};
+    ///
+    /// let mut buf = FixedBytesMut::with_capacity(8);
+    /// buf.put_slice(b"abcdefgh");
+    /// assert!(buf.try_reserve_in_place(1).is_err());
+    ///
+    /// let frame = buf.split_to(6);
+    /// assert!(buf.try_reserve_in_place(4).is_err());
+    ///
+    /// drop(frame);
+    /// buf.try_reserve_in_place(4).unwrap();
+    /// assert_eq!(buf.capacity(), 8);
+    /// assert_eq!(&buf[..], b"gh");
+    /// ```
+    pub fn try_reserve_in_place(&mut self, additional: usize) -> Result<(), CapacityError> {
+        if self.inner.try_reclaim(additional) {
+            Ok(())
+        } else {
+            Err(CapacityError {
+                requested: additional,
+                available: self.remaining_mut(),
+            })
+        }
+    }
+
+    /// Appends `src` to the buffer, or returns an error if there is not
+    /// enough spare capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::FixedBytesMut;
+    ///
+    /// let mut buf = FixedBytesMut::with_capacity(4);
+    /// buf.try_put_slice(b"abc").unwrap();
+    ///
+    /// let err = buf.try_put_slice(b"de").unwrap_err();
+    /// assert_eq!((err.requested, err.available), (2, 1));
+    /// assert_eq!(&buf[..], b"abc");
+    /// ```
+    pub fn try_put_slice(&mut self, src: &[u8]) -> Result<(), CapacityError> {
+        self.check_capacity(src.len())?;
+        self.put_slice(src);
+        Ok(())
+    }
+
+    /// Like [`BufMut::put_bytes`], but returns an error instead of panicking
+    /// if there is not enough spare capacity.
+    pub fn try_put_bytes(&mut self, val: u8, cnt: usize) -> Result<(), CapacityError> {
+        self.check_capacity(cnt)?;
+        self.put_bytes(val, cnt);
+        Ok(())
+    }
+
+    /// Like [`BufMut::put`], but returns an error instead of panicking if
+    /// there is not enough spare capacity.
+    ///
+    /// Nothing is consumed from `src` when an error is returned.
+    pub fn try_put<T: Buf>(&mut self, src: T) -> Result<(), CapacityError> {
+        self.check_capacity(src.remaining())?;
+        self.put(src);
+        Ok(())
+    }
+
+    try_put_impl! {
+        try_put_u8, put_u8(u8) => to_be_bytes;
+        try_put_i8, put_i8(i8) => to_be_bytes;
+        try_put_u16, put_u16(u16) => to_be_bytes;
+        try_put_u16_le, put_u16_le(u16) => to_le_bytes;
+        try_put_i16, put_i16(i16) => to_be_bytes;
+        try_put_i16_le, put_i16_le(i16) => to_le_bytes;
+        try_put_u32, put_u32(u32) => to_be_bytes;
+        try_put_u32_le, put_u32_le(u32) => to_le_bytes;
+        try_put_i32, put_i32(i32) => to_be_bytes;
+        try_put_i32_le, put_i32_le(i32) => to_le_bytes;
+        try_put_u64, put_u64(u64) => to_be_bytes;
+        try_put_u64_le, put_u64_le(u64) => to_le_bytes;
+        try_put_i64, put_i64(i64) => to_be_bytes;
+        try_put_i64_le, put_i64_le(i64) => to_le_bytes;
+        try_put_u128, put_u128(u128) => to_be_bytes;
+        try_put_u128_le, put_u128_le(u128) => to_le_bytes;
+        try_put_i128, put_i128(i128) => to_be_bytes;
+        try_put_i128_le, put_i128_le(i128) => to_le_bytes;
+        try_put_f32, put_f32(f32) => to_be_bytes;
+        try_put_f32_le, put_f32_le(f32) => to_le_bytes;
+        try_put_f64, put_f64(f64) => to_be_bytes;
+        try_put_f64_le, put_f64_le(f64) => to_le_bytes;
+    }
+
+    /// Removes the bytes from the current view, returning them in a new
+    /// `BytesMut` handle.
+    ///
+    /// Afterwards, `self` will be empty, but will retain any additional
+    /// capacity that it had before the operation. The returned handle shares
+    /// the allocation with `self`.
+    #[must_use = "consider FixedBytesMut::clear if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn split(&mut self) -> BytesMut {
+        self.inner.split()
+    }
+
+    /// Splits the buffer into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[at, len)`, and the returned
+    /// `BytesMut` contains elements `[0, at)`. The returned handle shares the
+    /// allocation with `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[must_use = "consider FixedBytesMut::advance if you don't need the other half"]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn split_to(&mut self, at: usize) -> BytesMut {
+        self.inner.split_to(at)
+    }
+
+    /// Converts `self` into an immutable `Bytes`.
+    #[inline]
+    #[cfg_attr(feature = "track", track_caller)]
+    pub fn freeze(self) -> Bytes {
+        self.inner.freeze()
+    }
+
+    /// Consumes `self`, returning the underlying `BytesMut`, which may grow
+    /// again.
+    #[inline]
+    pub fn into_inner(self) -> BytesMut {
+        self.inner
+    }
+
+    fn check_capacity(&self, requested: usize) -> Result<(), CapacityError> {
+        let available = self.remaining_mut();
+        if requested > available {
+            return Err(CapacityError {
+                requested,
+                available,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Buf for FixedBytesMut {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[inline]
+    fn chunk(&self) -> &[u8] {
+        &self.inner
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        self.inner.advance(cnt);
+    }
+
+    #[cfg_attr(feature = "track", track_caller)]
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        self.inner.copy_to_bytes(len)
+    }
+}
+
+unsafe impl BufMut for FixedBytesMut {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.inner.capacity() - self.inner.len()
+    }
+
+    #[inline]
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.inner.advance_mut(cnt);
+    }
+
+    #[inline]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        // Unlike `BytesMut`, never reserve more space when full.
+        self.inner.uninit_slice()
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        assert!(
+            src.len() <= self.remaining_mut(),
+            "buffer overflow; remaining = {}; src = {}",
+            self.remaining_mut(),
+            src.len()
+        );
+        // Safety: there is room for `src`, and `src` cannot overlap the
+        // exclusively borrowed buffer.
+        unsafe {
+            let dst = self.inner.uninit_slice().as_mut_ptr();
+            ptr::copy_nonoverlapping(src.as_ptr(), dst, src.len());
+            self.inner.advance_mut(src.len());
+        }
+    }
+
+    fn put_bytes(&mut self, val: u8, cnt: usize) {
+        assert!(
+            cnt <= self.remaining_mut(),
+            "buffer overflow; remaining = {}; cnt = {}",
+            self.remaining_mut(),
+            cnt
+        );
+        // Safety: there is room for `cnt` bytes.
+        unsafe {
+            let dst = self.inner.uninit_slice().as_mut_ptr();
+            ptr::write_bytes(dst, val, cnt);
+            self.inner.advance_mut(cnt);
+        }
+    }
+}
+
+impl Deref for FixedBytesMut {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl DerefMut for FixedBytesMut {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.inner
+    }
+}
+
+impl AsRef<[u8]> for FixedBytesMut {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl AsMut<[u8]> for FixedBytesMut {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl fmt::Debug for FixedBytesMut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl PartialEq for FixedBytesMut {
+    fn eq(&self, other: &FixedBytesMut) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl Eq for FixedBytesMut {}
+
+impl PartialEq<[u8]> for FixedBytesMut {
+    fn eq(&self, other: &[u8]) -> bool {
+        self[..] == *other
+    }
+}
+
+impl PartialEq<&[u8]> for FixedBytesMut {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self[..] == **other
+    }
+}
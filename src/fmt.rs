@@ -1,5 +1,10 @@
+//! Formatting helpers for buffers.
+
 mod debug;
 mod hex;
+mod hexdump;
+
+pub use self::hexdump::HexDump;
 
 /// `BytesRef` is not a part of public API of bytes crate.
-struct BytesRef<'a>(&'a [u8]);
\ No newline at end of file
+struct BytesRef<'a>(&'a [u8]);
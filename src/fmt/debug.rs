@@ -23,9 +23,11 @@
 // LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
-use core::fmt::{Debug, Formatter, Result};
+use core::fmt::{Debug, Display, Formatter, Result};
 
 use super::BytesRef;
+use super::HexDump;
+use crate::buf::ArrayBuf;
 use crate::{Bytes, BytesMut};
 
 /// Alternative implementation of `std::fmt::Debug` for byte slice.
@@ -63,12 +65,27 @@ impl Debug for BytesRef<'_> {
 
 impl Debug for Bytes {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if f.alternate() {
+            return Display::fmt(&self.hexdump(), f);
+        }
         Debug::fmt(&BytesRef(self.as_ref()), f)
     }
 }
 
 impl Debug for BytesMut {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if f.alternate() {
+            return Display::fmt(&self.hexdump(), f);
+        }
         Debug::fmt(&BytesRef(self.as_ref()), f)
     }
 }
+
+impl<const N: usize> Debug for ArrayBuf<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if f.alternate() {
+            return Display::fmt(&HexDump::new(&self[..]), f);
+        }
+        Debug::fmt(&BytesRef(self), f)
+    }
+}
@@ -0,0 +1,201 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use core::fmt::{self, Display, Formatter};
+
+use alloc::vec::Vec;
+
+use crate::{Buf, Bytes, BytesMut};
+
+/// Displays a buffer `xxd`-style: an offset, the bytes in hex grouped by
+/// two, and the printable ASCII characters.
+///
+/// ```text
+/// 00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 0001  Hello, world!...
+/// 00000010: 0203                                     ..
+/// ```
+///
+/// The buffer is read through a clone of it, so any `Buf + Clone`, such as
+/// [`Chain`] or `&[u8]`, can be displayed without being consumed.
+///
+/// `HexDump` is created by [`Bytes::hexdump`], [`BytesMut::hexdump`] or
+/// [`HexDump::new`]. The `{:#?}` format of `Bytes` and `BytesMut` also uses
+/// it.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::Bytes;
+///
+/// let packet = Bytes::from_static(b"\x00\x01GET / HTTP/1.1\r\n");
+///
+/// assert_eq!(
+///     packet.hexdump().width(8).to_string(),
+///     "00000000: 0001 4745 5420 2f20  ..GET / \n\
+///      00000008: 4854 5450 2f31 2e31  HTTP/1.1\n\
+///      00000010: 0d0a                 ..",
+/// );
+/// ```
+///
+/// [`Chain`]: crate::buf::Chain
+#[derive(Clone, Debug)]
+pub struct HexDump<B> {
+    buf: B,
+    width: usize,
+    max_len: usize,
+    offset: usize,
+}
+
+impl<B: Buf + Clone> HexDump<B> {
+    /// Creates a `HexDump` of the remaining bytes of `buf`.
+    pub fn new(buf: B) -> HexDump<B> {
+        HexDump {
+            buf,
+            width: 16,
+            max_len: usize::MAX,
+            offset: 0,
+        }
+    }
+
+    /// Sets the number of bytes per line. Defaults to 16.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is 0.
+    pub fn width(mut self, width: usize) -> HexDump<B> {
+        assert!(width > 0, "hexdump width must not be 0");
+        self.width = width;
+        self
+    }
+
+    /// Sets the maximum number of bytes to display.
+    ///
+    /// Any further bytes are summarized by a last line with their count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Bytes;
+    ///
+    /// let b = Bytes::from_static(b"hello world");
+    ///
+    /// assert_eq!(
+    ///     b.hexdump().max_len(4).to_string(),
+    ///     "00000000: 6865 6c6c                                hell\n\
+    ///      ... 7 more bytes",
+    /// );
+    /// ```
+    pub fn max_len(mut self, max_len: usize) -> HexDump<B> {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Sets the offset printed for the first byte. Defaults to 0.
+    ///
+    /// Useful when the buffer is a slice of a larger packet or file.
+    pub fn offset(mut self, offset: usize) -> HexDump<B> {
+        self.offset = offset;
+        self
+    }
+}
+
+impl<B: Buf + Clone> Display for HexDump<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut buf = self.buf.clone();
+        let total = buf.remaining();
+        let mut left = core::cmp::min(total, self.max_len);
+        let mut line = Vec::with_capacity(self.width);
+        let mut offset = self.offset;
+
+        while left > 0 {
+            line.clear();
+            while line.len() < self.width && left > 0 {
+                let chunk = buf.chunk();
+                let n = core::cmp::min(chunk.len(), core::cmp::min(self.width - line.len(), left));
+                line.extend_from_slice(&chunk[..n]);
+                buf.advance(n);
+                left -= n;
+            }
+
+            if offset != self.offset {
+                f.write_str("\n")?;
+            }
+            self.write_line(f, offset, &line)?;
+            offset += line.len();
+        }
+
+        let shown = offset - self.offset;
+        if shown < total {
+            if shown > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "... {} more bytes", total - shown)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<B> HexDump<B> {
+    fn write_line(&self, f: &mut Formatter<'_>, offset: usize, line: &[u8]) -> fmt::Result {
+        write!(f, "{:08x}:", offset)?;
+        for i in 0..self.width {
+            if i % 2 == 0 {
+                f.write_str(" ")?;
+            }
+            match line.get(i) {
+                Some(b) => write!(f, "{:02x}", b)?,
+                None => f.write_str("  ")?,
+            }
+        }
+
+        f.write_str("  ")?;
+        for &b in line {
+            let c = if (0x20..0x7f).contains(&b) {
+                b as char
+            } else {
+                '.'
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+impl Bytes {
+    /// Returns an adapter that displays `self` as a hexdump.
+    ///
+    /// See [`HexDump`] for the format and options.
+    pub fn hexdump(&self) -> HexDump<&[u8]> {
+        HexDump::new(self.as_ref())
+    }
+}
+
+impl BytesMut {
+    /// Returns an adapter that displays `self` as a hexdump.
+    ///
+    /// See [`HexDump`] for the format and options.
+    pub fn hexdump(&self) -> HexDump<&[u8]> {
+        HexDump::new(self.as_ref())
+    }
+}
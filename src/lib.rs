@@ -100,6 +100,11 @@
 //! argument to `Read::read` and `Write::write`. `Read` and `Write` may then
 //! perform a syscall, which has the potential of failing. Operations on `Buf`
 //! and `BufMut` are infallible.
+//!
+//! # Minimum supported Rust version
+//!
+//! `rcbytes` builds on Rust 1.51 and later. The `derive` feature needs Rust
+//! 1.71: the `rcbytes-derive` crate it pulls in depends on syn 2.
 
 extern crate alloc;
 
@@ -109,15 +114,38 @@ extern crate std;
 pub mod buf;
 pub use crate::buf::{Buf, BufMut};
 
+pub mod checksum;
+pub mod encoding;
+pub mod fmt;
+pub mod wire;
+
+mod byte_str;
+mod byte_string;
 mod bytes;
 mod bytes_mut;
-mod fmt;
+mod fixed_bytes_mut;
+mod placeholder;
+pub use crate::byte_str::{ByteStr, FromUtf8Error};
+pub use crate::byte_string::ByteString;
 pub use crate::bytes::Bytes;
-pub use crate::bytes_mut::BytesMut;
+pub use crate::bytes_mut::{BytesMut, GrowthPolicy};
+pub use crate::fixed_bytes_mut::{CapacityError, FixedBytesMut};
+pub use crate::placeholder::{LengthPrefixed, Placeholder, PlaceholderInt};
 
 // Optional Serde support
 #[cfg(feature = "serde")]
-mod serde;
+pub mod serde;
+
+#[cfg(feature = "track")]
+pub mod track;
+
+#[cfg(all(feature = "mmap", unix))]
+mod mmap;
+
+#[cfg(feature = "bytemuck")]
+mod pod;
+#[cfg(feature = "bytemuck")]
+pub use crate::pod::TypedBytes;
 
 #[inline(never)]
 #[cold]
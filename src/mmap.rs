@@ -0,0 +1,183 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use core::convert::TryFrom;
+use core::{ptr, slice};
+use std::fs::File;
+use std::io;
+use std::os::unix::io::AsRawFd;
+
+use crate::Bytes;
+
+impl Bytes {
+    /// Maps the whole of `file` into memory and returns it as `Bytes`.
+    ///
+    /// The mapping is read-only and private. It is unmapped once the last
+    /// handle pointing into it is dropped, so `slice`, `split_to` and
+    /// `copy_to_bytes` on the returned `Bytes` never copy. `Vec::from` copies
+    /// the viewed bytes out of the mapping.
+    ///
+    /// An empty file maps to an empty `Bytes` without calling `mmap`.
+    ///
+    /// This is only available on unix with the `mmap` feature enabled.
+    ///
+    /// # Safety
+    ///
+    /// The returned `Bytes` reads straight from the page cache. If the file
+    /// is modified or truncated while the mapping is alive, the contents of
+    /// the `Bytes` change underneath it, and accessing pages past a
+    /// truncated end of file raises `SIGBUS`. The caller must make sure that
+    /// no one, including other processes, modifies the file for as long as
+    /// any handle to the mapping is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Bytes;
+    /// # use std::io::Write;
+    /// # let path = std::env::temp_dir().join(format!("rcbytes-doc-map-file-{}", std::process::id()));
+    /// # std::fs::File::create(&path)?.write_all(b"hello world")?;
+    ///
+    /// let file = std::fs::File::open(&path)?;
+    /// let mut mem = unsafe { Bytes::map_file(&file)? };
+    /// let hello = mem.split_to(5);
+    ///
+    /// assert_eq!(hello, "hello");
+    /// assert_eq!(mem, " world");
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub unsafe fn map_file(file: &File) -> io::Result<Bytes> {
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file is too large to map"))?;
+        Bytes::map_range(file, 0, len)
+    }
+
+    /// Maps `len` bytes of `file` starting at byte `offset` into memory and
+    /// returns them as `Bytes`.
+    ///
+    /// `offset` does not need to be page aligned; the mapping starts at the
+    /// enclosing page boundary and the returned `Bytes` skips the extra
+    /// bytes. See [`Bytes::map_file`] for how the mapping is shared and
+    /// released.
+    ///
+    /// This is only available on unix with the `mmap` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidInput` if the range does not lie
+    /// within the file, or the error reported by `mmap` if mapping fails.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Bytes::map_file`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Bytes;
+    /// # use std::io::Write;
+    /// # let path = std::env::temp_dir().join(format!("rcbytes-doc-map-range-{}", std::process::id()));
+    /// # std::fs::File::create(&path)?.write_all(b"hello world")?;
+    ///
+    /// let file = std::fs::File::open(&path)?;
+    /// let world = unsafe { Bytes::map_range(&file, 6, 5)? };
+    ///
+    /// assert_eq!(world, "world");
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub unsafe fn map_range(file: &File, offset: u64, len: usize) -> io::Result<Bytes> {
+        let file_len = file.metadata()?.len();
+        if offset > file_len || len as u64 > file_len - offset {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "range is out of bounds of the file",
+            ));
+        }
+
+        if len == 0 {
+            return Ok(Bytes::new());
+        }
+
+        // `mmap` wants a page aligned offset, map from the start of the page
+        // and skip the difference afterwards.
+        let page_size = page_size() as u64;
+        let aligned = offset - offset % page_size;
+        let skip = (offset - aligned) as usize;
+        let map_len = len + skip;
+        let map_offset = libc::off_t::try_from(aligned).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, "offset is too large to map")
+        })?;
+
+        let addr = libc::mmap(
+            ptr::null_mut(),
+            map_len,
+            libc::PROT_READ,
+            libc::MAP_PRIVATE,
+            file.as_raw_fd(),
+            map_offset,
+        );
+        if addr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Bytes::from_owner(Mmap {
+            addr,
+            map_len,
+            skip,
+        }))
+    }
+}
+
+fn page_size() -> usize {
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
+
+/// A read-only mapping, unmapped on drop.
+struct Mmap {
+    addr: *mut libc::c_void,
+    map_len: usize,
+    // Bytes between the page aligned start of the mapping and the requested
+    // offset.
+    skip: usize,
+}
+
+impl AsRef<[u8]> for Mmap {
+    fn as_ref(&self) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(
+                (self.addr as *const u8).add(self.skip),
+                self.map_len - self.skip,
+            )
+        }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.addr, self.map_len);
+        }
+    }
+}
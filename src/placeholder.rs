@@ -0,0 +1,418 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::buf::UninitSlice;
+use crate::{BufMut, BytesMut};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Integer types that can fill a [`Placeholder`].
+///
+/// This trait is sealed and implemented for the primitive integer types from
+/// 8 to 128 bits.
+pub trait PlaceholderInt: sealed::Sealed + Copy {
+    #[doc(hidden)]
+    const SIZE: usize;
+
+    #[doc(hidden)]
+    fn write(self, dst: &mut [u8], little_endian: bool);
+
+    #[doc(hidden)]
+    fn from_len(len: usize) -> Option<Self>;
+}
+
+macro_rules! placeholder_int {
+    ($($ty:ty)*) => {$(
+        impl sealed::Sealed for $ty {}
+
+        impl PlaceholderInt for $ty {
+            const SIZE: usize = core::mem::size_of::<$ty>();
+
+            fn write(self, dst: &mut [u8], little_endian: bool) {
+                if little_endian {
+                    dst.copy_from_slice(&self.to_le_bytes());
+                } else {
+                    dst.copy_from_slice(&self.to_be_bytes());
+                }
+            }
+
+            fn from_len(len: usize) -> Option<$ty> {
+                <$ty>::try_from(len).ok()
+            }
+        }
+    )*};
+}
+
+placeholder_int!(u8 u16 u32 u64 u128 i8 i16 i32 i64 i128);
+
+/// A slot for a `T` in a [`BytesMut`], to be filled in later with
+/// [`BytesMut::patch`].
+///
+/// The slot is identified by its offset from the start of the buffer. It
+/// stays valid as long as the bytes in front of it are not removed, e.g. by
+/// `advance` or `split_to`, and the buffer is not truncated past it.
+#[must_use = "a placeholder is zero until it is filled with `BytesMut::patch`"]
+pub struct Placeholder<T> {
+    offset: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Placeholder<T> {
+    /// Returns the offset of the slot from the start of the buffer.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<T> fmt::Debug for Placeholder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Placeholder")
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+macro_rules! put_at_impl {
+    ($($name:ident($ty:ty) => $conv:ident;)*) => {$(
+        #[doc = concat!(
+            "Writes a `", stringify!($ty), "` at `offset`, in ",
+            put_at_impl!(@order $conv), " byte order, over bytes that were ",
+            "already put.\n\n",
+            "# Panics\n\n",
+            "Panics if the value doesn't fit between `offset` and `len()`.",
+        )]
+        #[inline]
+        pub fn $name(&mut self, offset: usize, n: $ty) {
+            self.put_slice_at(offset, &n.$conv());
+        }
+    )*};
+    (@order to_be_bytes) => { "big-endian" };
+    (@order to_le_bytes) => { "little-endian" };
+}
+
+impl BytesMut {
+    /// Copies `src` over the bytes starting at `offset`.
+    ///
+    /// Unlike [`put_slice`](BufMut::put_slice) this doesn't append, it
+    /// overwrites bytes that were already put. The length of the buffer
+    /// doesn't change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"hello world"[..]);
+    /// buf.put_slice_at(6, b"there");
+    /// assert_eq!(&buf[..], b"hello there");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset + src.len()` is greater than `len()`.
+    pub fn put_slice_at(&mut self, offset: usize, src: &[u8]) {
+        let len = self.len();
+        let end = offset.checked_add(src.len());
+        assert!(
+            end.map_or(false, |end| end <= len),
+            "write out of bounds: {:?} + {:?} <= {:?}",
+            offset,
+            src.len(),
+            len,
+        );
+
+        self[offset..offset + src.len()].copy_from_slice(src);
+    }
+
+    put_at_impl! {
+        put_u8_at(u8) => to_be_bytes;
+        put_i8_at(i8) => to_be_bytes;
+        put_u16_at(u16) => to_be_bytes;
+        put_u16_le_at(u16) => to_le_bytes;
+        put_i16_at(i16) => to_be_bytes;
+        put_i16_le_at(i16) => to_le_bytes;
+        put_u32_at(u32) => to_be_bytes;
+        put_u32_le_at(u32) => to_le_bytes;
+        put_i32_at(i32) => to_be_bytes;
+        put_i32_le_at(i32) => to_le_bytes;
+        put_u64_at(u64) => to_be_bytes;
+        put_u64_le_at(u64) => to_le_bytes;
+        put_i64_at(i64) => to_be_bytes;
+        put_i64_le_at(i64) => to_le_bytes;
+    }
+
+    /// Puts zeroed room for a `T` and returns a [`Placeholder`] to fill it in
+    /// later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::{BufMut, BytesMut};
+    ///
+    /// let mut buf = BytesMut::new();
+    /// let len = buf.put_placeholder::<u16>();
+    /// buf.put_slice(b"hello");
+    ///
+    /// let n = buf.len() - 2;
+    /// buf.patch(len, n as u16);
+    /// assert_eq!(&buf[..], b"\x00\x05hello");
+    /// ```
+    pub fn put_placeholder<T: PlaceholderInt>(&mut self) -> Placeholder<T> {
+        let offset = self.len();
+        self.put_bytes(0, T::SIZE);
+        Placeholder {
+            offset,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Fills in `placeholder` with `value` in big-endian byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the placeholder is no longer within the buffer.
+    pub fn patch<T: PlaceholderInt>(&mut self, placeholder: Placeholder<T>, value: T) {
+        self.patch_slot(placeholder.offset, value, false);
+    }
+
+    /// Fills in `placeholder` with `value` in little-endian byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the placeholder is no longer within the buffer.
+    pub fn patch_le<T: PlaceholderInt>(&mut self, placeholder: Placeholder<T>, value: T) {
+        self.patch_slot(placeholder.offset, value, true);
+    }
+
+    /// Puts a big-endian `T` length prefix, to be filled in with the length
+    /// of everything written through the returned guard.
+    ///
+    /// The prefix is filled in when the guard is dropped, or by
+    /// [`LengthPrefixed::finish`]. Guards can be nested to encode nested
+    /// messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::{BufMut, BytesMut};
+    ///
+    /// let mut buf = BytesMut::new();
+    /// {
+    ///     let mut msg = buf.begin_length_prefixed::<u32>();
+    ///     msg.put_u8(1);
+    ///     msg.put_slice(b"payload");
+    /// }
+    ///
+    /// assert_eq!(&buf[..], b"\x00\x00\x00\x08\x01payload");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Filling in the prefix panics if the length doesn't fit in a `T`.
+    pub fn begin_length_prefixed<T: PlaceholderInt>(&mut self) -> LengthPrefixed<'_, T> {
+        LengthPrefixed::new(self, false)
+    }
+
+    /// Like [`begin_length_prefixed`](BytesMut::begin_length_prefixed), but
+    /// the prefix is little-endian.
+    pub fn begin_length_prefixed_le<T: PlaceholderInt>(&mut self) -> LengthPrefixed<'_, T> {
+        LengthPrefixed::new(self, true)
+    }
+
+    fn patch_slot<T: PlaceholderInt>(&mut self, offset: usize, value: T, little_endian: bool) {
+        let len = self.len();
+        assert!(
+            offset <= len && T::SIZE <= len - offset,
+            "placeholder out of bounds: {:?} + {:?} <= {:?}",
+            offset,
+            T::SIZE,
+            len,
+        );
+
+        value.write(&mut self[offset..offset + T::SIZE], little_endian);
+    }
+}
+
+/// A guard that fills in a length prefix when it is dropped.
+///
+/// Created by [`BytesMut::begin_length_prefixed`]. Everything written
+/// through its [`BufMut`] implementation counts towards the length. It
+/// dereferences to the underlying `BytesMut` for reading only: removing
+/// bytes through the guard could cut into the prefix, so neither of these
+/// compiles.
+///
+/// ```compile_fail
+/// use rcbytes::{BufMut, BytesMut};
+///
+/// let mut buf = BytesMut::new();
+/// let mut msg = buf.begin_length_prefixed::<u32>();
+/// msg.put_slice(b"hello");
+/// msg.truncate(0);
+/// ```
+///
+/// ```compile_fail
+/// use rcbytes::{Buf, BufMut, BytesMut};
+///
+/// let mut buf = BytesMut::new();
+/// let mut msg = buf.begin_length_prefixed::<u32>();
+/// msg.put_slice(b"hello");
+/// msg.advance(2);
+/// ```
+///
+/// With the `std` feature, the prefix is left as zero if the guard is
+/// dropped during a panic. Without it, an overflowing prefix panics again
+/// and aborts.
+pub struct LengthPrefixed<'a, T: PlaceholderInt> {
+    buf: &'a mut BytesMut,
+    placeholder: Option<Placeholder<T>>,
+    little_endian: bool,
+}
+
+impl<'a, T: PlaceholderInt> LengthPrefixed<'a, T> {
+    fn new(buf: &'a mut BytesMut, little_endian: bool) -> LengthPrefixed<'a, T> {
+        let placeholder = Some(buf.put_placeholder());
+        LengthPrefixed {
+            buf,
+            placeholder,
+            little_endian,
+        }
+    }
+
+    /// Returns the number of bytes written after the prefix so far.
+    pub fn body_len(&self) -> usize {
+        match self.placeholder {
+            Some(ref placeholder) => self.buf.len() - placeholder.offset - T::SIZE,
+            None => 0,
+        }
+    }
+
+    /// Starts a nested length prefixed message, like
+    /// [`BytesMut::begin_length_prefixed`].
+    pub fn begin_length_prefixed<U: PlaceholderInt>(&mut self) -> LengthPrefixed<'_, U> {
+        self.buf.begin_length_prefixed()
+    }
+
+    /// Starts a nested length prefixed message, like
+    /// [`BytesMut::begin_length_prefixed_le`].
+    pub fn begin_length_prefixed_le<U: PlaceholderInt>(&mut self) -> LengthPrefixed<'_, U> {
+        self.buf.begin_length_prefixed_le()
+    }
+
+    /// Fills in the prefix and returns the length that was written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length doesn't fit in a `T`.
+    pub fn finish(mut self) -> usize {
+        self.fill()
+    }
+
+    fn fill(&mut self) -> usize {
+        let len = self.body_len();
+        if let Some(placeholder) = self.placeholder.take() {
+            let value = T::from_len(len).unwrap_or_else(|| {
+                panic!(
+                    "length prefix overflow: {:?} does not fit in {:?} bytes",
+                    len,
+                    T::SIZE
+                )
+            });
+            self.buf
+                .patch_slot(placeholder.offset, value, self.little_endian);
+        }
+        len
+    }
+}
+
+impl<T: PlaceholderInt> Deref for LengthPrefixed<'_, T> {
+    type Target = BytesMut;
+
+    fn deref(&self) -> &BytesMut {
+        self.buf
+    }
+}
+
+// Only appending is forwarded, so the body can't shrink below the prefix.
+unsafe impl<T: PlaceholderInt> BufMut for LengthPrefixed<'_, T> {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.buf.remaining_mut()
+    }
+
+    #[inline]
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.buf.advance_mut(cnt)
+    }
+
+    #[inline]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.buf.chunk_mut()
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        self.buf.put_slice(src)
+    }
+
+    fn put_bytes(&mut self, val: u8, cnt: usize) {
+        self.buf.put_bytes(val, cnt)
+    }
+
+    fn put_with<F>(&mut self, max_len: usize, f: F) -> usize
+    where
+        F: FnOnce(&mut UninitSlice) -> usize,
+        Self: Sized,
+    {
+        self.buf.put_with(max_len, f)
+    }
+}
+
+impl<T: PlaceholderInt> Drop for LengthPrefixed<'_, T> {
+    fn drop(&mut self) {
+        // A half written body isn't worth a second panic, which would abort.
+        #[cfg(feature = "std")]
+        {
+            if std::thread::panicking() {
+                return;
+            }
+        }
+
+        self.fill();
+    }
+}
+
+impl<T: PlaceholderInt> fmt::Debug for LengthPrefixed<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LengthPrefixed")
+            .field("placeholder", &self.placeholder)
+            .field("body_len", &self.body_len())
+            .finish()
+    }
+}
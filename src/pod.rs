@@ -0,0 +1,187 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::{fmt, mem};
+
+use bytemuck::{Pod, PodCastError};
+
+use crate::Bytes;
+
+impl Bytes {
+    /// Views the bytes as a slice of `T` without copying them.
+    ///
+    /// The returned [`TypedBytes`] keeps the underlying storage alive. The
+    /// bytes must be aligned for `T` and their length must be a multiple of
+    /// the size of `T`. Values are read in the native byte order.
+    ///
+    /// Short `Bytes` are stored inline and move along with the handle, so
+    /// their contents are copied into a properly aligned allocation instead.
+    ///
+    /// This is only available with the `bytemuck` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_pod_vec(vec![1u32, 2, 3, 4, 5, 6]);
+    /// let ints = bytes.slice(8..).try_cast_slice::<u32>().unwrap();
+    ///
+    /// assert_eq!(&ints[..], [3, 4, 5, 6]);
+    /// assert!(bytes.slice(1..).try_cast_slice::<u32>().is_err());
+    /// ```
+    pub fn try_cast_slice<T: Pod>(&self) -> Result<TypedBytes<T>, PodCastError> {
+        if self.is_empty() {
+            return Ok(TypedBytes::new(Bytes::new()));
+        }
+
+        if self.is_inline() {
+            let size = mem::size_of::<T>();
+            if size == 0 || self.len() % size != 0 {
+                return Err(PodCastError::OutputSliceWouldHaveSlop);
+            }
+            let mut vec = alloc::vec![T::zeroed(); self.len() / size];
+            bytemuck::cast_slice_mut::<T, u8>(&mut vec).copy_from_slice(self);
+            return Ok(TypedBytes::new(Bytes::from_pod_vec(vec)));
+        }
+
+        bytemuck::try_cast_slice::<u8, T>(self)?;
+        Ok(TypedBytes::new(self.clone()))
+    }
+
+    /// Converts a `Vec` of plain-old-data into `Bytes` without copying.
+    ///
+    /// The vector is kept alive until the last handle referencing its bytes
+    /// is dropped. The result can be turned back into a typed view with
+    /// [`try_cast_slice`](Bytes::try_cast_slice), which does not copy either.
+    ///
+    /// This is only available with the `bytemuck` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_pod_vec(vec![0x0102u16; 4]);
+    /// assert_eq!(bytes.len(), 8);
+    /// assert_eq!(&bytes[..2], 0x0102u16.to_ne_bytes());
+    /// ```
+    pub fn from_pod_vec<T: Pod>(vec: Vec<T>) -> Bytes {
+        Bytes::from_owner(PodVec(vec))
+    }
+}
+
+struct PodVec<T>(Vec<T>);
+
+impl<T: Pod> AsRef<[u8]> for PodVec<T> {
+    fn as_ref(&self) -> &[u8] {
+        bytemuck::cast_slice(&self.0)
+    }
+}
+
+/// A shared, immutable view of a `Bytes` as a slice of `T`.
+///
+/// Created by [`Bytes::try_cast_slice`]. Cloning is as cheap as cloning the
+/// underlying `Bytes`, and the typed slice is reached through `Deref`.
+///
+/// This is only available with the `bytemuck` feature enabled.
+pub struct TypedBytes<T> {
+    // Never stored inline, so its bytes stay put when the handle moves.
+    bytes: Bytes,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Pod> TypedBytes<T> {
+    fn new(bytes: Bytes) -> TypedBytes<T> {
+        TypedBytes {
+            bytes,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the underlying bytes.
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Consumes this `TypedBytes`, returning the underlying bytes.
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+}
+
+impl<T: Pod> Deref for TypedBytes<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        if self.bytes.is_empty() {
+            return &[];
+        }
+        // Checked when the view was created and the bytes cannot move.
+        bytemuck::cast_slice(&self.bytes)
+    }
+}
+
+impl<T: Pod> AsRef<[T]> for TypedBytes<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> Clone for TypedBytes<T> {
+    fn clone(&self) -> TypedBytes<T> {
+        TypedBytes {
+            bytes: self.bytes.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Pod + fmt::Debug> fmt::Debug for TypedBytes<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Pod + PartialEq> PartialEq for TypedBytes<T> {
+    fn eq(&self, other: &TypedBytes<T>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Pod + PartialEq> PartialEq<[T]> for TypedBytes<T> {
+    fn eq(&self, other: &[T]) -> bool {
+        **self == *other
+    }
+}
+
+impl<T> From<TypedBytes<T>> for Bytes {
+    fn from(typed: TypedBytes<T>) -> Bytes {
+        typed.bytes
+    }
+}
@@ -23,14 +23,299 @@
 // LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
-use super::{Bytes, BytesMut};
-use alloc::string::String;
+//! Serde support.
+//!
+//! [`Bytes`] and [`BytesMut`] serialize as byte strings by default. The
+//! [`hex`] and [`base64`] modules serialize them as text instead, and
+//! [`human_readable`] as base64 text only for human-readable formats such as
+//! JSON or YAML. They are for use with `#[serde(with = "...")]` on individual
+//! fields:
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! use rcbytes::Bytes;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Frame {
+//!     #[serde(with = "rcbytes::serde::hex")]
+//!     id: Bytes,
+//!     #[serde(with = "rcbytes::serde::base64")]
+//!     payload: Bytes,
+//!     #[serde(with = "rcbytes::serde::human_readable")]
+//!     trailer: Bytes,
+//! }
+//! ```
+//!
+//! This module also has a compact binary format of its own: [`to_buf`]
+//! writes any serializable value into a [`BufMut`], and [`from_bytes`] reads
+//! it back from a [`Bytes`], handing out `Bytes` fields as slices of the
+//! input instead of copies. [`SourceGuard`] does the same for other formats
+//! that borrow from their input.
+//!
+//! Deserializing a plain `Bytes` accepts input of any length. Use
+//! [`BoundedBytes`] and [`BoundedBytesMut`] for untrusted input: they reject
+//! anything over their limit before allocating for it.
+
+use crate::encoding::{self, DecodeError};
+use crate::{BufMut, ByteStr, ByteString, Bytes, BytesMut};
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
-use core::{cmp, fmt};
+use core::marker::PhantomData;
+use core::{cmp, fmt, str};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+pub mod base64;
+mod binary;
+mod bounded;
+pub mod hex;
+pub mod human_readable;
+#[cfg(feature = "std")]
+mod source;
+
+pub use self::binary::{from_bytes, to_buf};
+pub use self::bounded::{BoundedBytes, BoundedBytesMut};
+#[cfg(feature = "std")]
+pub use self::source::SourceGuard;
+
+/// The error returned by [`to_buf`] and [`from_bytes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input ended in the middle of a value.
+    UnexpectedEof,
+    /// An integer does not fit in the type being deserialized.
+    Overflow,
+    /// A `bool` is neither `0` nor `1`.
+    InvalidBool(u8),
+    /// An option tag is neither `0` nor `1`.
+    InvalidOption(u8),
+    /// A `char` is not a Unicode scalar value.
+    InvalidChar(u32),
+    /// A string is not valid UTF-8.
+    InvalidUtf8(str::Utf8Error),
+    /// A sequence or map was serialized without a known length.
+    UnknownLength,
+    /// The type needs a self-describing format, for example because it uses
+    /// `deserialize_any`.
+    NotSelfDescribing,
+    /// An error reported by a `Serialize` or `Deserialize` impl.
+    Custom(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnexpectedEof => f.write_str("unexpected end of input"),
+            Error::Overflow => f.write_str("integer out of range"),
+            Error::InvalidBool(byte) => write!(f, "invalid bool {:#04x}", byte),
+            Error::InvalidOption(byte) => write!(f, "invalid option tag {:#04x}", byte),
+            Error::InvalidChar(value) => write!(f, "invalid char {:#x}", value),
+            Error::InvalidUtf8(err) => write!(f, "invalid string: {}", err),
+            Error::UnknownLength => f.write_str("sequence length must be known up front"),
+            Error::NotSelfDescribing => f.write_str("format is not self-describing"),
+            Error::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+// `StdError` is `std::error::Error` whenever serde has `std`, even if this
+// crate does not.
+impl de::StdError for Error {}
+
+impl serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Buffer types that the [`hex`], [`base64`] and [`human_readable`] modules
+/// deserialize into.
+///
+/// This trait is sealed and implemented for [`Bytes`], [`BytesMut`] and
+/// `Vec<u8>`.
+pub trait Buffer: sealed::Sealed + Sized {
+    #[doc(hidden)]
+    fn from_bytes_mut(buf: BytesMut) -> Self;
+
+    /// Deserializes the default byte string representation.
+    #[doc(hidden)]
+    fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+impl sealed::Sealed for Bytes {}
+
+impl Buffer for Bytes {
+    fn from_bytes_mut(buf: BytesMut) -> Self {
+        buf.freeze()
+    }
+
+    fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Bytes::deserialize(deserializer)
+    }
+}
+
+impl sealed::Sealed for BytesMut {}
+
+impl Buffer for BytesMut {
+    fn from_bytes_mut(buf: BytesMut) -> Self {
+        buf
+    }
+
+    fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        BytesMut::deserialize(deserializer)
+    }
+}
+
+impl sealed::Sealed for Vec<u8> {}
+
+impl Buffer for Vec<u8> {
+    fn from_bytes_mut(buf: BytesMut) -> Self {
+        Vec::from(buf)
+    }
+
+    fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        BytesMut::deserialize(deserializer).map(Vec::from)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Codec {
+    Hex,
+    Base64,
+}
+
+impl Codec {
+    fn serialize<S>(self, src: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let text = match self {
+            Codec::Hex => encoding::hex::encode(src),
+            Codec::Base64 => encoding::base64::STANDARD.encode(src),
+        };
+        // Safety: both encodings only produce ASCII.
+        serializer.serialize_str(unsafe { str::from_utf8_unchecked(&text) })
+    }
+
+    fn decode(self, src: &[u8]) -> Result<BytesMut, DecodeError> {
+        let mut buf = BytesMut::new();
+        match self {
+            Codec::Hex => {
+                buf.reserve(src.len() / 2);
+                encoding::hex::decode_to(src, &mut buf)?;
+            }
+            Codec::Base64 => {
+                buf.reserve(src.len() / 4 * 3 + 3);
+                encoding::base64::STANDARD.decode_to(src, &mut buf)?;
+            }
+        }
+        Ok(buf)
+    }
+}
+
+/// Decodes text in one codec, and falls back to a sequence of numbers so data
+/// written before a field switched to text stays readable.
+struct TextVisitor<T> {
+    codec: Codec,
+    _marker: PhantomData<T>,
+}
+
+impl<T> TextVisitor<T> {
+    fn new(codec: Codec) -> Self {
+        TextVisitor {
+            codec,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: Buffer> de::Visitor<'de> for TextVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.codec {
+            Codec::Hex => formatter.write_str("a hex string"),
+            Codec::Base64 => formatter.write_str("a base64 string"),
+        }
+    }
+
+    #[inline]
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: de::SeqAccess<'de>,
+    {
+        let len = cmp::min(seq.size_hint().unwrap_or(0), 4096);
+        let mut values = BytesMut::with_capacity(len);
+
+        while let Some(value) = seq.next_element::<u8>()? {
+            values.put_u8(value);
+        }
+
+        Ok(T::from_bytes_mut(values))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match self.codec.decode(v) {
+            Ok(buf) => Ok(T::from_bytes_mut(buf)),
+            Err(err) => Err(E::custom(err)),
+        }
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(v.as_bytes())
+    }
+}
+
+/// Fails with `invalid_length` if `len` is over `limit`.
+///
+/// Visitors call this before allocating or copying anything, so a limit also
+/// bounds the memory spent on hostile input.
+fn check_len<E: de::Error>(len: usize, limit: usize, exp: &dyn de::Expected) -> Result<(), E> {
+    if len > limit {
+        Err(E::invalid_length(len, exp))
+    } else {
+        Ok(())
+    }
+}
+
 macro_rules! serde_impl {
-    ($ty:ident, $visitor_ty:ident, $from_slice:ident, $from_vec:ident) => {
+    (
+        $ty:ident,
+        $visitor_ty:ident,
+        $deserialize_limited:ident,
+        $from_slice:ident,
+        $from_vec:ident,
+        $from_borrowed:path
+    ) => {
         impl Serialize for $ty {
             #[inline]
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -41,13 +326,19 @@ macro_rules! serde_impl {
             }
         }
 
-        struct $visitor_ty;
+        struct $visitor_ty {
+            limit: usize,
+        }
 
         impl<'de> de::Visitor<'de> for $visitor_ty {
             type Value = $ty;
 
             fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-                formatter.write_str("byte array")
+                formatter.write_str("byte array")?;
+                if self.limit != usize::MAX {
+                    write!(formatter, " of at most {} bytes", self.limit)?;
+                }
+                Ok(())
             }
 
             #[inline]
@@ -55,10 +346,14 @@ macro_rules! serde_impl {
             where
                 V: de::SeqAccess<'de>,
             {
+                if let Some(len) = seq.size_hint() {
+                    check_len(len, self.limit, &self)?;
+                }
                 let len = cmp::min(seq.size_hint().unwrap_or(0), 4096);
                 let mut values: Vec<u8> = Vec::with_capacity(len);
 
                 while let Some(value) = seq.next_element()? {
+                    check_len(values.len() + 1, self.limit, &self)?;
                     values.push(value);
                 }
 
@@ -70,14 +365,25 @@ macro_rules! serde_impl {
             where
                 E: de::Error,
             {
+                check_len(v.len(), self.limit, &self)?;
                 Ok($ty::$from_slice(v))
             }
 
+            #[inline]
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                check_len(v.len(), self.limit, &self)?;
+                Ok($from_borrowed(v))
+            }
+
             #[inline]
             fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
             where
                 E: de::Error,
             {
+                check_len(v.len(), self.limit, &self)?;
                 Ok($ty::$from_vec(v))
             }
 
@@ -86,29 +392,178 @@ macro_rules! serde_impl {
             where
                 E: de::Error,
             {
+                check_len(v.len(), self.limit, &self)?;
                 Ok($ty::$from_slice(v.as_bytes()))
             }
 
+            #[inline]
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                check_len(v.len(), self.limit, &self)?;
+                Ok($from_borrowed(v.as_bytes()))
+            }
+
             #[inline]
             fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
             where
                 E: de::Error,
             {
+                check_len(v.len(), self.limit, &self)?;
                 Ok($ty::$from_vec(v.into_bytes()))
             }
         }
 
+        /// Deserializes a value of at most `limit` bytes.
+        fn $deserialize_limited<'de, D>(deserializer: D, limit: usize) -> Result<$ty, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_byte_buf($visitor_ty { limit })
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            #[inline]
+            fn deserialize<D>(deserializer: D) -> Result<$ty, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                $deserialize_limited(deserializer, usize::MAX)
+            }
+        }
+    };
+}
+
+/// Slices borrowed input out of the source `Bytes` being deserialized, if
+/// there is one, and copies it otherwise.
+fn bytes_from_borrowed(v: &[u8]) -> Bytes {
+    #[cfg(feature = "std")]
+    {
+        if let Some(bytes) = source::slice_ref(v) {
+            return bytes;
+        }
+    }
+    Bytes::copy_from_slice(v)
+}
+
+serde_impl!(
+    Bytes,
+    BytesVisitor,
+    deserialize_bytes_limited,
+    copy_from_slice,
+    from,
+    bytes_from_borrowed
+);
+serde_impl!(
+    BytesMut,
+    BytesMutVisitor,
+    deserialize_bytes_mut_limited,
+    from,
+    from_vec,
+    BytesMut::from
+);
+
+macro_rules! serde_str_impl {
+    ($ty:ident, $visitor_ty:ident, $from_borrowed:path) => {
+        impl Serialize for $ty {
+            #[inline]
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(self)
+            }
+        }
+
+        struct $visitor_ty;
+
+        impl<'de> de::Visitor<'de> for $visitor_ty {
+            type Value = $ty;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            #[inline]
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok($ty::from(String::from(v)))
+            }
+
+            #[inline]
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok($from_borrowed(v))
+            }
+
+            #[inline]
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok($ty::from(v))
+            }
+
+            #[inline]
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match core::str::from_utf8(v) {
+                    Ok(s) => self.visit_str(s),
+                    Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            #[inline]
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match core::str::from_utf8(v) {
+                    Ok(s) => self.visit_borrowed_str(s),
+                    Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            #[inline]
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match String::from_utf8(v) {
+                    Ok(s) => Ok($ty::from(s)),
+                    Err(e) => Err(de::Error::invalid_value(
+                        de::Unexpected::Bytes(e.as_bytes()),
+                        &self,
+                    )),
+                }
+            }
+        }
+
         impl<'de> Deserialize<'de> for $ty {
             #[inline]
             fn deserialize<D>(deserializer: D) -> Result<$ty, D::Error>
             where
                 D: Deserializer<'de>,
             {
-                deserializer.deserialize_byte_buf($visitor_ty)
+                deserializer.deserialize_string($visitor_ty)
             }
         }
     };
 }
 
-serde_impl!(Bytes, BytesVisitor, copy_from_slice, from);
-serde_impl!(BytesMut, BytesMutVisitor, from, from_vec);
+/// Like [`bytes_from_borrowed`], for strings.
+fn byte_str_from_borrowed(v: &str) -> ByteStr {
+    let bytes = bytes_from_borrowed(v.as_bytes());
+    // Safety: `bytes` holds the same contents as `v`.
+    unsafe { ByteStr::from_utf8_unchecked(bytes) }
+}
+
+serde_str_impl!(ByteStr, ByteStrVisitor, byte_str_from_borrowed);
+serde_str_impl!(ByteString, ByteStringVisitor, ByteString::from);
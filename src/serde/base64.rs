@@ -0,0 +1,54 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Serializes buffers as padded base64 strings, using the standard alphabet.
+//!
+//! Use it with `#[serde(with = "rcbytes::serde::base64")]` on a field of type
+//! [`Bytes`], [`BytesMut`] or `Vec<u8>`. The text form is used for every
+//! format, human-readable or not.
+//!
+//! [`Bytes`]: crate::Bytes
+//! [`BytesMut`]: crate::BytesMut
+
+use super::{Buffer, Codec, TextVisitor};
+use serde::{Deserializer, Serializer};
+
+/// Serializes `value` as a string, for example `[1, 2, 255]` as "AQL/".
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + AsRef<[u8]>,
+    S: Serializer,
+{
+    Codec::Base64.serialize(value.as_ref(), serializer)
+}
+
+/// Deserializes a buffer from a string written by [`serialize`].
+///
+/// Padding is required and is checked strictly.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Buffer,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(TextVisitor::new(Codec::Base64))
+}
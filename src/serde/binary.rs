@@ -0,0 +1,692 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! A compact binary serde format.
+//!
+//! Values are written without any type information, in the order serde
+//! visits them:
+//!
+//! - `bool`, `u8` and `i8` are one byte.
+//! - Other integers are [varints], zigzag mapped when signed.
+//! - `f32` and `f64` are little-endian.
+//! - `char` is its scalar value as a varint.
+//! - Strings, byte strings, sequences and maps are a varint length followed
+//!   by their contents. Sequences and maps must know their length up front.
+//! - Options are a `0` or `1` byte, followed by the value if present.
+//! - Enum variants are their index as a varint, followed by their contents.
+//! - Structs and tuples are their fields in order, and unit types are empty.
+//!
+//! The format is not self-describing, so it cannot deserialize types that
+//! rely on `deserialize_any`, such as untagged enums.
+//!
+//! [varints]: crate::encoding::varint
+
+use super::Error;
+use crate::encoding::varint::{self, VarintError};
+use crate::{Buf, BufMut, Bytes};
+use core::convert::TryFrom;
+use core::str;
+use serde::de::{self, IntoDeserializer};
+use serde::ser::{self, Serialize};
+
+/// Serializes `value` into `dst`.
+///
+/// # Panics
+///
+/// Panics if `dst` runs out of room, like [`BufMut::put_slice`].
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::BytesMut;
+///
+/// let mut buf = BytesMut::new();
+/// rcbytes::serde::to_buf(&(300u32, "hi"), &mut buf).unwrap();
+/// assert_eq!(buf, &b"\xac\x02\x02hi"[..]);
+/// ```
+pub fn to_buf<T, M>(value: &T, dst: &mut M) -> Result<(), Error>
+where
+    T: ?Sized + Serialize,
+    M: BufMut,
+{
+    value.serialize(&mut Serializer { dst })
+}
+
+/// Deserializes a value from the front of `src`, advancing past it.
+///
+/// Bytes after the value are left in `src`, so several values can be read
+/// back to back. `Bytes` and `ByteStr` fields are slices of `src` rather than
+/// copies, except for short ones which are stored inline.
+///
+/// On error, `src` is left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::{Bytes, BytesMut};
+///
+/// let mut buf = BytesMut::new();
+/// let payload = Bytes::from(vec![7; 64]);
+/// rcbytes::serde::to_buf(&(1u8, &payload), &mut buf).unwrap();
+///
+/// let mut src = buf.freeze();
+/// let (tag, body): (u8, Bytes) = rcbytes::serde::from_bytes(&mut src).unwrap();
+/// assert_eq!(tag, 1);
+/// assert_eq!(body, payload);
+/// assert!(src.is_empty());
+/// ```
+pub fn from_bytes<T>(src: &mut Bytes) -> Result<T, Error>
+where
+    T: de::DeserializeOwned,
+{
+    #[cfg(feature = "std")]
+    let _guard = super::SourceGuard::new(src);
+
+    let mut de = Deserializer { input: &src[..] };
+    let value = T::deserialize(&mut de)?;
+    let consumed = src.len() - de.input.len();
+    src.advance(consumed);
+    Ok(value)
+}
+
+struct Serializer<'a, M> {
+    dst: &'a mut M,
+}
+
+impl<M: BufMut> Serializer<'_, M> {
+    fn put_varint(&mut self, value: u64) {
+        varint::encode_to(value, self.dst);
+    }
+
+    fn put_len(&mut self, len: Option<usize>) -> Result<(), Error> {
+        let len = len.ok_or(Error::UnknownLength)?;
+        self.put_varint(len as u64);
+        Ok(())
+    }
+}
+
+impl<M: BufMut> ser::Serializer for &mut Serializer<'_, M> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.dst.put_u8(v as u8);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.dst.put_i8(v);
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.put_varint(varint::zigzag_encode(v));
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.dst.put_u8(v);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.put_varint(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.dst.put_f32_le(v);
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.dst.put_f64_le(v);
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.serialize_u32(v.into())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.put_varint(v.len() as u64);
+        self.dst.put_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.dst.put_u8(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        self.dst.put_u8(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        self.serialize_u32(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.put_varint(variant_index.into());
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, Error> {
+        self.put_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.put_varint(variant_index.into());
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, Error> {
+        self.put_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.put_varint(variant_index.into());
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! serialize_compound {
+    ($($trait:ident::$method:ident),*) => {
+        $(
+            impl<M: BufMut> ser::$trait for &mut Serializer<'_, M> {
+                type Ok = ();
+                type Error = Error;
+
+                fn $method<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> Result<(), Error> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+serialize_compound!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+impl<M: BufMut> ser::SerializeMap for &mut Serializer<'_, M> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<M: BufMut> ser::SerializeStruct for &mut Serializer<'_, M> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<M: BufMut> ser::SerializeStructVariant for &mut Serializer<'_, M> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Deserializer<'de> {
+    fn get_u8(&mut self) -> Result<u8, Error> {
+        if !self.input.has_remaining() {
+            return Err(Error::UnexpectedEof);
+        }
+        Ok(self.input.get_u8())
+    }
+
+    fn get_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let bytes = self.get_slice(N)?;
+        let mut array = [0; N];
+        array.copy_from_slice(bytes);
+        Ok(array)
+    }
+
+    fn get_varint(&mut self) -> Result<u64, Error> {
+        varint::decode(&mut self.input).map_err(|err| match err {
+            VarintError::UnexpectedEof => Error::UnexpectedEof,
+            VarintError::Overflow => Error::Overflow,
+        })
+    }
+
+    fn get_uint<T: TryFrom<u64>>(&mut self) -> Result<T, Error> {
+        T::try_from(self.get_varint()?).map_err(|_| Error::Overflow)
+    }
+
+    fn get_int<T: TryFrom<i64>>(&mut self) -> Result<T, Error> {
+        let value = varint::zigzag_decode(self.get_varint()?);
+        T::try_from(value).map_err(|_| Error::Overflow)
+    }
+
+    fn get_len(&mut self) -> Result<usize, Error> {
+        // A length that does not fit in a `usize` cannot fit in the input
+        // either.
+        usize::try_from(self.get_varint()?).map_err(|_| Error::UnexpectedEof)
+    }
+
+    fn get_slice(&mut self, len: usize) -> Result<&'de [u8], Error> {
+        if len > self.input.len() {
+            return Err(Error::UnexpectedEof);
+        }
+        let (slice, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(slice)
+    }
+
+    fn get_bytes(&mut self) -> Result<&'de [u8], Error> {
+        let len = self.get_len()?;
+        self.get_slice(len)
+    }
+
+    fn get_str(&mut self) -> Result<&'de str, Error> {
+        str::from_utf8(self.get_bytes()?).map_err(Error::InvalidUtf8)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::NotSelfDescribing)
+    }
+
+    fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.get_u8()? {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            byte => Err(Error::InvalidBool(byte)),
+        }
+    }
+
+    fn deserialize_i8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i8(self.get_u8()? as i8)
+    }
+
+    fn deserialize_i16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i16(self.get_int()?)
+    }
+
+    fn deserialize_i32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i32(self.get_int()?)
+    }
+
+    fn deserialize_i64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.get_int()?)
+    }
+
+    fn deserialize_u8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u8(self.get_u8()?)
+    }
+
+    fn deserialize_u16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u16(self.get_uint()?)
+    }
+
+    fn deserialize_u32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u32(self.get_uint()?)
+    }
+
+    fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u64(self.get_varint()?)
+    }
+
+    fn deserialize_f32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f32(f32::from_le_bytes(self.get_array()?))
+    }
+
+    fn deserialize_f64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(f64::from_le_bytes(self.get_array()?))
+    }
+
+    fn deserialize_char<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let value = self.get_uint()?;
+        match core::char::from_u32(value) {
+            Some(c) => visitor.visit_char(c),
+            None => Err(Error::InvalidChar(value)),
+        }
+    }
+
+    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.get_str()?)
+    }
+
+    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_bytes(self.get_bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.get_u8()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            byte => Err(Error::InvalidOption(byte)),
+        }
+    }
+
+    fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.get_len()?;
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.get_len()?;
+        visitor.visit_map(Access { de: self, len })
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::NotSelfDescribing)
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::NotSelfDescribing)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Hands out the `len` elements of a sequence, tuple or map.
+struct Access<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    len: usize,
+}
+
+impl<'a, 'de> de::SeqAccess<'de> for Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'a, 'de> de::MapAccess<'de> for Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let index: u32 = self.get_uint()?;
+        let value = seed.deserialize(index.into_deserializer())?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
@@ -0,0 +1,141 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Buffers with a length limit enforced on deserialization.
+
+use super::{deserialize_bytes_limited, deserialize_bytes_mut_limited};
+use crate::{Bytes, BytesMut};
+use core::{fmt, ops};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+macro_rules! bounded {
+    ($(#[$attr:meta])* $name:ident, $inner:ident, $deserialize_limited:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name<const MAX: usize> {
+            inner: $inner,
+        }
+
+        impl<const MAX: usize> $name<MAX> {
+            /// Wraps `inner`, or hands it back if it is longer than `MAX`.
+            pub fn new(inner: $inner) -> Result<Self, $inner> {
+                if inner.len() > MAX {
+                    Err(inner)
+                } else {
+                    Ok($name { inner })
+                }
+            }
+
+            /// Returns the wrapped buffer.
+            pub fn into_inner(self) -> $inner {
+                self.inner
+            }
+        }
+
+        impl<const MAX: usize> ops::Deref for $name<MAX> {
+            type Target = $inner;
+
+            #[inline]
+            fn deref(&self) -> &$inner {
+                &self.inner
+            }
+        }
+
+        impl<const MAX: usize> AsRef<[u8]> for $name<MAX> {
+            #[inline]
+            fn as_ref(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        impl<const MAX: usize> fmt::Debug for $name<MAX> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.inner, f)
+            }
+        }
+
+        impl<const MAX: usize> From<$name<MAX>> for $inner {
+            fn from(bounded: $name<MAX>) -> $inner {
+                bounded.inner
+            }
+        }
+
+        impl<const MAX: usize> Serialize for $name<MAX> {
+            #[inline]
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                self.inner.serialize(serializer)
+            }
+        }
+
+        impl<'de, const MAX: usize> Deserialize<'de> for $name<MAX> {
+            #[inline]
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                $deserialize_limited(deserializer, MAX).map(|inner| $name { inner })
+            }
+        }
+    };
+}
+
+bounded!(
+    /// A [`Bytes`] of at most `MAX` bytes.
+    ///
+    /// It serializes exactly like `Bytes`. Deserializing fails with an
+    /// `invalid_length` error as soon as the input is known to be longer
+    /// than `MAX`, before anything is allocated for it. Byte strings and
+    /// strings are checked up front, and sequences as soon as their size
+    /// hint or their element count goes over. Deserializers that hand out
+    /// owned buffers have allocated those already; the limit then only
+    /// keeps them from being turned into a `Bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcbytes::serde::BoundedBytes;
+    ///
+    /// let ok: BoundedBytes<4> = serde_json::from_str("[1, 2, 3, 4]").unwrap();
+    /// assert_eq!(*ok, &[1, 2, 3, 4][..]);
+    ///
+    /// let err = serde_json::from_str::<BoundedBytes<4>>("[1, 2, 3, 4, 5]").unwrap_err();
+    /// assert!(err.to_string().starts_with("invalid length 5"));
+    /// ```
+    BoundedBytes,
+    Bytes,
+    deserialize_bytes_limited
+);
+
+bounded!(
+    /// A [`BytesMut`] of at most `MAX` bytes.
+    ///
+    /// See [`BoundedBytes`] for how the limit is enforced. The buffer is only
+    /// reachable through a shared reference, so it cannot grow past `MAX`
+    /// after construction; call [`into_inner`](Self::into_inner) to edit it.
+    BoundedBytesMut,
+    BytesMut,
+    deserialize_bytes_mut_limited
+);
@@ -0,0 +1,65 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Serializes buffers as padded base64 strings in human-readable formats,
+//! and as byte strings in every other format.
+//!
+//! Use it with `#[serde(with = "rcbytes::serde::human_readable")]` on a field
+//! of type [`Bytes`], [`BytesMut`] or `Vec<u8>`. JSON then holds the field as
+//! a string instead of an array of numbers, while compact formats keep the
+//! raw bytes. Deserializing from a human-readable format also accepts a
+//! sequence of numbers, so data written before the field opted in stays
+//! readable.
+//!
+//! [`Bytes`]: crate::Bytes
+//! [`BytesMut`]: crate::BytesMut
+
+use super::{Buffer, Codec, TextVisitor};
+use serde::{Deserializer, Serializer};
+
+/// Serializes `value` as base64 if the format is human-readable, and as a
+/// byte string otherwise.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + AsRef<[u8]>,
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        Codec::Base64.serialize(value.as_ref(), serializer)
+    } else {
+        serializer.serialize_bytes(value.as_ref())
+    }
+}
+
+/// Deserializes a buffer written by [`serialize`].
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Buffer,
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(TextVisitor::new(Codec::Base64))
+    } else {
+        T::deserialize_bytes(deserializer)
+    }
+}
@@ -0,0 +1,112 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Zero-copy deserialization of `Bytes` from a `Bytes` input.
+
+use crate::Bytes;
+use core::cell::RefCell;
+
+std::thread_local! {
+    static SOURCE: RefCell<Option<Bytes>> = RefCell::new(None);
+}
+
+/// Makes deserialized [`Bytes`] and [`ByteStr`] values share the storage of a
+/// source buffer instead of copying out of it.
+///
+/// While the guard is alive, deserializing a `Bytes` or `ByteStr` on this
+/// thread checks whether the borrowed slice handed to the visitor lies inside
+/// `source`, and if so returns `source.slice_ref(slice)`. This works with any
+/// format that deserializes from a `&[u8]` and borrows byte strings or strings
+/// from it, such as `serde_json::from_slice`. Anything else is copied as
+/// usual; slices short enough to be stored inline are copied as well, and so
+/// are `Bytes` decoded from text by the [`hex`], [`base64`] and
+/// [`human_readable`] modules.
+///
+/// The source lives in a thread local because serde gives visitors no way to
+/// reach the deserializer. Guards nest: dropping one restores the source that
+/// was current when it was created, so guards should be dropped in reverse
+/// order of creation.
+///
+/// [`ByteStr`]: crate::ByteStr
+/// [`hex`]: crate::serde::hex
+/// [`base64`]: crate::serde::base64
+/// [`human_readable`]: crate::serde::human_readable
+///
+/// # Examples
+///
+/// ```
+/// use rcbytes::serde::SourceGuard;
+/// use rcbytes::{ByteStr, Bytes};
+///
+/// let src = Bytes::from(r#"{"key": "a value long enough to not be inlined"}"#);
+///
+/// let guard = SourceGuard::new(&src);
+/// let map: std::collections::HashMap<String, ByteStr> = serde_json::from_slice(&src).unwrap();
+/// drop(guard);
+///
+/// let value = &map["key"];
+/// assert_eq!(value, "a value long enough to not be inlined");
+/// assert_eq!(value.as_ptr(), src[9..].as_ptr());
+/// ```
+#[derive(Debug)]
+#[must_use = "the source is only used while the guard is alive"]
+pub struct SourceGuard {
+    prev: Option<Bytes>,
+}
+
+impl SourceGuard {
+    /// Makes `source` the current source on this thread until the guard is
+    /// dropped.
+    pub fn new(source: &Bytes) -> SourceGuard {
+        let prev = SOURCE.with(|cell| cell.replace(Some(source.clone())));
+        SourceGuard { prev }
+    }
+}
+
+impl Drop for SourceGuard {
+    fn drop(&mut self) {
+        let prev = self.prev.take();
+        // The thread local may already be gone if this runs during thread
+        // teardown, in which case there is nothing to restore.
+        let _ = SOURCE.try_with(|cell| *cell.borrow_mut() = prev);
+    }
+}
+
+/// Returns `sub` as a slice of the current source, or `None` if there is no
+/// source or `sub` does not lie inside it.
+pub(crate) fn slice_ref(sub: &[u8]) -> Option<Bytes> {
+    SOURCE
+        .try_with(|cell| {
+            let cell = cell.borrow();
+            let source = cell.as_ref()?;
+            let start = source.as_ptr() as usize;
+            let sub_start = sub.as_ptr() as usize;
+            if sub_start >= start && sub_start + sub.len() <= start + source.len() {
+                Some(source.slice_ref(sub))
+            } else {
+                None
+            }
+        })
+        .ok()
+        .flatten()
+}
@@ -0,0 +1,269 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Provenance tracking for shared allocations.
+//!
+//! When the `track` feature is enabled, every allocation that is created
+//! through [`BytesMut::with_capacity`] or promoted to a shared, reference
+//! counted buffer (by cloning or slicing a `Bytes`, splitting a `BytesMut`,
+//! or freezing a `BytesMut`) is recorded together with the location of the
+//! caller that caused it. The record is dropped once the last handle
+//! referencing the allocation goes away.
+//!
+//! This is a debugging aid for finding out why memory stays alive, e.g. a
+//! small `Bytes::slice` that keeps a large receive buffer from being freed.
+//! Tracking adds a table lookup to every allocation and release, so it
+//! should not be enabled in production builds.
+//!
+//! `Bytes` and `BytesMut` are not `Send`, so an allocation never leaves the
+//! thread that created it. The table is kept per thread accordingly, and
+//! [`live_allocations`] only reports allocations of the current thread.
+//!
+//! # Examples
+//!
+//! ```
+//! use rcbytes::{track, BufMut, BytesMut};
+//!
+//! let mut buf = BytesMut::with_capacity(1024);
+//! buf.put(&b"hello world"[..]);
+//! let hello = buf.split_to(5).freeze();
+//!
+//! let live = track::live_allocations();
+//! let alloc = live
+//!     .iter()
+//!     .find(|a| a.size() == 1024)
+//!     .expect("allocation is tracked");
+//!
+//! assert_eq!(alloc.ref_count(), 2);
+//! assert_eq!(alloc.origin().file(), file!());
+//!
+//! drop(hello);
+//! drop(buf);
+//! assert!(track::live_allocations().iter().all(|a| a.size() != 1024));
+//! ```
+//!
+//! [`BytesMut::with_capacity`]: crate::BytesMut::with_capacity
+
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::panic::Location;
+use std::collections::HashMap;
+use std::thread_local;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+/// A snapshot of an allocation that is still alive.
+///
+/// Returned by [`live_allocations`].
+#[derive(Clone)]
+pub struct Allocation {
+    size: usize,
+    ref_count: usize,
+    origin: &'static Location<'static>,
+    age: Duration,
+}
+
+impl Allocation {
+    /// Returns the size of the underlying buffer in bytes.
+    ///
+    /// This is the capacity of the allocation, not the length of any handle
+    /// pointing into it.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of handles sharing the allocation.
+    ///
+    /// An allocation that has not been promoted to a shared buffer yet is
+    /// owned by a single handle and reports `1`.
+    pub fn ref_count(&self) -> usize {
+        self.ref_count
+    }
+
+    /// Returns the location of the call that created or promoted the
+    /// allocation.
+    pub fn origin(&self) -> &'static Location<'static> {
+        self.origin
+    }
+
+    /// Returns how long ago the allocation was recorded.
+    pub fn age(&self) -> Duration {
+        self.age
+    }
+}
+
+impl fmt::Debug for Allocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Allocation")
+            .field("size", &self.size)
+            .field("ref_count", &self.ref_count)
+            .field("origin", &format_args!("{}", self.origin))
+            .field("age", &self.age)
+            .finish()
+    }
+}
+
+/// Returns every tracked allocation of the current thread that is still
+/// alive, oldest first.
+pub fn live_allocations() -> Vec<Allocation> {
+    let now = Instant::now();
+    let mut live = REGISTRY
+        .try_with(|registry| {
+            registry
+                .borrow()
+                .values()
+                .map(|entry| Allocation {
+                    size: entry.size,
+                    // Safety: entries are removed before the shared state
+                    // holding the counter is freed.
+                    ref_count: entry.ref_cnt.map_or(1, |cnt| unsafe { (*cnt).get() }),
+                    origin: entry.origin,
+                    age: now.saturating_duration_since(entry.created),
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    live.sort_by_key(|a| core::cmp::Reverse(a.age));
+    live
+}
+
+struct Entry {
+    size: usize,
+    ref_cnt: Option<*const Cell<usize>>,
+    origin: &'static Location<'static>,
+    created: Instant,
+}
+
+thread_local! {
+    // Keyed by the address of the start of the allocation, which stays the
+    // same when a buffer is promoted from its `Vec` to its shared
+    // representation.
+    static REGISTRY: RefCell<HashMap<usize, Entry>> = RefCell::new(HashMap::new());
+
+    // `Bytes::clone` goes through the vtable, which loses the
+    // `#[track_caller]` chain. The caller is stashed here for the duration of
+    // the vtable call instead.
+    static CALLER: Cell<Option<&'static Location<'static>>> = Cell::new(None);
+}
+
+fn with_registry<F>(f: F)
+where
+    F: FnOnce(&mut HashMap<usize, Entry>),
+{
+    // The table may already be gone if a buffer is dropped while the thread
+    // is being torn down, in which case there is nothing left to report.
+    let _ = REGISTRY.try_with(|registry| f(&mut registry.borrow_mut()));
+}
+
+/// Restores the previous caller when dropped.
+pub(crate) struct CallerGuard(Option<&'static Location<'static>>);
+
+impl Drop for CallerGuard {
+    fn drop(&mut self) {
+        let prev = self.0;
+        let _ = CALLER.try_with(|caller| caller.set(prev));
+    }
+}
+
+/// Records `caller` as the origin of any promotion performed until the
+/// returned guard is dropped.
+pub(crate) fn enter(caller: &'static Location<'static>) -> CallerGuard {
+    CallerGuard(CALLER.try_with(|c| c.replace(Some(caller))).unwrap_or(None))
+}
+
+#[track_caller]
+fn caller() -> &'static Location<'static> {
+    match CALLER.try_with(Cell::get) {
+        Ok(Some(caller)) => caller,
+        // Not called through the vtable, `#[track_caller]` already points at
+        // the right place.
+        _ => Location::caller(),
+    }
+}
+
+/// Records a freshly allocated buffer.
+#[track_caller]
+pub(crate) fn create(buf: *const u8, size: usize) {
+    if size == 0 {
+        return;
+    }
+
+    let origin = Location::caller();
+    with_registry(|registry| {
+        registry.insert(
+            buf as usize,
+            Entry {
+                size,
+                ref_cnt: None,
+                origin,
+                created: Instant::now(),
+            },
+        );
+    });
+}
+
+/// Records that the buffer starting at `buf` is now shared and reference
+/// counted through `ref_cnt`, or is handed out as a `Bytes` if `ref_cnt` is
+/// `None`.
+///
+/// Buffers that were already tracked keep their original origin.
+#[track_caller]
+pub(crate) fn promote(buf: *const u8, size: usize, ref_cnt: Option<&Cell<usize>>) {
+    if size == 0 {
+        return;
+    }
+
+    let origin = caller();
+    let ref_cnt = ref_cnt.map(|cnt| cnt as *const Cell<usize>);
+    with_registry(|registry| {
+        let entry = registry.entry(buf as usize).or_insert_with(|| Entry {
+            size,
+            ref_cnt: None,
+            origin,
+            created: Instant::now(),
+        });
+        entry.size = size;
+        entry.ref_cnt = ref_cnt;
+    });
+}
+
+/// Records that a tracked buffer was reallocated.
+pub(crate) fn moved(old: *const u8, new: *const u8, size: usize) {
+    with_registry(|registry| {
+        if let Some(mut entry) = registry.remove(&(old as usize)) {
+            entry.size = size;
+            if size != 0 {
+                registry.insert(new as usize, entry);
+            }
+        }
+    });
+}
+
+/// Records that the buffer starting at `buf` was freed or handed back to a
+/// `Vec`.
+pub(crate) fn release(buf: *const u8) {
+    with_registry(|registry| {
+        registry.remove(&(buf as usize));
+    });
+}
@@ -0,0 +1,565 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Encoding fixed-layout messages field by field.
+//!
+//! [`BufEncode`] writes a value to any [`BufMut`] and [`BufDecode`] reads it
+//! back from any [`Buf`]. Integers and floats are big-endian by default,
+//! `bool` is a single byte, arrays are their elements back to back, and
+//! `Bytes`, `ByteStr`, `String` and `Vec` are prefixed with their length as
+//! a varint. Decoding a `Bytes` or `ByteStr` uses `copy_to_bytes`, so it
+//! does not copy when reading from a `Bytes`.
+//!
+//! With the `derive` feature enabled, which needs Rust 1.71,
+//! `#[derive(BufEncode, BufDecode)]` implements both traits for structs and
+//! enums. Fields are encoded in declaration order, and `#[wire(...)]`
+//! attributes change how a field is laid out:
+//!
+//! | Attribute                    | Encoding                                             |
+//! |------------------------------|------------------------------------------------------|
+//! | `#[wire(le)]`                | little-endian integer or float, see [`LittleEndian`] |
+//! | `#[wire(varint)]`            | LEB128 varint, zigzag for signed, see [`Varint`]     |
+//! | `#[wire(len = "u16")]`       | length prefix of the given [`IntRepr`]               |
+//! | `#[wire(len = "u32", le)]`   | little-endian length prefix                          |
+//! | `#[wire(skip)]`              | not encoded, decoded as `Default::default()`         |
+//!
+//! Length prefixes apply to [`PrefixedEncode`] and [`PrefixedDecode`] types and may be `"u8"`,
+//! `"u16"`, `"u32"`, `"u64"` or `"varint"`. They count bytes for `Bytes`,
+//! `ByteStr` and `String` and elements for `Vec`. A `Vec` prefix larger than
+//! the bytes left to decode is rejected, even for elements that encode to
+//! nothing.
+//!
+//! An enum is encoded as a tag followed by the fields of its variant. The tag
+//! is a `u8` unless the enum has a `#[wire(tag = "u16")]` attribute, which
+//! takes the same representations as `len`. A variant's tag is its
+//! discriminant, or the value of its own `#[wire(tag = 7)]` attribute. A tag
+//! that doesn't fit in the representation is rejected at compile time:
+//!
+//! ```compile_fail
+//! use rcbytes::wire::BufEncode;
+//!
+//! #[derive(BufEncode)]
+//! enum Kind {
+//!     Data,
+//!     #[wire(tag = 300)]
+//!     Ping,
+//! }
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use rcbytes::wire::{BufDecode, BufEncode};
+//! use rcbytes::{Bytes, BytesMut};
+//!
+//! #[derive(Debug, PartialEq, BufEncode, BufDecode)]
+//! struct Frame {
+//!     kind: Kind,
+//!     #[wire(varint)]
+//!     stream: u64,
+//!     #[wire(len = "u16")]
+//!     payload: Bytes,
+//! }
+//!
+//! #[derive(Debug, PartialEq, BufEncode, BufDecode)]
+//! enum Kind {
+//!     Data = 0,
+//!     Ping = 6,
+//! }
+//!
+//! let frame = Frame {
+//!     kind: Kind::Ping,
+//!     stream: 300,
+//!     payload: Bytes::from_static(b"hello"),
+//! };
+//!
+//! let mut buf = BytesMut::new();
+//! frame.encode(&mut buf);
+//! assert_eq!(buf, &b"\x06\xac\x02\x00\x05hello"[..]);
+//!
+//! let mut src = buf.freeze();
+//! assert_eq!(Frame::decode(&mut src), Ok(frame));
+//! # }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::mem::{self, MaybeUninit};
+use core::{cmp, fmt, ptr, str};
+
+use crate::buf::TryGetError;
+use crate::encoding::varint::{self, VarintError};
+use crate::{Buf, BufMut, ByteStr, Bytes};
+
+#[cfg(feature = "derive")]
+pub use rcbytes_derive::{BufDecode, BufEncode};
+
+/// The error returned when decoding a value fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WireError {
+    /// The input ended before the value was complete.
+    UnexpectedEof,
+    /// A varint or length does not fit the type it is decoded into.
+    Overflow,
+    /// A `bool` holds a byte other than 0 or 1.
+    InvalidBool(u8),
+    /// A string is not valid UTF-8.
+    InvalidUtf8(str::Utf8Error),
+    /// An enum tag does not match any variant.
+    InvalidTag(u64),
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            WireError::UnexpectedEof => f.write_str("unexpected end of input"),
+            WireError::Overflow => f.write_str("value overflows its type"),
+            WireError::InvalidBool(b) => write!(f, "invalid bool {:#04x}", b),
+            WireError::InvalidUtf8(ref e) => fmt::Display::fmt(e, f),
+            WireError::InvalidTag(tag) => write!(f, "invalid enum tag {}", tag),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WireError {}
+
+impl From<TryGetError> for WireError {
+    fn from(_: TryGetError) -> WireError {
+        WireError::UnexpectedEof
+    }
+}
+
+impl From<VarintError> for WireError {
+    fn from(err: VarintError) -> WireError {
+        match err {
+            VarintError::UnexpectedEof => WireError::UnexpectedEof,
+            VarintError::Overflow => WireError::Overflow,
+        }
+    }
+}
+
+/// A value that can be written to a [`BufMut`].
+pub trait BufEncode {
+    /// Writes `self` to `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` runs out of capacity, or if a length does not fit its
+    /// prefix.
+    fn encode<B: BufMut>(&self, buf: &mut B);
+}
+
+/// A value that can be read from a [`Buf`].
+pub trait BufDecode: Sized {
+    /// Reads a value from the front of `buf`.
+    ///
+    /// On error, `buf` may have been partially consumed.
+    fn decode<B: Buf>(buf: &mut B) -> Result<Self, WireError>;
+}
+
+/// A value with a little-endian encoding, used for `#[wire(le)]` fields.
+pub trait LittleEndian: Sized {
+    /// Writes `self` to `buf` in little-endian byte order.
+    fn encode_le<B: BufMut>(&self, buf: &mut B);
+
+    /// Reads a little-endian value from the front of `buf`.
+    fn decode_le<B: Buf>(buf: &mut B) -> Result<Self, WireError>;
+}
+
+/// An integer with a varint encoding, used for `#[wire(varint)]` fields.
+///
+/// Signed integers are zigzag mapped first, see
+/// [`encoding::varint`](crate::encoding::varint).
+pub trait Varint: Sized {
+    /// Writes `self` to `buf` as a varint.
+    fn encode_varint<B: BufMut>(&self, buf: &mut B);
+
+    /// Reads a varint from the front of `buf`.
+    fn decode_varint<B: Buf>(buf: &mut B) -> Result<Self, WireError>;
+}
+
+/// A value made of a variable number of units, written after a length
+/// prefix.
+///
+/// The prefix is written by [`IntRepr::put_prefixed`]. The [`BufEncode`]
+/// implementations of these types use a varint.
+pub trait PrefixedEncode {
+    /// Returns the length to write in the prefix.
+    fn prefix_len(&self) -> usize;
+
+    /// Writes the contents of `self` without the prefix.
+    fn encode_body<B: BufMut>(&self, buf: &mut B);
+}
+
+/// A value made of a variable number of units, read after a length prefix.
+///
+/// The prefix is read by [`IntRepr::get_prefixed`]. The [`BufDecode`]
+/// implementations of these types use a varint.
+pub trait PrefixedDecode: Sized {
+    /// Reads a value whose prefix held `len`.
+    fn decode_body<B: Buf>(buf: &mut B, len: usize) -> Result<Self, WireError>;
+}
+
+/// How a length prefix or an enum tag is encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IntRepr {
+    /// A single byte.
+    U8,
+    /// A big-endian `u16`.
+    U16,
+    /// A little-endian `u16`.
+    U16Le,
+    /// A big-endian `u32`.
+    U32,
+    /// A little-endian `u32`.
+    U32Le,
+    /// A big-endian `u64`.
+    U64,
+    /// A little-endian `u64`.
+    U64Le,
+    /// A LEB128 varint.
+    Varint,
+}
+
+impl IntRepr {
+    /// Writes `value` to `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` does not fit this representation.
+    pub fn put<B: BufMut>(self, buf: &mut B, value: u64) {
+        let max = match self {
+            IntRepr::U8 => u8::MAX as u64,
+            IntRepr::U16 | IntRepr::U16Le => u16::MAX as u64,
+            IntRepr::U32 | IntRepr::U32Le => u32::MAX as u64,
+            IntRepr::U64 | IntRepr::U64Le | IntRepr::Varint => u64::MAX,
+        };
+        assert!(
+            value <= max,
+            "value {} does not fit in a {:?} prefix",
+            value,
+            self
+        );
+
+        match self {
+            IntRepr::U8 => buf.put_u8(value as u8),
+            IntRepr::U16 => buf.put_u16(value as u16),
+            IntRepr::U16Le => buf.put_u16_le(value as u16),
+            IntRepr::U32 => buf.put_u32(value as u32),
+            IntRepr::U32Le => buf.put_u32_le(value as u32),
+            IntRepr::U64 => buf.put_u64(value),
+            IntRepr::U64Le => buf.put_u64_le(value),
+            IntRepr::Varint => varint::encode_to(value, buf),
+        }
+    }
+
+    /// Reads a value from the front of `buf`.
+    pub fn get<B: Buf>(self, buf: &mut B) -> Result<u64, WireError> {
+        Ok(match self {
+            IntRepr::U8 => buf.try_get_u8()? as u64,
+            IntRepr::U16 => buf.try_get_u16()? as u64,
+            IntRepr::U16Le => buf.try_get_u16_le()? as u64,
+            IntRepr::U32 => buf.try_get_u32()? as u64,
+            IntRepr::U32Le => buf.try_get_u32_le()? as u64,
+            IntRepr::U64 => buf.try_get_u64()?,
+            IntRepr::U64Le => buf.try_get_u64_le()?,
+            IntRepr::Varint => varint::decode(buf)?,
+        })
+    }
+
+    /// Writes the length of `value` in this representation, followed by
+    /// `value` itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length does not fit this representation.
+    pub fn put_prefixed<T: PrefixedEncode, B: BufMut>(self, buf: &mut B, value: &T) {
+        self.put(buf, value.prefix_len() as u64);
+        value.encode_body(buf);
+    }
+
+    /// Reads a length in this representation and then a value of that
+    /// length.
+    pub fn get_prefixed<T: PrefixedDecode, B: Buf>(self, buf: &mut B) -> Result<T, WireError> {
+        let len = usize::try_from(self.get(buf)?).map_err(|_| WireError::Overflow)?;
+        T::decode_body(buf, len)
+    }
+}
+
+// ===== primitives =====
+
+impl BufEncode for bool {
+    fn encode<B: BufMut>(&self, buf: &mut B) {
+        buf.put_u8(*self as u8);
+    }
+}
+
+impl BufDecode for bool {
+    fn decode<B: Buf>(buf: &mut B) -> Result<Self, WireError> {
+        match buf.try_get_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(WireError::InvalidBool(b)),
+        }
+    }
+}
+
+macro_rules! fixed {
+    ($($ty:ty => $put:ident, $put_le:ident, $get:ident, $get_le:ident;)*) => {$(
+        impl BufEncode for $ty {
+            #[inline]
+            fn encode<B: BufMut>(&self, buf: &mut B) {
+                buf.$put(*self);
+            }
+        }
+
+        impl BufDecode for $ty {
+            #[inline]
+            fn decode<B: Buf>(buf: &mut B) -> Result<Self, WireError> {
+                Ok(buf.$get()?)
+            }
+        }
+
+        impl LittleEndian for $ty {
+            #[inline]
+            fn encode_le<B: BufMut>(&self, buf: &mut B) {
+                buf.$put_le(*self);
+            }
+
+            #[inline]
+            fn decode_le<B: Buf>(buf: &mut B) -> Result<Self, WireError> {
+                Ok(buf.$get_le()?)
+            }
+        }
+    )*};
+}
+
+fixed! {
+    u8 => put_u8, put_u8, try_get_u8, try_get_u8;
+    i8 => put_i8, put_i8, try_get_i8, try_get_i8;
+    u16 => put_u16, put_u16_le, try_get_u16, try_get_u16_le;
+    i16 => put_i16, put_i16_le, try_get_i16, try_get_i16_le;
+    u32 => put_u32, put_u32_le, try_get_u32, try_get_u32_le;
+    i32 => put_i32, put_i32_le, try_get_i32, try_get_i32_le;
+    u64 => put_u64, put_u64_le, try_get_u64, try_get_u64_le;
+    i64 => put_i64, put_i64_le, try_get_i64, try_get_i64_le;
+    u128 => put_u128, put_u128_le, try_get_u128, try_get_u128_le;
+    i128 => put_i128, put_i128_le, try_get_i128, try_get_i128_le;
+    f32 => put_f32, put_f32_le, try_get_f32, try_get_f32_le;
+    f64 => put_f64, put_f64_le, try_get_f64, try_get_f64_le;
+}
+
+macro_rules! varint_unsigned {
+    ($($ty:ty),*) => {$(
+        impl Varint for $ty {
+            #[inline]
+            fn encode_varint<B: BufMut>(&self, buf: &mut B) {
+                varint::encode_to(*self as u64, buf);
+            }
+
+            #[inline]
+            fn decode_varint<B: Buf>(buf: &mut B) -> Result<Self, WireError> {
+                <$ty>::try_from(varint::decode(buf)?).map_err(|_| WireError::Overflow)
+            }
+        }
+    )*};
+}
+
+macro_rules! varint_signed {
+    ($($ty:ty),*) => {$(
+        impl Varint for $ty {
+            #[inline]
+            fn encode_varint<B: BufMut>(&self, buf: &mut B) {
+                varint::encode_to(varint::zigzag_encode(*self as i64), buf);
+            }
+
+            #[inline]
+            fn decode_varint<B: Buf>(buf: &mut B) -> Result<Self, WireError> {
+                let value = varint::zigzag_decode(varint::decode(buf)?);
+                <$ty>::try_from(value).map_err(|_| WireError::Overflow)
+            }
+        }
+    )*};
+}
+
+varint_unsigned!(u8, u16, u32, u64);
+varint_signed!(i8, i16, i32, i64);
+
+// ===== arrays =====
+
+impl<T: BufEncode, const N: usize> BufEncode for [T; N] {
+    fn encode<B: BufMut>(&self, buf: &mut B) {
+        for item in self {
+            item.encode(buf);
+        }
+    }
+}
+
+impl<T: BufDecode, const N: usize> BufDecode for [T; N] {
+    fn decode<B: Buf>(buf: &mut B) -> Result<Self, WireError> {
+        // Safety: an array of `MaybeUninit` needs no initialization.
+        let mut out: [MaybeUninit<T>; N] = unsafe { MaybeUninit::uninit().assume_init() };
+
+        for i in 0..N {
+            match T::decode(buf) {
+                Ok(item) => out[i] = MaybeUninit::new(item),
+                Err(err) => {
+                    for item in &mut out[..i] {
+                        // Safety: the first `i` items were initialized above.
+                        unsafe { ptr::drop_in_place(item.as_mut_ptr()) };
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        // Safety: every item was initialized, and `MaybeUninit<T>` has the
+        // same layout as `T`.
+        Ok(unsafe { ptr::read(out.as_ptr() as *const [T; N]) })
+    }
+}
+
+// ===== length prefixed =====
+
+fn check_remaining<B: Buf>(buf: &B, len: usize) -> Result<(), WireError> {
+    if buf.remaining() < len {
+        return Err(WireError::UnexpectedEof);
+    }
+    Ok(())
+}
+
+impl PrefixedEncode for Bytes {
+    fn prefix_len(&self) -> usize {
+        self.len()
+    }
+
+    fn encode_body<B: BufMut>(&self, buf: &mut B) {
+        buf.put_slice(self);
+    }
+}
+
+impl PrefixedDecode for Bytes {
+    fn decode_body<B: Buf>(buf: &mut B, len: usize) -> Result<Self, WireError> {
+        check_remaining(buf, len)?;
+        Ok(buf.copy_to_bytes(len))
+    }
+}
+
+impl PrefixedEncode for ByteStr {
+    fn prefix_len(&self) -> usize {
+        self.len()
+    }
+
+    fn encode_body<B: BufMut>(&self, buf: &mut B) {
+        buf.put_slice(self.as_bytes());
+    }
+}
+
+impl PrefixedDecode for ByteStr {
+    fn decode_body<B: Buf>(buf: &mut B, len: usize) -> Result<Self, WireError> {
+        let bytes = Bytes::decode_body(buf, len)?;
+        ByteStr::from_utf8(bytes).map_err(|e| WireError::InvalidUtf8(e.utf8_error()))
+    }
+}
+
+impl PrefixedEncode for String {
+    fn prefix_len(&self) -> usize {
+        self.len()
+    }
+
+    fn encode_body<B: BufMut>(&self, buf: &mut B) {
+        buf.put_slice(self.as_bytes());
+    }
+}
+
+impl PrefixedDecode for String {
+    fn decode_body<B: Buf>(buf: &mut B, len: usize) -> Result<Self, WireError> {
+        check_remaining(buf, len)?;
+        let mut vec = alloc::vec![0; len];
+        buf.copy_to_slice(&mut vec);
+        String::from_utf8(vec).map_err(|e| WireError::InvalidUtf8(e.utf8_error()))
+    }
+}
+
+impl<T: BufEncode> PrefixedEncode for Vec<T> {
+    fn prefix_len(&self) -> usize {
+        self.len()
+    }
+
+    fn encode_body<B: BufMut>(&self, buf: &mut B) {
+        for item in self {
+            item.encode(buf);
+        }
+    }
+}
+
+impl<T: BufDecode> PrefixedDecode for Vec<T> {
+    fn decode_body<B: Buf>(buf: &mut B, len: usize) -> Result<Self, WireError> {
+        // The prefix comes from the input. Every element is taken to use at
+        // least one byte of it, so a prefix larger than what is left is
+        // rejected up front. Without this a zero-sized `T`, which decodes
+        // from no input at all, would loop as many times as the prefix says.
+        check_remaining(buf, len)?;
+        // Don't let the prefix size the allocation beyond the input either.
+        let fits = buf.remaining() / cmp::max(mem::size_of::<T>(), 1);
+        let mut vec = Vec::with_capacity(cmp::min(len, fits));
+        for _ in 0..len {
+            vec.push(T::decode(buf)?);
+        }
+        Ok(vec)
+    }
+}
+
+macro_rules! varint_prefixed {
+    ($($ty:ty),*) => {$(
+        impl BufEncode for $ty {
+            fn encode<B: BufMut>(&self, buf: &mut B) {
+                IntRepr::Varint.put_prefixed(buf, self);
+            }
+        }
+
+        impl BufDecode for $ty {
+            fn decode<B: Buf>(buf: &mut B) -> Result<Self, WireError> {
+                IntRepr::Varint.get_prefixed(buf)
+            }
+        }
+    )*};
+}
+
+varint_prefixed!(Bytes, ByteStr, String);
+
+impl<T: BufEncode> BufEncode for Vec<T> {
+    fn encode<B: BufMut>(&self, buf: &mut B) {
+        IntRepr::Varint.put_prefixed(buf, self);
+    }
+}
+
+impl<T: BufDecode> BufDecode for Vec<T> {
+    fn decode<B: Buf>(buf: &mut B) -> Result<Self, WireError> {
+        IntRepr::Varint.get_prefixed(buf)
+    }
+}
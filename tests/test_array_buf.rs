@@ -0,0 +1,137 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::buf::ArrayBuf;
+use rcbytes::{Buf, BufMut, Bytes};
+
+#[test]
+fn write_then_read() {
+    let mut buf = ArrayBuf::<8>::new();
+    assert!(buf.is_empty());
+    assert_eq!(buf.capacity(), 8);
+    assert_eq!(buf.remaining_mut(), 8);
+
+    buf.put_u32(0xdead_beef);
+    buf.put_slice(b"ab");
+    assert_eq!(buf.len(), 6);
+    assert_eq!(buf.remaining_mut(), 2);
+    assert_eq!(buf, &b"\xde\xad\xbe\xefab"[..]);
+
+    assert_eq!(buf.get_u32(), 0xdead_beef);
+    assert_eq!(buf.chunk(), b"ab");
+    buf[0] = b'A';
+    assert_eq!(buf.copy_to_bytes(2), "Ab");
+    assert!(buf.is_empty());
+    assert_eq!(buf.remaining_mut(), 8);
+}
+
+#[test]
+fn reuses_space_freed_by_reads() {
+    let mut buf = ArrayBuf::<4>::new();
+    buf.put_slice(b"abcd");
+    assert!(buf.is_full());
+    assert_eq!(buf.remaining_mut(), 0);
+    assert_eq!(buf.chunk_mut().len(), 0);
+
+    buf.advance(3);
+    assert_eq!(buf.remaining_mut(), 3);
+    // The unread byte is moved to the front to make room.
+    buf.put_slice(b"ef");
+    assert_eq!(buf, &b"def"[..]);
+    buf.put_u8(b'g');
+    assert_eq!(buf, &b"defg"[..]);
+
+    buf.advance(1);
+    assert_eq!(buf.chunk_mut().len(), 1);
+    buf.put(&b"h"[..]);
+    assert_eq!(buf, &b"efgh"[..]);
+}
+
+#[test]
+fn chunk_mut_spans_space_freed_by_reads() {
+    let mut buf = ArrayBuf::<8>::new();
+    buf.put_slice(b"abcdef");
+    buf.advance(4);
+    let chunk = buf.chunk_mut();
+    assert_eq!(chunk.len(), 6);
+    let ptr = chunk.as_mut_ptr();
+    assert_eq!(buf, &b"ef"[..]);
+
+    // `put_with` writes in place instead of going through a scratch buffer.
+    let n = buf.put_with(6, |dst| {
+        assert_eq!(dst.as_mut_ptr(), ptr);
+        dst.copy_from_slice(b"ghijkl");
+        6
+    });
+    assert_eq!(n, 6);
+    assert_eq!(buf, &b"efghijkl"[..]);
+}
+
+#[test]
+#[should_panic]
+fn put_slice_overflow() {
+    let mut buf = ArrayBuf::<4>::new();
+    buf.put_slice(b"abc");
+    buf.put_slice(b"de");
+}
+
+#[test]
+#[should_panic]
+fn advance_past_remaining() {
+    let mut buf = ArrayBuf::<4>::new();
+    buf.put_slice(b"abc");
+    buf.advance(4);
+}
+
+#[test]
+fn conversions() {
+    let buf = ArrayBuf::from(*b"0123456789abcdefghij");
+    assert!(buf.is_full());
+    assert_eq!(Bytes::from(buf.clone()), "0123456789abcdefghij");
+
+    let mut short = buf;
+    short.truncate(3);
+    assert_eq!(Bytes::from(short), "012");
+
+    let mut a = ArrayBuf::<8>::default();
+    let mut b = ArrayBuf::<8>::new();
+    a.put_slice(b"xyz");
+    b.put_slice(b"_xyz");
+    b.advance(1);
+    assert_eq!(a, b);
+    a.clear();
+    assert!(a < b);
+}
+
+#[test]
+fn debug() {
+    let mut buf = ArrayBuf::<8>::new();
+    buf.put_slice(b"hi\n\0");
+    assert_eq!(format!("{:?}", buf), r#"b"hi\n\0""#);
+    assert_eq!(
+        format!("{:#?}", buf),
+        "00000000: 6869 0a00                                hi.."
+    );
+}
@@ -0,0 +1,289 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::buf::{BitError, BitOrder, BitReader, BitWriter};
+use rcbytes::{Buf, BufMut};
+
+#[test]
+fn read_msb_first() {
+    let mut bits = BitReader::new(&b"\xb5\x0f"[..]);
+
+    assert_eq!(bits.read_bits(3), Ok(0b101));
+    assert_eq!(bits.read_bits(7), Ok(0b101_0100));
+    assert!(!bits.is_aligned());
+    assert_eq!(bits.remaining_bits(), 6);
+    assert_eq!(bits.read_bits(6), Ok(0b00_1111));
+    assert!(bits.is_aligned());
+    assert_eq!(bits.read_bits(0), Ok(0));
+}
+
+#[test]
+fn read_lsb_first() {
+    let mut bits = BitReader::with_order(&b"\xb5\x0f"[..], BitOrder::LsbFirst);
+    assert_eq!(bits.order(), BitOrder::LsbFirst);
+
+    assert_eq!(bits.read_bits(3), Ok(0b101));
+    assert_eq!(bits.read_bits(7), Ok(0b111_0110));
+    assert_eq!(bits.read_bits(6), Ok(0b00_0011));
+}
+
+#[test]
+fn read_across_chunks() {
+    let buf = (&b"\x12\x34"[..])
+        .chain(&b"\x56"[..])
+        .chain(&b"\x78\x9a"[..]);
+    let mut bits = BitReader::new(buf);
+
+    assert_eq!(bits.read_bits(4), Ok(0x1));
+    assert_eq!(bits.read_bits(32), Ok(0x2345_6789));
+    assert_eq!(bits.read_bits(4), Ok(0xa));
+    assert_eq!(bits.remaining_bits(), 0);
+}
+
+#[test]
+fn read_64_bits() {
+    let data = [0xff; 9];
+    let mut bits = BitReader::new(&data[..]);
+    bits.read_bits(4).unwrap();
+    assert_eq!(bits.read_bits(64), Ok(u64::MAX));
+
+    let mut bits = BitReader::with_order(&data[..], BitOrder::LsbFirst);
+    bits.read_bits(4).unwrap();
+    assert_eq!(bits.read_bits(64), Ok(u64::MAX));
+}
+
+#[test]
+fn read_truncated() {
+    let mut bits = BitReader::new(&b"\xf0"[..]);
+    assert_eq!(bits.read_bits(3), Ok(0b111));
+
+    assert_eq!(
+        bits.read_bits(6),
+        Err(BitError::Truncated {
+            requested: 6,
+            available: 5
+        })
+    );
+    // Nothing was consumed.
+    assert_eq!(bits.read_bits(5), Ok(0b10000));
+    assert!(bits.read_bit().is_err());
+    assert_eq!(
+        bits.read_bit().unwrap_err().to_string(),
+        "not enough bits remaining (requested 1 but only 0 available)"
+    );
+}
+
+#[test]
+#[should_panic]
+fn read_too_many_bits() {
+    let mut bits = BitReader::new(&[0; 16][..]);
+    let _ = bits.read_bits(65);
+}
+
+#[test]
+fn align_to_byte() {
+    let mut bits = BitReader::new(&b"\xff\x01"[..]);
+    bits.read_bits(3).unwrap();
+    bits.align_to_byte();
+    assert_eq!(bits.remaining_bits(), 8);
+    assert_eq!(bits.read_bits(8), Ok(1));
+
+    let inner = BitReader::new(&b"\xff\x01"[..]).into_inner();
+    assert_eq!(inner.remaining(), 2);
+}
+
+#[test]
+fn write_msb_first() {
+    let mut bits = BitWriter::new(vec![]);
+    bits.write_bits(0b101, 3).unwrap();
+    bits.write_bits(0b101_0100, 7).unwrap();
+    assert_eq!(bits.get_ref(), b"\xb5");
+    assert!(!bits.is_aligned());
+    bits.write_bits(0b11, 2).unwrap();
+
+    assert_eq!(bits.into_inner(), b"\xb5\x30");
+}
+
+#[test]
+fn write_lsb_first() {
+    let mut bits = BitWriter::with_order(vec![], BitOrder::LsbFirst);
+    bits.write_bits(0b101, 3).unwrap();
+    bits.write_bits(0b111_0110, 7).unwrap();
+    bits.write_bits(0b00_0011, 6).unwrap();
+
+    assert!(bits.is_aligned());
+    assert_eq!(bits.into_inner(), b"\xb5\x0f");
+}
+
+#[test]
+fn write_only_low_bits() {
+    let mut bits = BitWriter::new(vec![]);
+    bits.write_bits(u64::MAX, 4).unwrap();
+    bits.write_bits(0, 4).unwrap();
+    bits.write_bits(u64::MAX, 64).unwrap();
+    assert_eq!(bits.into_inner(), b"\xf0\xff\xff\xff\xff\xff\xff\xff\xff");
+}
+
+#[test]
+fn write_truncated() {
+    let mut storage = [0; 2];
+    {
+        let mut bits = BitWriter::new(&mut storage[..]);
+        bits.write_bits(0x3ff, 10).unwrap();
+        assert_eq!(bits.remaining_bits(), 6);
+
+        assert_eq!(
+            bits.write_bits(0, 7),
+            Err(BitError::Truncated {
+                requested: 7,
+                available: 6
+            })
+        );
+        // 0001000
+        assert_eq!(
+            bits.write_exp_golomb(7),
+            Err(BitError::Truncated {
+                requested: 7,
+                available: 6
+            })
+        );
+        bits.write_bit(true).unwrap();
+        bits.flush();
+        assert!(bits.is_aligned());
+        assert_eq!(bits.get_ref().remaining_mut(), 0);
+    }
+    assert_eq!(storage, [0xff, 0xe0]);
+}
+
+#[test]
+fn round_trip() {
+    for &order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let mut bits = BitWriter::with_order(Vec::new(), order);
+        for n in 0..=64u32 {
+            let value = 0x9e37_79b9_7f4a_7c15u64 >> (64 - n.max(1)) as u64;
+            bits.write_bits(value, n).unwrap();
+        }
+        bits.write_bit(true).unwrap();
+        let buf = bits.into_inner();
+
+        let mut bits = BitReader::with_order(&buf[..], order);
+        for n in 0..=64u32 {
+            let value = 0x9e37_79b9_7f4a_7c15u64 >> (64 - n.max(1)) as u64;
+            assert_eq!(bits.read_bits(n), Ok(value & mask(n)), "{:?} {}", order, n);
+        }
+        assert_eq!(bits.read_bit(), Ok(true));
+        assert!(bits.remaining_bits() < 8);
+    }
+}
+
+fn mask(n: u32) -> u64 {
+    if n == 64 {
+        u64::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+#[test]
+fn exp_golomb() {
+    // 1, 010, 011, 00100, 00101
+    let mut bits = BitWriter::new(vec![]);
+    for v in 0..5 {
+        bits.write_exp_golomb(v).unwrap();
+    }
+    let buf = bits.into_inner();
+    assert_eq!(buf, b"\xa6\x42\x80");
+
+    let mut bits = BitReader::new(&buf[..]);
+    for v in 0..5 {
+        assert_eq!(bits.read_exp_golomb(), Ok(v));
+    }
+}
+
+#[test]
+fn signed_exp_golomb() {
+    let values = [0, 1, -1, 2, -2, 1000, -1000];
+
+    let mut bits = BitWriter::new(vec![]);
+    for &v in &values {
+        bits.write_signed_exp_golomb(v).unwrap();
+    }
+    let buf = bits.into_inner();
+    // 0 -> 1, 1 -> 010, -1 -> 011, 2 -> 00100, -2 -> 00101
+    assert_eq!(&buf[..2], b"\xa6\x42");
+
+    let mut bits = BitReader::new(&buf[..]);
+    for &v in &values {
+        assert_eq!(bits.read_signed_exp_golomb(), Ok(v));
+    }
+}
+
+#[test]
+fn exp_golomb_extremes() {
+    for &order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let unsigned = [0, 1, 2, 1 << 40, u64::MAX - 1, u64::MAX];
+        let signed = [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX];
+
+        let mut bits = BitWriter::with_order(vec![], order);
+        for &v in &unsigned {
+            bits.write_exp_golomb(v).unwrap();
+        }
+        for &v in &signed {
+            bits.write_signed_exp_golomb(v).unwrap();
+        }
+        let buf = bits.into_inner();
+
+        let mut bits = BitReader::with_order(&buf[..], order);
+        for &v in &unsigned {
+            assert_eq!(bits.read_exp_golomb(), Ok(v), "{:?}", order);
+        }
+        for &v in &signed {
+            assert_eq!(bits.read_signed_exp_golomb(), Ok(v), "{:?}", order);
+        }
+    }
+}
+
+#[test]
+fn exp_golomb_errors() {
+    // 65 leading zeros
+    let mut bits = BitReader::new(&[0; 9][..]);
+    assert_eq!(bits.read_exp_golomb(), Err(BitError::Overflow));
+
+    // 64 leading zeros and a non-zero suffix is one past u64::MAX.
+    let mut bits = BitWriter::new(vec![]);
+    bits.write_bits(0, 64).unwrap();
+    bits.write_bit(true).unwrap();
+    bits.write_bits(1, 64).unwrap();
+    let buf = bits.into_inner();
+    let mut bits = BitReader::new(&buf[..]);
+    assert_eq!(bits.read_exp_golomb(), Err(BitError::Overflow));
+
+    // Runs out of bits in the suffix.
+    let mut bits = BitReader::new(&b"\x01"[..]);
+    assert!(matches!(
+        bits.read_exp_golomb(),
+        Err(BitError::Truncated { .. })
+    ));
+}
@@ -97,6 +97,101 @@ fn test_vec_deque() {
     assert_eq!(b"world piece", &out[..]);
 }
 
+/// Builds a deque whose contents wrap around the end of its allocation.
+fn wrapped_deque() -> std::collections::VecDeque<u8> {
+    let mut buffer = std::collections::VecDeque::with_capacity(16);
+    buffer.extend(b"world");
+    for &b in b"hello ".iter().rev() {
+        buffer.push_front(b);
+    }
+    assert!(!buffer.as_slices().1.is_empty());
+    buffer
+}
+
+#[test]
+fn test_vec_deque_wrapped_chunk() {
+    let mut buffer = wrapped_deque();
+    assert_eq!(11, buffer.remaining());
+
+    let first = buffer.chunk().len();
+    assert!(first < 11);
+    buffer.advance(first);
+    assert_eq!(&b"hello world"[first..], buffer.chunk());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_vec_deque_chunks_vectored() {
+    let buffer = wrapped_deque();
+
+    let mut dst = [IoSlice::new(&[]); 4];
+    let n = buffer.chunks_vectored(&mut dst[..]);
+    assert_eq!(2, n);
+    let joined: Vec<u8> = dst[..n].iter().flat_map(|s| s.iter().copied()).collect();
+    assert_eq!(b"hello world", &joined[..]);
+
+    let mut dst = [IoSlice::new(&[])];
+    assert_eq!(1, buffer.chunks_vectored(&mut dst[..]));
+    assert_eq!(buffer.chunk(), &dst[0][..]);
+
+    let mut empty = std::collections::VecDeque::new();
+    empty.extend(b"x");
+    empty.advance(1);
+    let mut dst = [IoSlice::new(&[]); 2];
+    assert_eq!(0, empty.chunks_vectored(&mut dst[..]));
+}
+
+#[test]
+fn test_vec_deque_copy_to_bytes() {
+    let mut buffer = wrapped_deque();
+
+    let bytes = buffer.copy_to_bytes(3);
+    assert_eq!(bytes, &b"hel"[..]);
+
+    // Spans both halves of the deque.
+    let bytes = buffer.copy_to_bytes(6);
+    assert_eq!(bytes, &b"lo wor"[..]);
+    assert_eq!(2, buffer.remaining());
+
+    let bytes = buffer.copy_to_bytes(2);
+    assert_eq!(bytes, &b"ld"[..]);
+    assert!(buffer.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_vec_deque_copy_to_bytes_overflow() {
+    let mut buffer = wrapped_deque();
+    let _ = buffer.copy_to_bytes(12);
+}
+
+#[test]
+fn test_try_get() {
+    let mut buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08\x09"[..];
+
+    assert_eq!(buf.try_get_u8(), Ok(1));
+    assert_eq!(buf.try_get_u16_le(), Ok(0x0302));
+    assert_eq!(buf.try_get_uint(3), Ok(0x040506));
+    assert_eq!(buf.try_get_i16(), Ok(0x0708));
+
+    let err = buf.try_get_u32().unwrap_err();
+    assert_eq!((err.requested, err.available, err.position), (4, 1, None));
+    assert_eq!(buf, b"\x09");
+
+    assert_eq!(buf.try_get_i8(), Ok(9));
+    assert!(buf.try_get_u8().is_err());
+    assert!(buf.try_get_f64().is_err());
+}
+
+#[test]
+fn test_try_get_across_chunks() {
+    let mut buf = (&b"\x00\x00"[..]).chain(&b"\x01\x00\x3F\x80\x00\x00"[..]);
+
+    assert_eq!(buf.try_get_u32(), Ok(0x0100));
+    assert_eq!(buf.try_get_f32(), Ok(1.0));
+    assert!(buf.try_get_u8().is_err());
+}
+
 #[test]
 fn test_deref_buf_forwards() {
     struct Special;
@@ -201,3 +201,252 @@ fn copy_from_slice_panics_if_different_length_2() {
     let slice = unsafe { UninitSlice::from_raw_parts_mut(data.as_mut_ptr(), 3) };
     slice.copy_from_slice(b"abcd");
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn test_chunks_vectored_mut_default() {
+    use std::io::IoSliceMut;
+
+    let mut buf = BytesMut::with_capacity(8);
+    buf.put_slice(b"ab");
+    let spare = buf.capacity() - buf.len();
+
+    assert_eq!(buf.chunks_vectored_mut(&mut []), 0);
+    {
+        let mut dst = [IoSliceMut::new(&mut []), IoSliceMut::new(&mut [])];
+        assert_eq!(buf.chunks_vectored_mut(&mut dst), 1);
+        assert_eq!(dst[0].len(), spare);
+        assert!(dst[0].iter().all(|&b| b == 0));
+        dst[0][..3].copy_from_slice(b"cde");
+    }
+    unsafe { buf.advance_mut(3) };
+    assert_eq!(buf, "abcde");
+
+    let mut full = [0u8; 0];
+    let mut slice = &mut full[..];
+    let mut dst = [IoSliceMut::new(&mut [])];
+    assert_eq!(slice.chunks_vectored_mut(&mut dst), 0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_chunks_vectored_mut_bytes_mut() {
+    use std::io::IoSliceMut;
+
+    let mut buf = BytesMut::with_capacity(16);
+    buf.put_slice(b"ab");
+    let spare = buf.capacity() - buf.len();
+
+    {
+        let mut dst = [IoSliceMut::new(&mut [])];
+        assert_eq!(buf.chunks_vectored_mut(&mut dst), 1);
+        assert_eq!(dst[0].len(), spare);
+        assert!(dst[0].iter().all(|&b| b == 0));
+        dst[0][..4].copy_from_slice(b"cdef");
+    }
+    unsafe { buf.advance_mut(2) };
+    assert_eq!(buf, "abcd");
+
+    // What is left of the spare capacity is zeroed again.
+    {
+        let mut dst = [IoSliceMut::new(&mut [])];
+        assert_eq!(buf.chunks_vectored_mut(&mut dst), 1);
+        assert_eq!(dst[0].len(), spare - 2);
+        assert!(dst[0].iter().all(|&b| b == 0));
+    }
+
+    // A split off handle shares the storage and still gets a zeroed view of
+    // its own spare capacity.
+    let mut head = buf.split_to(2);
+    head.reserve(4);
+    let mut dst = [IoSliceMut::new(&mut [])];
+    assert_eq!(head.chunks_vectored_mut(&mut dst), 1);
+    assert!(dst[0].iter().all(|&b| b == 0));
+    assert_eq!(buf, "cd");
+
+    // A full buffer grows first, like `chunk_mut`.
+    let mut full = BytesMut::new();
+    let mut dst = [IoSliceMut::new(&mut [])];
+    assert_eq!(full.chunks_vectored_mut(&mut dst), 1);
+    assert!(!dst[0].is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_chunks_vectored_mut_chain_limit() {
+    use std::io::{IoSliceMut, Read};
+
+    let mut header = [0u8; 3];
+    let mut body = [0u8; 8];
+    let mut buf = (&mut header[..]).chain_mut((&mut body[..]).limit(5));
+
+    let n = {
+        let mut dst = [
+            IoSliceMut::new(&mut []),
+            IoSliceMut::new(&mut []),
+            IoSliceMut::new(&mut []),
+        ];
+        let cnt = buf.chunks_vectored_mut(&mut dst);
+        assert_eq!(cnt, 2);
+        assert_eq!(dst[0].len(), 3);
+        assert_eq!(dst[1].len(), 5);
+        let mut src = &b"abcdefg"[..];
+        src.read_vectored(&mut dst[..cnt]).unwrap()
+    };
+    assert_eq!(n, 7);
+    // Commit a read that stopped in the middle of the second slice.
+    unsafe { buf.advance_mut(n) };
+    assert_eq!(buf.remaining_mut(), 1);
+    assert_eq!(&header, b"abc");
+    assert_eq!(&body, b"defg\0\0\0\0");
+
+    let mut body = [0u8; 8];
+    let mut limited = (&mut body[..]).limit(0);
+    let mut dst = [IoSliceMut::new(&mut [])];
+    assert_eq!(limited.chunks_vectored_mut(&mut dst), 0);
+}
+
+#[test]
+fn test_put_with_vec() {
+    let mut buf = b"ab".to_vec();
+    let n = buf.put_with(4, |dst| {
+        assert_eq!(dst.len(), 4);
+        dst.copy_from_slice(b"cdef");
+        3
+    });
+    assert_eq!(n, 3);
+    assert_eq!(buf, b"abcde");
+
+    assert_eq!(buf.put_with(0, |_| 0), 0);
+    assert_eq!(buf, b"abcde");
+}
+
+#[test]
+fn test_put_with_bytes_mut() {
+    let mut buf = BytesMut::new();
+    buf.put_slice(b"ab");
+    let n = buf.put_with(100, |dst| {
+        assert_eq!(dst.len(), 100);
+        dst[..2].copy_from_slice(b"cd");
+        2
+    });
+    assert_eq!(n, 2);
+    assert_eq!(buf, b"abcd"[..]);
+    assert!(buf.capacity() >= 102);
+}
+
+#[test]
+fn test_put_with_reports_unwritten_bytes_as_zero() {
+    let mut buf = BytesMut::with_capacity(8);
+    buf.put_with(8, |_| 8);
+    assert_eq!(buf, [0; 8][..]);
+
+    let mut buf = Vec::with_capacity(8);
+    buf.put_with(8, |_| 8);
+    assert_eq!(buf, [0; 8]);
+}
+
+#[test]
+fn test_put_with_slice() {
+    let mut data = [b'x'; 6];
+    let mut slice = &mut data[..];
+    let n = slice.put_with(4, |dst| {
+        dst[..2].copy_from_slice(b"ab");
+        2
+    });
+    assert_eq!(n, 2);
+    assert_eq!(slice.remaining_mut(), 4);
+    // The bytes that weren't written are left alone.
+    assert_eq!(&data, b"abxxxx");
+}
+
+#[test]
+fn test_put_with_non_contiguous() {
+    let mut a = [0u8; 2];
+    let mut b = [0u8; 4];
+    let mut buf = (&mut a[..]).chain_mut(&mut b[..]);
+    let n = buf.put_with(5, |dst| {
+        assert_eq!(dst.len(), 5);
+        dst[..4].copy_from_slice(b"abcd");
+        4
+    });
+    assert_eq!(n, 4);
+    assert_eq!(buf.remaining_mut(), 2);
+    assert_eq!(&a, b"ab");
+    assert_eq!(&b, b"cd\0\0");
+}
+
+#[test]
+#[should_panic(expected = "closure wrote past `max_len`")]
+fn test_put_with_past_max_len() {
+    let mut buf = BytesMut::new();
+    buf.put_with(4, |_| 5);
+}
+
+#[test]
+#[should_panic(expected = "buffer overflow")]
+fn test_put_with_overflow() {
+    let mut data = [0u8; 4];
+    let mut slice = &mut data[..];
+    slice.put_with(5, |_| 0);
+}
+
+#[test]
+fn test_bytes_mut_write_with() {
+    use std::io::Write as _;
+
+    let mut buf = BytesMut::from(&b"id="[..]);
+    let n = buf.write_with(20, |mut dst| {
+        let len = dst.len();
+        write!(dst, "{}", 12345).unwrap();
+        len - dst.len()
+    });
+    assert_eq!(n, 5);
+    assert_eq!(buf, b"id=12345"[..]);
+}
+
+#[test]
+fn test_bytes_mut_spare_capacity_mut() {
+    let mut buf = BytesMut::with_capacity(16);
+    buf.put_slice(b"ab");
+    assert_eq!(buf.spare_capacity_mut().len(), 14);
+
+    for (i, dst) in buf.spare_capacity_mut()[..3].iter_mut().enumerate() {
+        *dst = core::mem::MaybeUninit::new(b'c' + i as u8);
+    }
+    unsafe { buf.commit(3) };
+    assert_eq!(buf, b"abcde"[..]);
+    assert_eq!(buf.spare_capacity_mut().len(), 11);
+}
+
+// Run under Miri: handing out bytes that were deinitialized through
+// `spare_capacity_mut` as `&mut [u8]` is undefined behavior.
+#[cfg(feature = "std")]
+#[test]
+fn test_bytes_mut_spare_capacity_uninit_then_vectored() {
+    use core::mem::MaybeUninit;
+    use std::io::IoSliceMut;
+
+    let mut buf = BytesMut::with_capacity(16);
+    buf.put_slice(b"ab");
+    {
+        let mut dst = [IoSliceMut::new(&mut [])];
+        assert_eq!(buf.chunks_vectored_mut(&mut dst), 1);
+    }
+
+    for dst in buf.spare_capacity_mut() {
+        *dst = MaybeUninit::uninit();
+    }
+
+    let mut dst = [IoSliceMut::new(&mut [])];
+    assert_eq!(buf.chunks_vectored_mut(&mut dst), 1);
+    assert_eq!(dst[0].len(), 14);
+    assert!(dst[0].iter().all(|&b| b == 0));
+}
+
+#[test]
+#[should_panic(expected = "cannot commit past the spare capacity")]
+fn test_bytes_mut_commit_past_capacity() {
+    let mut buf = BytesMut::with_capacity(4);
+    unsafe { buf.commit(5) };
+}
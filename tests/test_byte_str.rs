@@ -0,0 +1,188 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::{ByteStr, Bytes};
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[test]
+fn from_static() {
+    let s = ByteStr::from_static("hello");
+    assert_eq!(s, "hello");
+    assert_eq!(s.len(), 5);
+    assert_eq!(ByteStr::new(), "");
+}
+
+#[test]
+fn from_string_is_zero_copy() {
+    let string = String::from("hello world, long enough to not be inlined");
+    let ptr = string.as_ptr();
+
+    let s = ByteStr::from(string);
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(s.as_bytes().as_ptr(), ptr);
+}
+
+#[test]
+fn try_from_bytes() {
+    let bytes = Bytes::from("héllo wörld");
+    let ptr = bytes.as_ptr();
+
+    let s = ByteStr::try_from(bytes).unwrap();
+    assert_eq!(s, "héllo wörld");
+    assert_eq!(s.as_ptr(), ptr);
+}
+
+#[test]
+fn try_from_invalid_bytes() {
+    let bytes = Bytes::from(&b"ok\xffno"[..]);
+
+    let err = ByteStr::try_from(bytes).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), 2);
+    assert_eq!(err.into_bytes(), &b"ok\xffno"[..]);
+}
+
+#[test]
+fn slice() {
+    let s = ByteStr::from(String::from("héllo wörld"));
+
+    let a = s.slice(0..6);
+    assert_eq!(a, "héllo");
+
+    let b = s.slice(7..);
+    assert_eq!(b, "wörld");
+
+    let c = s.slice(..=2);
+    assert_eq!(c, "hé");
+}
+
+#[test]
+fn slice_is_zero_copy() {
+    let s = ByteStr::from_static("héllo wörld, long enough to not be inlined");
+
+    let a = s.slice(7..);
+    assert_eq!(a, "wörld, long enough to not be inlined");
+    assert_eq!(a.as_ptr(), s[7..].as_ptr());
+}
+
+#[test]
+#[should_panic(expected = "byte index 2 is not a char boundary")]
+fn slice_not_char_boundary() {
+    let s = ByteStr::from("héllo");
+    let _ = s.slice(2..);
+}
+
+#[test]
+#[should_panic]
+fn slice_out_of_bounds() {
+    let s = ByteStr::from("hello");
+    let _ = s.slice(..6);
+}
+
+#[test]
+fn split_to() {
+    let mut s = ByteStr::from(String::from("héllo wörld, long enough to not be inlined"));
+    let ptr = s.as_ptr();
+
+    let head = s.split_to(7);
+    assert_eq!(head, "héllo ");
+    assert_eq!(head.as_ptr(), ptr);
+    assert_eq!(s, "wörld, long enough to not be inlined");
+}
+
+#[test]
+fn split_off() {
+    let mut s = ByteStr::from(String::from("héllo wörld"));
+
+    let tail = s.split_off(6);
+    assert_eq!(s, "héllo");
+    assert_eq!(tail, " wörld");
+}
+
+#[test]
+#[should_panic(expected = "byte index 9 is not a char boundary")]
+fn split_to_not_char_boundary() {
+    let mut s = ByteStr::from("héllo wörld");
+    let _ = s.split_to(9);
+}
+
+#[test]
+#[should_panic(expected = "byte index 2 is not a char boundary")]
+fn split_off_not_char_boundary() {
+    let mut s = ByteStr::from("héllo");
+    let _ = s.split_off(2);
+}
+
+#[test]
+fn truncate() {
+    let mut s = ByteStr::from("héllo");
+    s.truncate(10);
+    assert_eq!(s, "héllo");
+    s.truncate(3);
+    assert_eq!(s, "hé");
+}
+
+#[test]
+fn display_and_debug() {
+    let s = ByteStr::from("a \"quoted\" str");
+    assert_eq!(format!("{}", s), "a \"quoted\" str");
+    assert_eq!(format!("{:?}", s), "\"a \\\"quoted\\\" str\"");
+}
+
+#[test]
+fn eq_and_ord_with_str() {
+    let s = ByteStr::from("b");
+    let c = ByteStr::from("c");
+
+    assert_eq!(s, "b");
+    assert_eq!("b", s);
+    assert_eq!(s, String::from("b"));
+    assert_eq!(String::from("b"), s);
+    assert!(s > "a");
+    assert!("c" > s);
+    assert!(s < c);
+}
+
+#[test]
+// The `UnsafeCell` inside `Bytes` only caches the shared state, it never
+// changes the contents that are hashed.
+#[allow(clippy::mutable_key_type)]
+fn hash_map_lookup_by_str() {
+    let mut map = HashMap::new();
+    map.insert(ByteStr::from("content-type"), 1);
+    map.insert(ByteStr::from(String::from("accept")), 2);
+
+    assert_eq!(map.get("content-type"), Some(&1));
+    assert_eq!(map.get("accept"), Some(&2));
+    assert_eq!(map.get("host"), None);
+}
+
+#[test]
+fn into_bytes_and_string() {
+    let s = ByteStr::from("hello");
+    assert_eq!(Bytes::from(s.clone()), "hello");
+    assert_eq!(String::from(s), "hello");
+}
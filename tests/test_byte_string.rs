@@ -0,0 +1,169 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::{ByteStr, ByteString, BytesMut};
+
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+#[test]
+fn push_and_push_str() {
+    let mut s = ByteString::new();
+    s.push_str("caf");
+    s.push('é');
+    s.push(' ');
+    s.push('🦀');
+
+    assert_eq!(s, "café 🦀");
+    assert_eq!(s.len(), "café 🦀".len());
+}
+
+#[test]
+fn fmt_write() {
+    let mut s = ByteString::with_capacity(4);
+    let name = "id";
+    write!(s, "{}-{:03}", name, 7).unwrap();
+    s.write_char('!').unwrap();
+
+    assert_eq!(s, "id-007!");
+}
+
+#[test]
+fn freeze_is_zero_copy() {
+    let mut s = ByteString::with_capacity(64);
+    s.push_str("hello world");
+    let ptr = s.as_ptr();
+
+    let frozen: ByteStr = s.freeze();
+    assert_eq!(frozen, "hello world");
+    assert_eq!(frozen.as_ptr(), ptr);
+}
+
+#[test]
+fn split_shares_allocation() {
+    let mut s = ByteString::with_capacity(64);
+    s.push_str("first");
+    let first = s.split();
+    s.push_str("second");
+    let second = s.split();
+
+    assert_eq!(first, "first");
+    assert_eq!(second, "second");
+    assert_eq!(unsafe { first.as_ptr().add(5) }, second.as_ptr());
+    assert!(s.is_empty());
+}
+
+#[test]
+fn split_to() {
+    let mut s = ByteString::from("héllo wörld");
+
+    let head = s.split_to(7);
+    assert_eq!(head, "héllo ");
+    assert_eq!(s, "wörld");
+}
+
+#[test]
+fn split_off() {
+    let mut s = ByteString::from("héllo wörld");
+
+    let tail = s.split_off(6);
+    assert_eq!(s, "héllo");
+    assert_eq!(tail, " wörld");
+}
+
+#[test]
+#[should_panic(expected = "byte index 2 is not a char boundary")]
+fn split_to_not_char_boundary() {
+    let mut s = ByteString::from("héllo");
+    let _ = s.split_to(2);
+}
+
+#[test]
+#[should_panic(expected = "byte index 9 is not a char boundary")]
+fn split_off_not_char_boundary() {
+    let mut s = ByteString::from("héllo wörld");
+    let _ = s.split_off(9);
+}
+
+#[test]
+fn truncate() {
+    let mut s = ByteString::from("héllo");
+    s.truncate(10);
+    assert_eq!(s, "héllo");
+    s.truncate(3);
+    assert_eq!(s, "hé");
+}
+
+#[test]
+#[should_panic(expected = "byte index 2 is not a char boundary")]
+fn truncate_not_char_boundary() {
+    let mut s = ByteString::from("héllo");
+    s.truncate(2);
+}
+
+#[test]
+fn reserve_keeps_contents() {
+    let mut s = ByteString::from("héllo");
+    s.reserve(1024);
+
+    assert!(s.capacity() >= 1024 + "héllo".len());
+    assert_eq!(s, "héllo");
+}
+
+#[test]
+fn from_utf8() {
+    let s = ByteString::try_from(BytesMut::from("wörld")).unwrap();
+    assert_eq!(s, "wörld");
+
+    let err = ByteString::try_from(BytesMut::from(&b"ok\xc3"[..])).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), 2);
+    assert_eq!(err.into_bytes(), &b"ok\xc3"[..]);
+}
+
+#[test]
+fn from_string_is_zero_copy() {
+    let string = String::from("hello");
+    let ptr = string.as_ptr();
+
+    let s = ByteString::from(string);
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(String::from(s), "hello");
+}
+
+#[test]
+fn extend_and_collect() {
+    let mut s: ByteString = "héllo".chars().collect();
+    s.extend(vec![" ", "wörld"]);
+
+    assert_eq!(s, "héllo wörld");
+}
+
+#[test]
+fn mutate_through_str() {
+    let mut s = ByteString::from("hello");
+    s.make_ascii_uppercase();
+
+    assert_eq!(s, "HELLO");
+}
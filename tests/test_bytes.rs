@@ -25,7 +25,7 @@
 // SOFTWARE.
 #![warn(rust_2018_idioms)]
 
-use rcbytes::{Buf, BufMut, Bytes, BytesMut};
+use rcbytes::{Buf, BufMut, Bytes, BytesMut, GrowthPolicy};
 
 use std::usize;
 
@@ -589,6 +589,389 @@ fn reserve_shared_reuse() {
     assert_eq!(bytes.capacity(), 2009);
 }
 
+#[test]
+fn reserve_in_arc_unique_keeps_data_written_after_split() {
+    let mut bytes = BytesMut::with_capacity(8);
+    bytes.put_slice(b"ab");
+    drop(bytes.split_to(1));
+
+    // now bytes is Arc and refcount == 1, and more is written to it
+    bytes.put_slice(b"cdef");
+
+    bytes.reserve(100);
+    assert_eq!(&*bytes, b"bcdef");
+}
+
+#[test]
+fn growth_policy_default() {
+    let bytes = BytesMut::with_capacity(64);
+    assert_eq!(bytes.growth_policy(), GrowthPolicy::default());
+    assert_eq!(
+        GrowthPolicy::default(),
+        GrowthPolicy::OriginalCapacity {
+            min: 1024,
+            max: 64 * 1024
+        }
+    );
+
+    let bytes = BytesMut::with_growth_policy(64, GrowthPolicy::default());
+    assert_eq!(bytes.growth_policy(), GrowthPolicy::default());
+}
+
+#[test]
+fn growth_policy_exact() {
+    let mut bytes = BytesMut::with_growth_policy(16, GrowthPolicy::Exact);
+    assert_eq!(bytes.capacity(), 16);
+    bytes.put_slice(&[1; 16]);
+
+    bytes.reserve(1);
+    assert_eq!(bytes.capacity(), 17);
+
+    bytes.put_u8(2);
+    bytes.reserve(100);
+    assert_eq!(bytes.capacity(), 117);
+    assert_eq!(&bytes[..16], &[1; 16]);
+    assert_eq!(bytes[16], 2);
+}
+
+#[test]
+fn growth_policy_exact_shared() {
+    let mut bytes = BytesMut::with_growth_policy(2048, GrowthPolicy::Exact);
+    bytes.put_slice(b"hello");
+    let _other = bytes.split();
+
+    // now bytes is Arc and refcount == 2, the original capacity is ignored
+    bytes.put_slice(&[0; 2043]);
+    bytes.reserve(5);
+    assert_eq!(bytes.capacity(), 2048);
+    assert_eq!(bytes.growth_policy(), GrowthPolicy::Exact);
+}
+
+#[test]
+fn growth_policy_doubling() {
+    let mut bytes = BytesMut::with_growth_policy(0, GrowthPolicy::Doubling);
+
+    let mut capacities = Vec::new();
+    for _ in 0..300 {
+        bytes.put_u8(b'x');
+        if capacities.last() != Some(&bytes.capacity()) {
+            capacities.push(bytes.capacity());
+        }
+    }
+    assert_eq!(capacities, [1, 2, 4, 8, 16, 32, 64, 128, 256, 512]);
+
+    let other = bytes.split_to(100);
+    assert_eq!(other.growth_policy(), GrowthPolicy::Doubling);
+
+    // now bytes is Arc and refcount == 2
+    bytes.reserve(1000);
+    assert_eq!(bytes.capacity(), 2048);
+    assert_eq!(bytes.len(), 200);
+}
+
+#[test]
+fn growth_policy_original_capacity() {
+    let policy = GrowthPolicy::OriginalCapacity { min: 64, max: 256 };
+
+    let mut bytes = BytesMut::with_growth_policy(100, policy);
+    bytes.put_slice(b"hello");
+    let _other = bytes.split();
+
+    // now bytes is Arc and refcount == 2
+    bytes.reserve(96);
+    assert_eq!(bytes.capacity(), 100);
+    assert_eq!(bytes.growth_policy(), policy);
+
+    // the original capacity is capped at `max`
+    let mut bytes = BytesMut::with_growth_policy(1000, policy);
+    let _other = bytes.split_off(10);
+    bytes.reserve(11);
+    assert_eq!(bytes.capacity(), 256);
+
+    // and ignored below `min`
+    let mut bytes = BytesMut::with_growth_policy(32, policy);
+    let _other = bytes.split_off(10);
+    bytes.reserve(11);
+    assert_eq!(bytes.capacity(), 11);
+}
+
+#[test]
+fn growth_policy_inherited_by_splits() {
+    let mut bytes = BytesMut::with_growth_policy(64, GrowthPolicy::Exact);
+    bytes.put_slice(LONG);
+
+    let mut a = bytes.split_to(10);
+    let mut b = bytes.split_off(10);
+    let c = bytes.split();
+    assert_eq!(a.growth_policy(), GrowthPolicy::Exact);
+    assert_eq!(b.growth_policy(), GrowthPolicy::Exact);
+    assert_eq!(c.growth_policy(), GrowthPolicy::Exact);
+    assert_eq!(bytes.growth_policy(), GrowthPolicy::Exact);
+
+    // the policy also survives moving to a new allocation
+    a.reserve(1000);
+    assert_eq!(a.capacity(), 1010);
+    assert_eq!(a.growth_policy(), GrowthPolicy::Exact);
+    a.split_off(5).reserve(2000);
+
+    b.reserve(b.capacity());
+    assert_eq!(b.growth_policy(), GrowthPolicy::Exact);
+    assert_eq!(&b[..], &LONG[20..]);
+}
+
+#[test]
+fn reserve_exact_vec() {
+    let mut bytes = BytesMut::with_capacity(8);
+    bytes.put_slice(b"abcdefgh");
+    bytes.reserve_exact(3);
+    assert_eq!(bytes.capacity(), 11);
+    assert_eq!(&bytes[..], b"abcdefgh");
+
+    // space at the front is reclaimed before allocating
+    let ptr = bytes.as_ptr();
+    bytes.advance(8);
+    bytes.reserve_exact(11);
+    assert_eq!(bytes.capacity(), 11);
+    assert_eq!(bytes.as_ptr(), ptr);
+}
+
+#[test]
+fn reserve_exact_in_arc_unique() {
+    let mut bytes = BytesMut::with_capacity(1000);
+    let _ = bytes.split();
+
+    // now bytes is Arc and refcount == 1
+    bytes.reserve_exact(1001);
+    assert_eq!(bytes.capacity(), 1001);
+}
+
+#[test]
+fn reserve_exact_in_arc_nonunique() {
+    let mut bytes = BytesMut::with_capacity(2048);
+    bytes.put_slice(b"hello");
+    let copy = bytes.split_to(2);
+
+    // now bytes is Arc and refcount == 2, the original capacity is ignored
+    bytes.reserve_exact(2046);
+    assert_eq!(bytes.capacity(), 2049);
+    assert_eq!(&bytes[..], b"llo");
+    assert_eq!(&copy[..], b"he");
+}
+
+#[test]
+fn shrink_to_fit_vec() {
+    let mut bytes = BytesMut::with_capacity(1024);
+    bytes.put_slice(b"hello world");
+    bytes.advance(6);
+
+    bytes.shrink_to_fit();
+    assert_eq!(bytes.capacity(), 5);
+    assert_eq!(&bytes[..], b"world");
+
+    bytes.put_u8(b'!');
+    assert_eq!(&bytes[..], b"world!");
+
+    let mut empty = BytesMut::with_capacity(64);
+    empty.shrink_to_fit();
+    assert_eq!(empty.capacity(), 0);
+}
+
+#[test]
+fn shrink_to_fit_in_arc_unique() {
+    let mut bytes = BytesMut::with_capacity(1024);
+    bytes.put_slice(b"hello world");
+    drop(bytes.split_to(6));
+
+    // now bytes is Arc and refcount == 1
+    bytes.shrink_to_fit();
+    assert_eq!(bytes.capacity(), 5);
+    assert_eq!(&bytes[..], b"world");
+
+    bytes.reserve(10);
+    assert!(bytes.capacity() >= 15);
+    assert_eq!(&bytes[..], b"world");
+}
+
+#[test]
+fn shrink_to_fit_in_arc_nonunique() {
+    let mut bytes = BytesMut::with_capacity(1024);
+    bytes.put_slice(b"hello world");
+    let other = bytes.split_to(6);
+
+    // now bytes is Arc and refcount == 2
+    let ptr = bytes.as_ptr();
+    bytes.shrink_to_fit();
+    assert_eq!(bytes.capacity(), 1018);
+    assert_eq!(bytes.as_ptr(), ptr);
+    assert_eq!(&bytes[..], b"world");
+    assert_eq!(&other[..], b"hello ");
+}
+
+#[test]
+fn insert_slice() {
+    let mut bytes = BytesMut::with_capacity(64);
+    bytes.put_slice(b"hello world");
+    let ptr = bytes.as_ptr();
+
+    bytes.insert_slice(5, b",");
+    bytes.insert_slice(0, b">> ");
+    bytes.insert_slice(bytes.len(), b"!");
+    bytes.insert_slice(3, b"");
+    assert_eq!(bytes, b">> hello, world!"[..]);
+    assert_eq!(bytes.as_ptr(), ptr);
+
+    let mut bytes = BytesMut::from(&b"ad"[..]);
+    bytes.insert_slice(1, LONG);
+    assert_eq!(bytes.len(), LONG.len() + 2);
+    assert_eq!(&bytes[1..bytes.len() - 1], LONG);
+    assert_eq!(bytes[bytes.len() - 1], b'd');
+}
+
+#[test]
+#[should_panic(expected = "insertion index out of bounds")]
+fn insert_slice_out_of_bounds() {
+    let mut bytes = BytesMut::from(&b"abc"[..]);
+    bytes.insert_slice(4, b"d");
+}
+
+#[test]
+fn remove_range() {
+    let mut bytes = BytesMut::from(&b"hello, world"[..]);
+    let cap = bytes.capacity();
+
+    bytes.remove_range(5..7);
+    assert_eq!(bytes, b"helloworld"[..]);
+    bytes.remove_range(..=1);
+    assert_eq!(bytes, b"lloworld"[..]);
+    bytes.remove_range(3..);
+    assert_eq!(bytes, b"llo"[..]);
+    bytes.remove_range(1..1);
+    assert_eq!(bytes, b"llo"[..]);
+    bytes.remove_range(..);
+    assert!(bytes.is_empty());
+    assert_eq!(bytes.capacity(), cap);
+}
+
+#[test]
+#[should_panic(expected = "range end out of bounds")]
+fn remove_range_out_of_bounds() {
+    let mut bytes = BytesMut::from(&b"abc"[..]);
+    bytes.remove_range(2..4);
+}
+
+#[test]
+#[should_panic(expected = "range start must not be greater than end")]
+fn remove_range_inverted() {
+    let mut bytes = BytesMut::from(&b"abc"[..]);
+    let (start, end) = (2, 1);
+    bytes.remove_range(start..end);
+}
+
+#[test]
+fn drain() {
+    let mut bytes = BytesMut::with_capacity(64);
+    bytes.put_slice(b"GET /index.html HTTP/1.1");
+    let ptr = bytes.as_ptr();
+
+    assert_eq!(bytes.drain(4..15), "/index.html");
+    assert_eq!(bytes, b"GET  HTTP/1.1"[..]);
+    assert_eq!(bytes.drain(..4), "GET ");
+    assert_eq!(bytes.drain(0..0), "");
+    assert_eq!(bytes.drain(1..), "HTTP/1.1");
+    assert_eq!(bytes, b" "[..]);
+
+    // The buffer stays where it is, with all of its capacity.
+    assert_eq!(bytes.as_ptr(), ptr);
+    assert_eq!(bytes.capacity(), 64);
+
+    let mut bytes = BytesMut::from(LONG);
+    let drained = bytes.drain(1..LONG.len() - 1);
+    assert_eq!(drained, LONG[1..LONG.len() - 1]);
+    assert_eq!(bytes, [LONG[0], LONG[LONG.len() - 1]][..]);
+}
+
+#[test]
+fn splice() {
+    let mut bytes = BytesMut::from(&b"Host: example.com\r\n"[..]);
+
+    bytes.splice(6..17, b"example.org:8080");
+    assert_eq!(bytes, b"Host: example.org:8080\r\n"[..]);
+    bytes.splice(6..17, b"x");
+    assert_eq!(bytes, b"Host: x:8080\r\n"[..]);
+    bytes.splice(..4, b"host");
+    assert_eq!(bytes, b"host: x:8080\r\n"[..]);
+    bytes.splice(bytes.len().., b"\r\n");
+    assert_eq!(bytes, b"host: x:8080\r\n\r\n"[..]);
+    bytes.splice(.., b"");
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn retain() {
+    let mut bytes = BytesMut::from(&b"a-b--c-"[..]);
+    bytes.retain(|&b| b != b'-');
+    assert_eq!(bytes, b"abc"[..]);
+
+    let mut seen = Vec::new();
+    bytes.retain(|&b| {
+        seen.push(b);
+        false
+    });
+    assert_eq!(seen, b"abc");
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn extend_from_within() {
+    let mut bytes = BytesMut::from(&b"abc-"[..]);
+    bytes.extend_from_within(..3);
+    assert_eq!(bytes, b"abc-abc"[..]);
+    bytes.extend_from_within(2..=4);
+    assert_eq!(bytes, b"abc-abcc-a"[..]);
+    bytes.extend_from_within(..);
+    assert_eq!(bytes, b"abc-abcc-aabc-abcc-a"[..]);
+}
+
+#[test]
+fn copy_within_and_fill() {
+    let mut bytes = BytesMut::from(&b"abcdef"[..]);
+    bytes.copy_within(..3, 3);
+    assert_eq!(bytes, b"abcabc"[..]);
+    bytes[1..5].fill(b'x');
+    assert_eq!(bytes, b"axxxxc"[..]);
+}
+
+#[test]
+fn editing_shared_buffer() {
+    let mut bytes = BytesMut::with_capacity(64);
+    bytes.put_slice(b"hello world");
+    let mut other = bytes.split_off(5);
+    let ptr = bytes.as_ptr();
+
+    // now bytes is Arc and refcount == 2
+
+    bytes.remove_range(1..3);
+    bytes.retain(|&b| b != b'o');
+    bytes.splice(1.., b"EL");
+    assert_eq!(bytes, b"hEL"[..]);
+    assert_eq!(bytes.as_ptr(), ptr);
+    assert_eq!(other, b" world"[..]);
+
+    // Growing past the view copies the data out instead of writing into
+    // the other half.
+    bytes.insert_slice(3, b"LO");
+    bytes.extend_from_within(..);
+    assert_eq!(bytes, b"hELLOhELLO"[..]);
+    assert_ne!(bytes.as_ptr(), ptr);
+    assert_eq!(other, b" world"[..]);
+
+    other.insert_slice(0, b",");
+    assert_eq!(other.drain(..2), ", ");
+    other.extend_from_within(..);
+    assert_eq!(other, b"worldworld"[..]);
+    assert_eq!(bytes, b"hELLOhELLO"[..]);
+}
+
 #[test]
 fn extend_mut() {
     let mut bytes = BytesMut::with_capacity(0);
@@ -1140,3 +1523,116 @@ fn test_bytes_into_vec_promotable_even() {
     assert_eq!(Vec::from(b2), vec[20..]);
     assert_eq!(Vec::from(b1), vec[..20]);
 }
+
+const INLINE_CAP: usize = 3 * std::mem::size_of::<usize>() - 1;
+
+fn is_inline(b: &Bytes) -> bool {
+    let start = b as *const Bytes as usize;
+    let ptr = b.as_ptr() as usize;
+    ptr >= start && ptr < start + std::mem::size_of::<Bytes>()
+}
+
+#[test]
+fn inline_copy_from_slice() {
+    let b = Bytes::copy_from_slice(&LONG[..INLINE_CAP]);
+    assert!(is_inline(&b));
+    assert_eq!(b, &LONG[..INLINE_CAP]);
+
+    let b = Bytes::copy_from_slice(&LONG[..INLINE_CAP + 1]);
+    assert!(!is_inline(&b));
+    assert_eq!(b, &LONG[..INLINE_CAP + 1]);
+
+    assert!(Bytes::copy_from_slice(b"").is_empty());
+}
+
+#[test]
+fn inline_from_vec() {
+    let b = Bytes::from(SHORT[..8].to_vec());
+    assert!(is_inline(&b));
+    assert_eq!(b, &SHORT[..8]);
+    assert_eq!(Vec::from(b), &SHORT[..8]);
+
+    let b = Bytes::from(LONG.to_vec());
+    assert!(!is_inline(&b));
+}
+
+#[test]
+fn inline_clone_and_move() {
+    let a = Bytes::copy_from_slice(&SHORT[..8]);
+    let b = a.clone();
+    let moved = [a];
+
+    assert_eq!(moved[0], &SHORT[..8]);
+    assert_eq!(b, &SHORT[..8]);
+    assert!(is_inline(&moved[0]));
+    assert!(is_inline(&b));
+}
+
+#[test]
+fn inline_advance_and_split() {
+    let mut b = Bytes::copy_from_slice(&SHORT[..8]);
+    b.advance(2);
+    assert_eq!(b, &SHORT[2..8]);
+
+    let tail = b.split_off(3);
+    assert_eq!(b, &SHORT[2..5]);
+    assert_eq!(tail, &SHORT[5..8]);
+
+    let head = b.split_to(1);
+    assert_eq!(head, &SHORT[2..3]);
+    assert_eq!(b, &SHORT[3..5]);
+
+    b.truncate(1);
+    assert_eq!(b, &SHORT[3..4]);
+    assert!(b.slice(1..).is_empty());
+}
+
+#[test]
+fn inline_full_capacity() {
+    let mut b = Bytes::copy_from_slice(&LONG[..INLINE_CAP]);
+    assert_eq!(b.len(), INLINE_CAP);
+
+    let tail = b.split_off(INLINE_CAP - 3);
+    assert!(is_inline(&tail));
+    assert_eq!(tail, &LONG[INLINE_CAP - 3..INLINE_CAP]);
+
+    b.advance(1);
+    assert_eq!(b, &LONG[1..INLINE_CAP - 3]);
+    assert_eq!(b.clone(), &LONG[1..INLINE_CAP - 3]);
+    assert_eq!(Vec::from(b), &LONG[1..INLINE_CAP - 3]);
+}
+
+#[test]
+fn inline_copy_to_bytes() {
+    let mut b = Bytes::copy_from_slice(&SHORT[..8]);
+    let head = b.copy_to_bytes(3);
+
+    assert_eq!(head, &SHORT[..3]);
+    assert_eq!(b, &SHORT[3..8]);
+    assert!(is_inline(&head));
+}
+
+#[test]
+fn slice_of_vec_is_inlined() {
+    let b = Bytes::from(LONG.to_vec());
+
+    let short = b.slice(3..3 + INLINE_CAP);
+    assert!(is_inline(&short));
+    assert_eq!(short, &LONG[3..3 + INLINE_CAP]);
+
+    let long = b.slice(3..4 + INLINE_CAP);
+    assert!(!is_inline(&long));
+    assert_eq!(long.as_ptr(), b[3..].as_ptr());
+}
+
+#[test]
+fn slice_of_shared_is_not_inlined() {
+    let b = Bytes::from(LONG.to_vec());
+    let b2 = b.clone();
+
+    let short = b.slice(3..7);
+    assert_eq!(short.as_ptr(), b2[3..].as_ptr());
+
+    let short = Bytes::from_static(LONG).slice(3..7);
+    assert_eq!(short.as_ptr(), LONG[3..].as_ptr());
+}
@@ -158,6 +158,31 @@ fn vectored_read() {
     }
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn vectored_read_into_growable_header() {
+    use std::io::{IoSliceMut, Read};
+
+    let mut body = [0u8; 16];
+    let mut buf = rcbytes::BytesMut::with_capacity(4).chain_mut(&mut body[..]);
+
+    let n = {
+        let mut dst = [IoSliceMut::new(&mut []), IoSliceMut::new(&mut [])];
+        let cnt = buf.chunks_vectored_mut(&mut dst);
+        assert_eq!(cnt, 2);
+        assert_eq!(dst[0].len(), 4);
+        let mut src = &b"HEADbody bytes"[..];
+        src.read_vectored(&mut dst[..cnt]).unwrap()
+    };
+    assert_eq!(n, 14);
+    // The header reports far more `remaining_mut` than the slice it exposed.
+    unsafe { buf.advance_mut(n) };
+
+    let (header, _) = buf.into_inner();
+    assert_eq!(header, "HEAD");
+    assert_eq!(&body[..10], b"body bytes");
+}
+
 #[test]
 fn chain_growing_buffer() {
     let mut buff = [' ' as u8; 10];
@@ -182,8 +207,8 @@ fn chain_overflow_remaining_mut() {
 
 #[test]
 fn chain_get_bytes() {
-    let mut ab = Bytes::copy_from_slice(b"ab");
-    let mut cd = Bytes::copy_from_slice(b"cd");
+    let mut ab = Bytes::from_static(b"ab");
+    let mut cd = Bytes::from_static(b"cd");
     let ab_ptr = ab.as_ptr();
     let cd_ptr = cd.as_ptr();
     let mut chain = (&mut ab).chain(&mut cd);
@@ -0,0 +1,171 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::checksum::{Adler32, Crc32, Crc32c};
+use rcbytes::{Buf, BufMut, Bytes, BytesMut};
+
+use core::hash::Hasher;
+
+const CHECK: &[u8] = b"123456789";
+
+#[test]
+fn check_values() {
+    assert_eq!(Crc32::checksum(CHECK), 0xcbf4_3926);
+    assert_eq!(Crc32c::checksum(CHECK), 0xe306_9283);
+    assert_eq!(Adler32::checksum(CHECK), 0x091e_01de);
+
+    assert_eq!(Crc32::checksum(b""), 0);
+    assert_eq!(Crc32c::checksum(b""), 0);
+    assert_eq!(Adler32::checksum(b""), 1);
+}
+
+#[test]
+fn incremental_matches_one_shot() {
+    let data: Vec<u8> = (0..20_000u32).map(|i| (i * 31 + 7) as u8).collect();
+
+    let mut crc = Crc32::new();
+    let mut crc_c = Crc32c::default();
+    let mut adler = Adler32::new();
+    for part in data.chunks(777) {
+        crc.update(part);
+        crc_c.write(part);
+        adler.update(part);
+    }
+
+    assert_eq!(crc.sum(), Crc32::checksum(&data));
+    assert_eq!(crc_c.finish(), Crc32c::checksum(&data) as u64);
+    assert_eq!(adler.sum(), Adler32::checksum(&data));
+
+    crc.reset();
+    assert_eq!(crc, Crc32::new());
+}
+
+#[test]
+fn adler32_large_run() {
+    // Long runs of 0xff stress the deferred modulo reduction.
+    let data = vec![0xff; 100_000];
+    let mut a: u32 = 1;
+    let mut b: u32 = 0;
+    for &byte in &data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    assert_eq!(Adler32::checksum(&data), (b << 16) | a);
+}
+
+#[test]
+fn buf_hashes_consumed_bytes() {
+    let buf = (&b"1234"[..]).chain(&b"56789 trailer"[..]);
+    let mut buf = buf.inspect_with(Crc32::new());
+
+    // Looking at the chunk does not hash it.
+    assert_eq!(buf.chunk(), b"1234");
+    assert_eq!(buf.get_u8(), b'1');
+    let mut dst = [0; 3];
+    buf.copy_to_slice(&mut dst);
+    // Spans both halves of the chain.
+    buf.advance(5);
+
+    assert_eq!(buf.finish(), 0xcbf4_3926);
+    assert_eq!(buf.remaining(), 8);
+}
+
+#[test]
+fn buf_copy_to_bytes_stays_zero_copy() {
+    // Long enough that the split-off `Bytes` is not stored inline.
+    let data: Vec<u8> = (0..64).collect();
+    let src = Bytes::from(data.clone());
+    let ptr = src.as_ptr();
+
+    let mut buf = src.inspect_with(Crc32c::new());
+    let bytes = buf.copy_to_bytes(40);
+
+    assert_eq!(bytes.as_ptr(), ptr);
+    assert_eq!(buf.hasher().sum(), Crc32c::checksum(&data[..40]));
+
+    let (rest, hasher) = buf.into_parts();
+    assert_eq!(rest, &data[40..]);
+    assert_eq!(hasher.sum(), Crc32c::checksum(&data[..40]));
+}
+
+#[test]
+fn buf_accepts_any_hasher() {
+    use std::collections::hash_map::DefaultHasher;
+
+    let mut buf = CHECK.inspect_with(DefaultHasher::new());
+    buf.advance(CHECK.len());
+
+    let mut expected = DefaultHasher::new();
+    expected.write(CHECK);
+    assert_eq!(buf.finish(), expected.finish());
+}
+
+#[test]
+#[should_panic]
+fn buf_advance_past_remaining() {
+    let mut buf = CHECK.inspect_with(Crc32::new());
+    buf.advance(10);
+}
+
+#[test]
+fn buf_mut_hashes_written_bytes() {
+    let mut buf = BytesMut::with_capacity(4).checksummed(Adler32::new());
+    buf.put_u8(b'1');
+    buf.put_slice(b"23");
+    buf.put(&b"456"[..]);
+    buf.put_bytes(b'7', 1);
+    buf.put_u16(0x3839);
+
+    assert_eq!(buf.hasher().sum(), 0x091e_01de);
+    assert_eq!(buf.get_ref(), CHECK);
+}
+
+#[test]
+fn buf_mut_chunk_mut() {
+    let mut buf = Vec::new().checksummed(Crc32::new());
+
+    let chunk = buf.chunk_mut();
+    chunk[..4].copy_from_slice(b"1234");
+    unsafe { buf.advance_mut(4) };
+    unsafe { buf.advance_mut(0) };
+
+    let mut dst = [0u8; 5];
+    let mut slice = &mut dst[..];
+    slice.put_slice(b"56789");
+    buf.put_slice(&dst);
+
+    assert_eq!(buf.finish(), 0xcbf4_3926);
+    assert_eq!(buf.into_inner(), CHECK);
+}
+
+#[test]
+fn buf_mut_into_slice() {
+    let mut storage = [0u8; 9];
+    let mut buf = (&mut storage[..]).checksummed(Crc32c::new());
+    buf.put_slice(&CHECK[..4]);
+    buf.put_slice(&CHECK[4..]);
+    assert_eq!(buf.remaining_mut(), 0);
+    assert_eq!(buf.hasher().sum(), 0xe306_9283);
+}
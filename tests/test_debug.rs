@@ -25,7 +25,8 @@
 // SOFTWARE.
 #![warn(rust_2018_idioms)]
 
-use rcbytes::Bytes;
+use rcbytes::fmt::HexDump;
+use rcbytes::{Buf, Bytes, BytesMut};
 
 #[test]
 fn fmt() {
@@ -58,3 +59,75 @@ fn fmt() {
 
     assert_eq!(expected, format!("{:?}", Bytes::from(vec)));
 }
+
+#[test]
+fn hexdump() {
+    let b = Bytes::from_static(b"Hello, world!\n\x00\x01\x02\x03");
+
+    let expected = "\
+        00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 0001  Hello, world!...\n\
+        00000010: 0203                                     ..";
+    assert_eq!(b.hexdump().to_string(), expected);
+    assert_eq!(format!("{:#?}", b), expected);
+    assert_eq!(format!("{:#?}", BytesMut::from(&b[..])), expected);
+}
+
+#[test]
+fn hexdump_empty() {
+    assert_eq!(Bytes::new().hexdump().to_string(), "");
+    assert_eq!(Bytes::new().hexdump().max_len(0).to_string(), "");
+}
+
+#[test]
+fn hexdump_odd_width_and_offset() {
+    let b = Bytes::from_static(b"abcdefg");
+
+    let expected = "\
+        00000100: 6162 63  abc\n\
+        00000103: 6465 66  def\n\
+        00000106: 67       g";
+    assert_eq!(b.hexdump().width(3).offset(0x100).to_string(), expected);
+}
+
+#[test]
+fn hexdump_max_len() {
+    let b = Bytes::from(vec![0xffu8; 4096]);
+
+    let expected = "\
+        00000000: ffff ffff ffff ffff ffff ffff ffff ffff  ................\n\
+        00000010: ffff                                     ..\n\
+        ... 4078 more bytes";
+    assert_eq!(b.hexdump().max_len(18).to_string(), expected);
+    assert_eq!(b.hexdump().max_len(0).to_string(), "... 4096 more bytes");
+}
+
+#[test]
+fn hexdump_chain() {
+    let chain = (&b"Hello, "[..]).chain(&b"world!"[..]).chain(&b"\r\n"[..]);
+
+    let expected = "\
+        00000000: 4865 6c6c 6f2c  Hello,\n\
+        00000006: 2077 6f72 6c64   world\n\
+        0000000c: 210d 0a         !..";
+    assert_eq!(HexDump::new(chain.clone()).width(6).to_string(), expected);
+    // Displaying does not consume the buffer.
+    assert_eq!(chain.remaining(), 15);
+}
+
+#[test]
+fn alternate_debug_in_struct() {
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Packet {
+        body: Bytes,
+    }
+
+    let p = Packet {
+        body: Bytes::from_static(b"hi"),
+    };
+    assert_eq!(format!("{:?}", p), "Packet { body: b\"hi\" }");
+    assert_eq!(
+        format!("{:#?}", p),
+        "Packet {\n    body: 00000000: 6869                                     hi,\n}"
+    );
+}
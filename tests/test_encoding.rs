@@ -0,0 +1,318 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::encoding::base64::{self, Config};
+use rcbytes::encoding::varint::{self, VarintError};
+use rcbytes::encoding::{hex, DecodeError};
+use rcbytes::{hex, Buf, BufMut, Bytes, BytesMut};
+
+// RFC 4648 section 10 test vectors.
+const RFC_4648: &[(&str, &str, &str)] = &[
+    ("", "", ""),
+    ("f", "Zg==", "666f"),
+    ("fo", "Zm8=", "666f6f"),
+    ("foo", "Zm9v", "666f6f6f"),
+    ("foob", "Zm9vYg==", "666f6f6f62"),
+    ("fooba", "Zm9vYmE=", "666f6f6f6261"),
+    ("foobar", "Zm9vYmFy", "666f6f6f626172"),
+];
+
+/// Splits `data` into a chain of single byte `Buf`s.
+fn fragmented(data: &[u8]) -> impl Buf + '_ {
+    Fragmented(data)
+}
+
+struct Fragmented<'a>(&'a [u8]);
+
+impl Buf for Fragmented<'_> {
+    fn remaining(&self) -> usize {
+        self.0.len()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        &self.0[..self.0.len().min(1)]
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.0 = &self.0[cnt..];
+    }
+}
+
+#[test]
+fn hex_rfc_4648() {
+    for &(plain, _, _) in RFC_4648 {
+        let encoded: String = plain.bytes().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex::encode(plain.as_bytes()), encoded.as_bytes());
+        assert_eq!(hex::decode(encoded.as_bytes()).unwrap(), plain.as_bytes());
+    }
+}
+
+#[test]
+fn hex_encode_chain() {
+    let data = Bytes::from_static(b"\x00\x01").chain(Bytes::from_static(b"\xfe\xff"));
+
+    let mut dst = BytesMut::new();
+    hex::encode_upper_to(data, &mut dst);
+    assert_eq!(dst, "0001FEFF");
+}
+
+#[test]
+fn hex_encode_long() {
+    let data: Vec<u8> = (0..=255).collect();
+    let encoded = hex::encode(&data[..]);
+
+    assert_eq!(encoded.len(), 512);
+    assert_eq!(&encoded[..8], b"00010203");
+    assert_eq!(&encoded[504..], b"fcfdfeff");
+    assert_eq!(hex::decode(&encoded).unwrap(), data);
+}
+
+#[test]
+fn hex_decode_fragmented() {
+    let mut dst = BytesMut::new();
+    hex::decode_to(fragmented(b"DeadBeef"), &mut dst).unwrap();
+    assert_eq!(dst, &b"\xde\xad\xbe\xef"[..]);
+}
+
+#[test]
+fn hex_decode_errors() {
+    assert_eq!(
+        hex::decode(b"abc"),
+        Err(DecodeError::InvalidLength { len: 3 })
+    );
+    assert_eq!(
+        hex::decode(b"ab c"),
+        Err(DecodeError::InvalidByte {
+            index: 2,
+            byte: b' '
+        })
+    );
+
+    let mut dst = BytesMut::new();
+    let err = hex::decode_to(&b"0102zz"[..], &mut dst).unwrap_err();
+    assert_eq!(
+        err,
+        DecodeError::InvalidByte {
+            index: 4,
+            byte: b'z'
+        }
+    );
+    assert_eq!(dst, &b"\x01\x02"[..]);
+}
+
+#[test]
+fn hex_macro() {
+    const EMPTY: &str = "";
+
+    assert_eq!(hex!(EMPTY), Bytes::new());
+    assert_eq!(hex!("00ff"), &b"\x00\xff"[..]);
+    assert_eq!(hex!("DEAD_beef"), &b"\xde\xad\xbe\xef"[..]);
+
+    let a = hex!("0102 0304\n0506");
+    assert_eq!(a, &b"\x01\x02\x03\x04\x05\x06"[..]);
+
+    // The bytes are static, slicing never copies.
+    let b = a.slice(1..3);
+    assert_eq!(b.as_ptr(), a[1..].as_ptr());
+}
+
+fn check_base64(config: Config, plain: &[u8], encoded: &str) {
+    assert_eq!(config.encode(plain), encoded.as_bytes(), "{:?}", config);
+    assert_eq!(config.encoded_len(plain.len()), encoded.len());
+    assert_eq!(config.decode(encoded.as_bytes()).unwrap(), plain);
+
+    let mut dst = BytesMut::new();
+    config.encode_to(fragmented(plain), &mut dst);
+    assert_eq!(dst, encoded.as_bytes());
+
+    let mut dst = BytesMut::new();
+    config
+        .decode_to(fragmented(encoded.as_bytes()), &mut dst)
+        .unwrap();
+    assert_eq!(dst, plain);
+}
+
+#[test]
+fn base64_rfc_4648() {
+    for &(plain, encoded, _) in RFC_4648 {
+        check_base64(base64::STANDARD, plain.as_bytes(), encoded);
+        check_base64(
+            base64::STANDARD_NO_PAD,
+            plain.as_bytes(),
+            encoded.trim_end_matches('='),
+        );
+    }
+}
+
+#[test]
+fn base64_url_safe() {
+    check_base64(base64::STANDARD, b"\xfb\xef\xff", "++//");
+    check_base64(base64::URL_SAFE, b"\xfb\xef\xff", "--__");
+    check_base64(base64::URL_SAFE, b"\xfb\xef", "--8=");
+    check_base64(base64::URL_SAFE_NO_PAD, b"\xfb\xef", "--8");
+}
+
+#[test]
+fn base64_long_chain() {
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    let (a, b) = data.split_at(333);
+    let chain = a.chain(b);
+
+    let encoded = base64::STANDARD.encode(chain);
+    assert_eq!(encoded.len(), base64::STANDARD.encoded_len(1000));
+    assert_eq!(base64::STANDARD.decode(&encoded).unwrap(), data);
+}
+
+#[test]
+fn base64_decode_errors() {
+    use DecodeError::*;
+
+    let std = base64::STANDARD;
+    let no_pad = base64::STANDARD_NO_PAD;
+
+    assert_eq!(std.decode(b"Zg"), Err(InvalidPadding));
+    assert_eq!(std.decode(b"Zg="), Err(InvalidPadding));
+    assert_eq!(std.decode(b"Zg==="), Err(InvalidPadding));
+    assert_eq!(std.decode(b"Z==="), Err(InvalidPadding));
+    assert_eq!(
+        std.decode(b"Zg==Zg=="),
+        Err(InvalidByte {
+            index: 4,
+            byte: b'Z'
+        })
+    );
+    assert_eq!(std.decode(b"Zm9vY"), Err(InvalidLength { len: 5 }));
+    assert_eq!(
+        std.decode(b"Zm-v"),
+        Err(InvalidByte {
+            index: 2,
+            byte: b'-'
+        })
+    );
+    // Non-zero trailing bits.
+    assert_eq!(
+        std.decode(b"Zh=="),
+        Err(InvalidByte {
+            index: 1,
+            byte: b'h'
+        })
+    );
+    assert_eq!(
+        no_pad.decode(b"Zm9"),
+        Err(InvalidByte {
+            index: 2,
+            byte: b'9'
+        })
+    );
+
+    assert_eq!(no_pad.decode(b"Zg=="), Err(InvalidPadding));
+    assert_eq!(no_pad.decode(b"Z"), Err(InvalidLength { len: 1 }));
+
+    let mut dst = BytesMut::new();
+    let err = std.decode_to(&b"Zm9vYmFy!"[..], &mut dst).unwrap_err();
+    assert_eq!(
+        err,
+        InvalidByte {
+            index: 8,
+            byte: b'!'
+        }
+    );
+    assert_eq!(dst, "foobar");
+}
+
+#[test]
+#[should_panic]
+fn encode_to_limited_dst_panics() {
+    let mut dst = [0u8; 3];
+    hex::encode_to(&b"ab"[..], &mut (&mut dst[..]).limit(3));
+}
+
+#[test]
+fn varint_round_trip() {
+    let values = [
+        0,
+        1,
+        0x7f,
+        0x80,
+        300,
+        0x3fff,
+        0x4000,
+        u64::from(u32::MAX),
+        u64::MAX - 1,
+        u64::MAX,
+    ];
+    let mut buf = BytesMut::new();
+    for &value in &values {
+        let before = buf.len();
+        varint::encode_to(value, &mut buf);
+        assert_eq!(buf.len() - before, varint::encoded_len(value), "{}", value);
+    }
+    assert_eq!(&buf[..3], b"\x00\x01\x7f");
+    assert_eq!(
+        &buf[buf.len() - varint::MAX_LEN..],
+        b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01"
+    );
+
+    let mut src = fragmented(&buf);
+    for &value in &values {
+        assert_eq!(varint::decode(&mut src), Ok(value));
+    }
+    assert!(!src.has_remaining());
+}
+
+#[test]
+fn varint_decode_errors() {
+    assert_eq!(
+        varint::decode(&mut &b""[..]),
+        Err(VarintError::UnexpectedEof)
+    );
+    assert_eq!(
+        varint::decode(&mut &b"\x80\x80"[..]),
+        Err(VarintError::UnexpectedEof)
+    );
+    assert_eq!(
+        varint::decode(&mut &b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x02"[..]),
+        Err(VarintError::Overflow)
+    );
+    assert_eq!(
+        varint::decode(&mut &b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x00"[..]),
+        Err(VarintError::Overflow)
+    );
+}
+
+#[test]
+fn varint_zigzag() {
+    for &(signed, unsigned) in &[
+        (0, 0),
+        (-1, 1),
+        (1, 2),
+        (-2, 3),
+        (i64::MAX, u64::MAX - 1),
+        (i64::MIN, u64::MAX),
+    ] {
+        assert_eq!(varint::zigzag_encode(signed), unsigned);
+        assert_eq!(varint::zigzag_decode(unsigned), signed);
+    }
+}
@@ -0,0 +1,222 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::{Buf, BufMut, Bytes, CapacityError, FixedBytesMut};
+
+#[test]
+fn new_is_empty() {
+    let buf = FixedBytesMut::with_capacity(16);
+    assert!(buf.is_empty());
+    assert!(!buf.is_full());
+    assert_eq!(buf.capacity(), 16);
+    assert_eq!(buf.remaining_mut(), 16);
+}
+
+#[test]
+fn remaining_mut_is_spare_capacity() {
+    let mut buf = FixedBytesMut::with_capacity(16);
+    buf.put_u32(1);
+    assert_eq!(buf.remaining_mut(), 12);
+    buf.put_slice(&[0; 12]);
+    assert_eq!(buf.remaining_mut(), 0);
+    assert!(buf.is_full());
+    assert_eq!(buf.chunk_mut().len(), 0);
+    assert_eq!(buf.capacity(), 16);
+}
+
+#[test]
+#[should_panic(expected = "buffer overflow")]
+fn put_slice_past_capacity_panics() {
+    let mut buf = FixedBytesMut::with_capacity(4);
+    buf.put_slice(b"abcde");
+}
+
+#[test]
+#[should_panic(expected = "buffer overflow")]
+fn put_int_past_capacity_panics() {
+    let mut buf = FixedBytesMut::with_capacity(4);
+    buf.put_u8(1);
+    buf.put_u32(2);
+}
+
+#[test]
+#[should_panic]
+fn put_bytes_past_capacity_panics() {
+    let mut buf = FixedBytesMut::with_capacity(4);
+    buf.put_bytes(0, 5);
+}
+
+#[test]
+fn try_put_does_not_grow() {
+    let mut buf = FixedBytesMut::with_capacity(6);
+    buf.try_put_u16(0x0102).unwrap();
+    buf.try_put_u16_le(0x0304).unwrap();
+
+    let err: CapacityError = buf.try_put_u32(5).unwrap_err();
+    assert_eq!((err.requested, err.available), (4, 2));
+    assert_eq!(buf.try_put_bytes(0, 3).unwrap_err().requested, 3);
+    assert!(buf.try_put(&b"xyz"[..]).is_err());
+    assert_eq!(&buf[..], b"\x01\x02\x04\x03");
+    assert_eq!(buf.capacity(), 6);
+
+    buf.try_put_bytes(b'z', 1).unwrap();
+    buf.try_put_i8(-1).unwrap();
+    assert!(buf.try_put_u8(0).is_err());
+    assert_eq!(&buf[..], b"\x01\x02\x04\x03z\xff");
+}
+
+#[test]
+fn try_put_matches_put() {
+    let mut fixed = FixedBytesMut::with_capacity(128);
+    let mut vec = Vec::new();
+
+    fixed.try_put_u64(1).unwrap();
+    vec.put_u64(1);
+    fixed.try_put_i64_le(-2).unwrap();
+    vec.put_i64_le(-2);
+    fixed.try_put_u128(3).unwrap();
+    vec.put_u128(3);
+    fixed.try_put_i128_le(-4).unwrap();
+    vec.put_i128_le(-4);
+    fixed.try_put_f32(1.5).unwrap();
+    vec.put_f32(1.5);
+    fixed.try_put_f64_le(-2.5).unwrap();
+    vec.put_f64_le(-2.5);
+    fixed.try_put(&b"abc"[..]).unwrap();
+    vec.put(&b"abc"[..]);
+
+    assert_eq!(fixed, &vec[..]);
+}
+
+#[test]
+fn capacity_error_display() {
+    let mut buf = FixedBytesMut::with_capacity(2);
+    let err = buf.try_put_u32(0).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "not enough capacity in fixed buffer (requested 4 but only 2 available)"
+    );
+}
+
+#[test]
+fn read_and_split() {
+    let mut buf = FixedBytesMut::with_capacity(16);
+    buf.put_slice(b"hello world");
+
+    assert_eq!(buf.get_u8(), b'h');
+    assert_eq!(buf.copy_to_bytes(4), Bytes::from_static(b"ello"));
+    assert_eq!(buf.split_to(1), b" "[..]);
+    assert_eq!(buf.split().freeze(), "world");
+    assert!(buf.is_empty());
+    assert_eq!(buf.capacity(), 5);
+}
+
+#[test]
+fn try_reserve_in_place_reclaims_front() {
+    let mut buf = FixedBytesMut::with_capacity(8);
+    buf.put_slice(b"abcdefgh");
+    let ptr = buf.as_ptr();
+
+    buf.advance(6);
+    assert_eq!(buf.remaining_mut(), 0);
+    buf.try_reserve_in_place(6).unwrap();
+    assert_eq!(buf.capacity(), 8);
+    assert_eq!(buf.as_ptr(), ptr);
+    assert_eq!(&buf[..], b"gh");
+
+    buf.put_slice(b"123456");
+    assert_eq!(&buf[..], b"gh123456");
+}
+
+#[test]
+fn try_reserve_in_place_needs_unique() {
+    let mut buf = FixedBytesMut::with_capacity(8);
+    buf.put_slice(b"abcdefgh");
+    let frame = buf.split_to(4).freeze();
+
+    let err = buf.try_reserve_in_place(1).unwrap_err();
+    assert_eq!((err.requested, err.available), (1, 0));
+    assert_eq!(&buf[..], b"efgh");
+
+    drop(frame);
+    buf.try_reserve_in_place(4).unwrap();
+    assert_eq!(buf.capacity(), 8);
+    assert_eq!(&buf[..], b"efgh");
+}
+
+#[test]
+fn try_reserve_in_place_never_allocates() {
+    let mut buf = FixedBytesMut::with_capacity(8);
+    buf.put_slice(b"abcd");
+    buf.try_reserve_in_place(4).unwrap();
+    assert!(buf.try_reserve_in_place(5).is_err());
+
+    buf.advance(2);
+    assert!(buf.try_reserve_in_place(7).is_err());
+    assert_eq!(buf.capacity(), 6);
+    assert_eq!(&buf[..], b"cd");
+
+    buf.try_reserve_in_place(6).unwrap();
+    assert_eq!(buf.capacity(), 8);
+    assert!(buf.try_reserve_in_place(usize::MAX).is_err());
+}
+
+#[test]
+fn into_inner_can_grow() {
+    let mut buf = FixedBytesMut::with_capacity(2);
+    buf.put_slice(b"ab");
+
+    let mut inner = buf.into_inner();
+    inner.put_slice(b"cd");
+    assert_eq!(inner, b"abcd"[..]);
+}
+
+#[test]
+fn fmt() {
+    let mut buf = FixedBytesMut::with_capacity(4);
+    buf.put_slice(b"ab\n");
+    assert_eq!(format!("{:?}", buf), "b\"ab\\n\"");
+}
+
+#[test]
+fn put_with_in_place() {
+    let mut buf = FixedBytesMut::with_capacity(8);
+    let ptr = buf.as_ptr();
+    let n = buf.put_with(8, |dst| {
+        dst[..3].copy_from_slice(b"abc");
+        3
+    });
+    assert_eq!(n, 3);
+    assert_eq!(&buf[..], b"abc");
+    assert_eq!(buf.as_ptr(), ptr);
+    assert_eq!(buf.capacity(), 8);
+}
+
+#[test]
+#[should_panic(expected = "buffer overflow")]
+fn put_with_past_capacity_panics() {
+    let mut buf = FixedBytesMut::with_capacity(8);
+    buf.put_with(9, |_| 0);
+}
@@ -0,0 +1,140 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![cfg(all(feature = "mmap", unix))]
+#![warn(rust_2018_idioms)]
+
+use rcbytes::{Buf, Bytes};
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, contents: &[u8]) -> TempFile {
+        let path = std::env::temp_dir().join(format!("rcbytes-{}-{}", name, std::process::id()));
+        File::create(&path).unwrap().write_all(contents).unwrap();
+        TempFile(path)
+    }
+
+    fn open(&self) -> File {
+        File::open(&self.0).unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn contents(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn map_file() {
+    let data = contents(10_000);
+    let tmp = TempFile::new("map-file", &data);
+
+    let bytes = unsafe { Bytes::map_file(&tmp.open()).unwrap() };
+    assert_eq!(bytes, data);
+}
+
+#[test]
+fn map_empty_file() {
+    let tmp = TempFile::new("map-empty-file", b"");
+
+    let bytes = unsafe { Bytes::map_file(&tmp.open()).unwrap() };
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn map_range_unaligned() {
+    let data = contents(3 * 4096 + 17);
+    let tmp = TempFile::new("map-range-unaligned", &data);
+
+    let bytes = unsafe { Bytes::map_range(&tmp.open(), 4099, 5000).unwrap() };
+    assert_eq!(bytes, data[4099..9099]);
+
+    let tail = unsafe { Bytes::map_range(&tmp.open(), data.len() as u64, 0).unwrap() };
+    assert!(tail.is_empty());
+}
+
+#[test]
+fn map_range_out_of_bounds() {
+    let tmp = TempFile::new("map-range-out-of-bounds", b"hello world");
+    let file = tmp.open();
+
+    let err = unsafe { Bytes::map_range(&file, 6, 6).unwrap_err() };
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let err = unsafe { Bytes::map_range(&file, 12, 0).unwrap_err() };
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn slicing_is_zero_copy() {
+    let data = contents(8192);
+    let tmp = TempFile::new("slicing-is-zero-copy", &data);
+
+    let mut bytes = unsafe { Bytes::map_file(&tmp.open()).unwrap() };
+    let base = bytes.as_ptr();
+
+    let slice = bytes.slice(100..200);
+    assert_eq!(slice.as_ptr(), unsafe { base.add(100) });
+    assert_eq!(slice, data[100..200]);
+
+    let head = bytes.split_to(1000);
+    assert_eq!(head.as_ptr(), base);
+    assert_eq!(bytes.as_ptr(), unsafe { base.add(1000) });
+
+    let copied = bytes.copy_to_bytes(500);
+    assert_eq!(copied.as_ptr(), unsafe { base.add(1000) });
+    assert_eq!(copied, data[1000..1500]);
+
+    // The mapping stays alive as long as any handle does.
+    drop(bytes);
+    drop(head);
+    assert_eq!(slice, data[100..200]);
+    assert_eq!(copied.clone(), data[1000..1500]);
+}
+
+#[test]
+fn to_vec_copies() {
+    let data = contents(5000);
+    let tmp = TempFile::new("to-vec-copies", &data);
+
+    let bytes = unsafe { Bytes::map_file(&tmp.open()).unwrap() };
+    let other = bytes.slice(10..);
+    let ptr = bytes.as_ptr();
+
+    let vec = Vec::from(bytes);
+    assert_ne!(vec.as_ptr(), ptr);
+    assert_eq!(vec, data);
+
+    let vec = Vec::from(other);
+    assert_eq!(vec, data[10..]);
+}
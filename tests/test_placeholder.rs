@@ -0,0 +1,196 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::{Buf, BufMut, BytesMut};
+
+const LONG: &[u8] = b"mary had a little lamb, little lamb, little lamb";
+
+#[test]
+fn put_slice_at() {
+    let mut buf = BytesMut::from(&b"hello world"[..]);
+    buf.put_slice_at(0, b"J");
+    buf.put_slice_at(6, b"there");
+    buf.put_slice_at(11, b"");
+    assert_eq!(buf, b"Jello there"[..]);
+}
+
+#[test]
+#[should_panic(expected = "write out of bounds")]
+fn put_slice_at_past_len() {
+    let mut buf = BytesMut::with_capacity(64);
+    buf.put_slice(b"hello");
+    buf.put_slice_at(3, b"abc");
+}
+
+#[test]
+#[should_panic(expected = "write out of bounds")]
+fn put_slice_at_overflow() {
+    let mut buf = BytesMut::from(&b"hello"[..]);
+    buf.put_slice_at(usize::MAX, b"a");
+}
+
+#[test]
+fn put_int_at() {
+    let mut buf = BytesMut::new();
+    buf.put_bytes(0, 16);
+
+    buf.put_u32_at(0, 0x0102_0304);
+    buf.put_u32_le_at(4, 0x0102_0304);
+    buf.put_i16_at(8, -2);
+    buf.put_u16_le_at(10, 0x0a0b);
+    buf.put_u8_at(12, 0xff);
+    buf.put_i8_at(13, -1);
+    assert_eq!(
+        &buf[..14],
+        b"\x01\x02\x03\x04\x04\x03\x02\x01\xff\xfe\x0b\x0a\xff\xff"
+    );
+
+    buf.put_u64_at(8, 1);
+    buf.put_i64_le_at(0, -1);
+    assert_eq!(buf.get_u64(), u64::MAX);
+    assert_eq!(buf.get_u64(), 1);
+}
+
+#[test]
+#[should_panic(expected = "write out of bounds")]
+fn put_int_at_past_len() {
+    let mut buf = BytesMut::from(&b"abc"[..]);
+    buf.put_u32_at(0, 1);
+}
+
+#[test]
+fn placeholder() {
+    let mut buf = BytesMut::new();
+    buf.put_u8(0x7f);
+    let len = buf.put_placeholder::<u16>();
+    let crc = buf.put_placeholder::<u32>();
+    assert_eq!(len.offset(), 1);
+    assert_eq!(crc.offset(), 3);
+    assert_eq!(buf, b"\x7f\0\0\0\0\0\0"[..]);
+
+    buf.put_slice(b"body");
+    buf.patch_le(crc, 0x0102_0304);
+    buf.patch(len, 4);
+    assert_eq!(buf, b"\x7f\x00\x04\x04\x03\x02\x01body"[..]);
+}
+
+#[test]
+fn placeholder_survives_reallocation() {
+    let mut buf = BytesMut::with_capacity(4);
+    let len = buf.put_placeholder::<u32>();
+    buf.put_slice(LONG);
+    buf.patch(len, LONG.len() as u32);
+
+    assert_eq!(buf.get_u32(), LONG.len() as u32);
+    assert_eq!(buf, LONG);
+}
+
+#[test]
+#[should_panic(expected = "placeholder out of bounds")]
+fn placeholder_truncated() {
+    let mut buf = BytesMut::new();
+    buf.put_u8(1);
+    let len = buf.put_placeholder::<u64>();
+    buf.truncate(4);
+    buf.patch(len, 0);
+}
+
+#[test]
+fn length_prefixed() {
+    let mut buf = BytesMut::new();
+    buf.put_u8(1);
+    {
+        let mut msg = buf.begin_length_prefixed::<u32>();
+        assert_eq!(msg.body_len(), 0);
+        msg.put_slice(b"hello");
+        assert_eq!(msg.body_len(), 5);
+    }
+    buf.put_u8(2);
+
+    assert_eq!(buf, b"\x01\x00\x00\x00\x05hello\x02"[..]);
+}
+
+#[test]
+fn length_prefixed_nested() {
+    let mut buf = BytesMut::new();
+    {
+        let mut outer = buf.begin_length_prefixed_le::<u16>();
+        outer.put_u8(0xaa);
+        {
+            let mut inner = outer.begin_length_prefixed::<u8>();
+            inner.put_slice(b"abc");
+        }
+        let mut inner = outer.begin_length_prefixed::<u8>();
+        inner.put_slice(b"de");
+        assert_eq!(inner.finish(), 2);
+    }
+
+    assert_eq!(buf, b"\x08\x00\xaa\x03abc\x02de"[..]);
+}
+
+#[test]
+fn length_prefixed_reads_through_guard() {
+    let mut buf = BytesMut::new();
+    let mut msg = buf.begin_length_prefixed::<u8>();
+    msg.put_slice(b"ab");
+    msg.put_with(4, |dst| {
+        dst[..1].copy_from_slice(b"c");
+        1
+    });
+    assert_eq!(&msg[..], b"\0abc");
+    assert_eq!(msg.body_len(), 3);
+    assert_eq!(msg.finish(), 3);
+    assert_eq!(buf, b"\x03abc"[..]);
+}
+
+#[test]
+fn length_prefixed_empty() {
+    let mut buf = BytesMut::new();
+    assert_eq!(buf.begin_length_prefixed::<i32>().finish(), 0);
+    assert_eq!(buf, b"\0\0\0\0"[..]);
+}
+
+#[test]
+#[should_panic(expected = "length prefix overflow: 256 does not fit in 1 bytes")]
+fn length_prefixed_overflow() {
+    let mut buf = BytesMut::new();
+    let mut msg = buf.begin_length_prefixed::<u8>();
+    msg.put_bytes(0, 256);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn length_prefixed_skipped_on_panic() {
+    let mut buf = BytesMut::new();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let mut msg = buf.begin_length_prefixed::<u8>();
+        msg.put_bytes(0, 300);
+        panic!("encoding failed");
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(buf[0], 0);
+    assert_eq!(buf.len(), 301);
+}
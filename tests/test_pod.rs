@@ -0,0 +1,181 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![cfg(feature = "bytemuck")]
+#![warn(rust_2018_idioms)]
+
+use bytemuck::{Pod, PodCastError, Zeroable};
+use rcbytes::{Buf, BufMut, Bytes, BytesMut, TypedBytes};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+struct Header {
+    kind: u16,
+    flags: u16,
+    len: u32,
+}
+
+unsafe impl Zeroable for Header {}
+unsafe impl Pod for Header {}
+
+#[test]
+fn get_and_put_pod() {
+    let header = Header {
+        kind: 7,
+        flags: 0x8001,
+        len: 1024,
+    };
+
+    let mut buf = BytesMut::new();
+    buf.put_u8(0xff);
+    buf.put_pod(&header);
+    buf.put_pod(&[1u8, 2, 3]);
+    assert_eq!(buf.len(), 12);
+
+    let mut buf = buf.freeze();
+    assert_eq!(buf.get_u8(), 0xff);
+    // Unaligned reads are fine.
+    assert_eq!(buf.get_pod::<Header>(), header);
+    assert_eq!(buf.get_pod::<[u8; 3]>(), [1, 2, 3]);
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+fn get_pod_across_chunks() {
+    let bytes = 0x0102_0304u32.to_ne_bytes();
+    let mut buf = (&bytes[..1]).chain(&bytes[1..]);
+    assert_eq!(buf.get_pod::<u32>(), 0x0102_0304);
+}
+
+#[test]
+fn try_get_pod() {
+    let mut buf = &[1, 2, 3][..];
+    let err = buf.try_get_pod::<Header>().unwrap_err();
+    assert_eq!((err.requested, err.available), (8, 3));
+    assert_eq!(buf.remaining(), 3);
+    assert_eq!(buf.try_get_pod::<[u8; 2]>(), Ok([1, 2]));
+}
+
+#[test]
+#[should_panic]
+fn get_pod_past_end() {
+    let mut buf = &[1, 2, 3][..];
+    buf.get_pod::<u32>();
+}
+
+#[test]
+fn from_pod_vec_is_zero_copy() {
+    let vec = vec![1u64, 2, 3];
+    let ptr = vec.as_ptr() as *const u8;
+
+    let bytes = Bytes::from_pod_vec(vec);
+    assert_eq!(bytes.as_ptr(), ptr);
+    assert_eq!(bytes.len(), 24);
+
+    let typed = bytes.try_cast_slice::<u64>().unwrap();
+    assert_eq!(typed.as_ptr() as *const u8, ptr);
+    assert_eq!(&typed[..], [1, 2, 3]);
+
+    // Small vectors are not inlined.
+    let bytes = Bytes::from_pod_vec(vec![5u32]);
+    assert_eq!(&bytes.try_cast_slice::<u32>().unwrap()[..], [5]);
+}
+
+#[test]
+fn typed_view_keeps_storage_alive() {
+    let typed: TypedBytes<Header> = {
+        let bytes = Bytes::from_pod_vec(vec![
+            Header {
+                kind: 1,
+                flags: 0,
+                len: 10
+            };
+            3
+        ]);
+        bytes.slice(8..).try_cast_slice().unwrap()
+    };
+
+    assert_eq!(typed.len(), 2);
+    assert_eq!(typed[1].len, 10);
+
+    let clone = typed.clone();
+    drop(typed);
+    assert_eq!(clone.iter().map(|h| h.kind).sum::<u16>(), 2);
+    assert_eq!(clone.as_bytes().len(), 16);
+    assert_eq!(Bytes::from(clone).len(), 16);
+}
+
+#[test]
+fn cast_errors() {
+    let bytes = Bytes::from_pod_vec(vec![0u32; 8]);
+
+    assert_eq!(
+        bytes.slice(2..).try_cast_slice::<u32>().unwrap_err(),
+        PodCastError::TargetAlignmentGreaterAndInputNotAligned
+    );
+    assert_eq!(
+        bytes.slice(..30).try_cast_slice::<u32>().unwrap_err(),
+        PodCastError::OutputSliceWouldHaveSlop
+    );
+    assert_eq!(
+        Bytes::copy_from_slice(b"abc")
+            .try_cast_slice::<u16>()
+            .unwrap_err(),
+        PodCastError::OutputSliceWouldHaveSlop
+    );
+}
+
+#[test]
+fn cast_inline_bytes() {
+    // Short `Bytes` are inline and copied into an aligned allocation, so
+    // the view stays valid after the original moves.
+    let src = Bytes::copy_from_slice(&[1, 0, 0, 0, 2, 0, 0, 0]);
+    let typed = src.try_cast_slice::<u32>().unwrap();
+    let moved = Box::new(src);
+
+    assert_eq!(typed.iter().map(|v| u32::from_le(*v)).sum::<u32>(), 3);
+    assert_eq!(moved.len(), 8);
+    assert_eq!(typed.as_ptr() as usize % 4, 0);
+}
+
+#[test]
+fn cast_empty() {
+    let typed = Bytes::new().try_cast_slice::<u64>().unwrap();
+    assert!(typed.is_empty());
+    assert_eq!(typed, TypedBytes::clone(&typed));
+
+    let bytes = Bytes::from_static(b"abc");
+    assert!(bytes
+        .slice(1..1)
+        .try_cast_slice::<u64>()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn debug() {
+    let typed = Bytes::from_pod_vec(vec![1u16, 2])
+        .try_cast_slice::<u16>()
+        .unwrap();
+    assert_eq!(format!("{:?}", typed), "[1, 2]");
+}
@@ -0,0 +1,178 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::buf::RingBuf;
+use rcbytes::{Buf, BufMut};
+#[cfg(feature = "std")]
+use std::io::{IoSlice, IoSliceMut, Read};
+
+/// Returns a buffer of capacity 8 holding `efgh` + `ijkl`, wrapped around
+/// the end of the storage.
+fn wrapped() -> RingBuf {
+    let mut buf = RingBuf::with_capacity(8);
+    buf.put_slice(b"abcdef");
+    buf.advance(4);
+    buf.put_slice(b"ghijkl");
+    buf
+}
+
+#[test]
+fn write_then_read() {
+    let mut buf = RingBuf::with_capacity(8);
+    assert!(buf.is_empty());
+    assert_eq!(buf.capacity(), 8);
+    assert_eq!(buf.remaining_mut(), 8);
+
+    buf.put_u32(0x0102_0304);
+    buf.put_slice(b"abcd");
+    assert!(buf.is_full());
+    assert_eq!(buf.remaining_mut(), 0);
+    assert_eq!(buf.chunk_mut().len(), 0);
+
+    assert_eq!(buf.get_u32(), 0x0102_0304);
+    assert_eq!(buf.chunk(), b"abcd");
+    buf.advance(4);
+    assert!(buf.is_empty());
+    // A drained buffer starts over at the front.
+    assert_eq!(buf.chunk_mut().len(), 8);
+}
+
+#[test]
+fn wraps_around() {
+    let mut buf = wrapped();
+    assert!(buf.is_full());
+    assert_eq!(buf.as_slices(), (&b"efgh"[..], &b"ijkl"[..]));
+    assert_eq!(buf.chunk(), b"efgh");
+
+    #[cfg(feature = "std")]
+    {
+        let mut dst = [IoSlice::new(&[]); 3];
+        assert_eq!(buf.chunks_vectored(&mut dst), 2);
+        assert_eq!(&*dst[0], b"efgh");
+        assert_eq!(&*dst[1], b"ijkl");
+        assert_eq!(buf.chunks_vectored(&mut dst[..1]), 1);
+    }
+
+    // Reading across the wrap.
+    buf.advance(2);
+    let mut out = [0; 4];
+    buf.copy_to_slice(&mut out);
+    assert_eq!(&out, b"ghij");
+    assert_eq!(buf.as_slices(), (&b"kl"[..], &b""[..]));
+
+    {
+        let (a, b) = buf.as_mut_slices();
+        a[0] = b'K';
+        assert!(b.is_empty());
+    }
+    assert_eq!(buf.copy_to_bytes(2), "Kl");
+}
+
+#[test]
+fn make_contiguous() {
+    let mut buf = wrapped();
+    assert_eq!(buf.make_contiguous(), b"efghijkl");
+    assert_eq!(buf.as_slices(), (&b"efghijkl"[..], &b""[..]));
+
+    let mut buf = RingBuf::with_capacity(8);
+    buf.put_slice(b"abc");
+    buf.advance(1);
+    assert_eq!(buf.make_contiguous(), b"bc");
+    assert_eq!(RingBuf::with_capacity(0).make_contiguous(), b"");
+}
+
+#[test]
+fn copy_to_bytes_across_the_wrap() {
+    let mut buf = wrapped();
+    assert_eq!(buf.copy_to_bytes(6), "efghij");
+    assert_eq!(buf.copy_to_bytes(2), "kl");
+    assert!(buf.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn readv_into_both_free_halves() {
+    let mut buf = RingBuf::with_capacity(8);
+    buf.put_slice(b"abcdef");
+    buf.advance(3);
+
+    let n = {
+        let mut dst = [
+            IoSliceMut::new(&mut []),
+            IoSliceMut::new(&mut []),
+            IoSliceMut::new(&mut []),
+        ];
+        let cnt = buf.chunks_vectored_mut(&mut dst);
+        assert_eq!(cnt, 2);
+        assert_eq!(dst[0].len(), 2);
+        assert_eq!(dst[1].len(), 3);
+        let mut src = &b"ghij"[..];
+        src.read_vectored(&mut dst[..cnt]).unwrap()
+    };
+    assert_eq!(n, 4);
+    unsafe { buf.advance_mut(n) };
+    assert_eq!(buf.as_slices(), (&b"defgh"[..], &b"ij"[..]));
+    assert_eq!(buf.remaining_mut(), 1);
+}
+
+#[test]
+fn put_slice_across_the_wrap() {
+    let mut buf = RingBuf::with_capacity(4);
+    buf.put_slice(b"abc");
+    buf.advance(2);
+    buf.put_slice(b"def");
+    assert_eq!(buf.make_contiguous(), b"cdef");
+}
+
+#[test]
+#[should_panic]
+fn put_slice_overflow() {
+    let mut buf = RingBuf::with_capacity(4);
+    buf.put_slice(b"abc");
+    buf.put_slice(b"de");
+}
+
+#[test]
+#[should_panic]
+fn advance_past_remaining() {
+    let mut buf = RingBuf::with_capacity(4);
+    buf.put_slice(b"abc");
+    buf.advance(4);
+}
+
+#[test]
+fn zero_capacity() {
+    let mut buf = RingBuf::with_capacity(0);
+    assert!(buf.is_empty());
+    assert!(buf.is_full());
+    assert_eq!(buf.chunk(), b"");
+    assert_eq!(buf.chunk_mut().len(), 0);
+    #[cfg(feature = "std")]
+    {
+        let mut dst = [IoSliceMut::new(&mut [])];
+        assert_eq!(buf.chunks_vectored_mut(&mut dst), 0);
+    }
+    assert_eq!(format!("{:?}", buf), "RingBuf { len: 0, capacity: 0 }");
+}
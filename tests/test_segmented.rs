@@ -0,0 +1,116 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::buf::SegmentedBuf;
+use rcbytes::{Buf, BufMut};
+#[cfg(feature = "std")]
+use std::io::{IoSlice, IoSliceMut, Read};
+
+#[test]
+fn write_and_read_across_segments() {
+    let mut buf = SegmentedBuf::new(4);
+    assert!(buf.is_empty());
+    assert_eq!(buf.chunk(), b"");
+    assert_eq!(buf.segment_size(), 4);
+
+    buf.put(&b"hello world"[..]);
+    assert_eq!(buf.len(), 11);
+    assert_eq!(buf.remaining(), 11);
+    assert_eq!(buf.chunk(), b"hell");
+
+    #[cfg(feature = "std")]
+    {
+        let mut dst = [IoSlice::new(&[]); 4];
+        assert_eq!(buf.chunks_vectored(&mut dst), 3);
+        assert_eq!(&*dst[2], b"rld");
+    }
+
+    buf.advance(5);
+    assert_eq!(buf.chunk(), b" wo");
+    buf.put_u8(b'!');
+    let mut out = [0; 7];
+    buf.copy_to_slice(&mut out);
+    assert_eq!(&out, b" world!");
+    assert!(buf.is_empty());
+
+    buf.put(&b"again"[..]);
+    assert_eq!(buf.copy_to_bytes(5), "again");
+}
+
+#[test]
+fn copy_to_bytes_shares_the_front_segment() {
+    let mut buf = SegmentedBuf::new(64);
+    buf.put(&[7; 100][..]);
+
+    let ptr = buf.chunk().as_ptr();
+    let front = buf.copy_to_bytes(40);
+    assert_eq!(front.as_ptr(), ptr);
+
+    // Spans two segments, so it is copied.
+    let rest = buf.copy_to_bytes(60);
+    assert_eq!(rest, &[7; 60][..]);
+    assert!(buf.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn readv_into_reserved_segments() {
+    let mut buf = SegmentedBuf::new(4);
+    buf.put(&b"ab"[..]);
+    buf.reserve(8);
+    assert_eq!(buf.spare_capacity(), 10);
+
+    let n = {
+        let mut dst: Vec<_> = (0..4).map(|_| IoSliceMut::new(&mut [])).collect();
+        let cnt = buf.chunks_vectored_mut(&mut dst);
+        assert_eq!(cnt, 3);
+        assert_eq!(
+            dst[..cnt].iter().map(|s| s.len()).collect::<Vec<_>>(),
+            [2, 4, 4]
+        );
+        let mut src = &b"cdefghi"[..];
+        src.read_vectored(&mut dst[..cnt]).unwrap()
+    };
+    assert_eq!(n, 7);
+    unsafe { buf.advance_mut(n) };
+
+    assert_eq!(buf.len(), 9);
+    assert_eq!(buf.spare_capacity(), 3);
+    assert_eq!(buf.copy_to_bytes(9), "abcdefghi");
+}
+
+#[test]
+#[should_panic]
+fn advance_past_remaining() {
+    let mut buf = SegmentedBuf::new(4);
+    buf.put(&b"abc"[..]);
+    buf.advance(4);
+}
+
+#[test]
+#[should_panic]
+fn zero_segment_size() {
+    SegmentedBuf::new(0);
+}
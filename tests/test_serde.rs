@@ -26,20 +26,460 @@
 #![cfg(feature = "serde")]
 #![warn(rust_2018_idioms)]
 
-use serde_test::{assert_tokens, Token};
+use rcbytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Configure, Token};
 
 #[test]
 fn test_ser_de_empty() {
     let b = rcbytes::Bytes::new();
-    assert_tokens(&b, &[Token::Bytes(b"")]);
+    assert_tokens(&b.compact(), &[Token::Bytes(b"")]);
     let b = rcbytes::BytesMut::with_capacity(0);
-    assert_tokens(&b, &[Token::Bytes(b"")]);
+    assert_tokens(&b.compact(), &[Token::Bytes(b"")]);
 }
 
 #[test]
 fn test_ser_de() {
     let b = rcbytes::Bytes::from(&b"bytes"[..]);
-    assert_tokens(&b, &[Token::Bytes(b"bytes")]);
+    assert_tokens(&b.compact(), &[Token::Bytes(b"bytes")]);
     let b = rcbytes::BytesMut::from(&b"bytes"[..]);
-    assert_tokens(&b, &[Token::Bytes(b"bytes")]);
+    assert_tokens(&b.compact(), &[Token::Bytes(b"bytes")]);
+}
+
+#[test]
+fn test_ser_de_byte_str() {
+    let s = rcbytes::ByteStr::from("héllo");
+    assert_tokens(&s, &[Token::Str("héllo")]);
+    assert_de_tokens(&s, &[Token::String("héllo")]);
+    assert_de_tokens(&s, &[Token::Bytes("héllo".as_bytes())]);
+}
+
+#[test]
+fn test_de_byte_str_invalid_utf8() {
+    assert_de_tokens_error::<rcbytes::ByteStr>(
+        &[Token::Bytes(b"\xff")],
+        "invalid value: byte array, expected a string",
+    );
+}
+
+#[test]
+fn test_ser_de_byte_string() {
+    let s = rcbytes::ByteString::from("héllo");
+    assert_tokens(&s, &[Token::Str("héllo")]);
+    assert_de_tokens(&s, &[Token::ByteBuf("héllo".as_bytes())]);
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Encoded {
+    #[serde(with = "rcbytes::serde::hex")]
+    hex: Bytes,
+    #[serde(with = "rcbytes::serde::base64")]
+    base64: BytesMut,
+    #[serde(with = "rcbytes::serde::hex")]
+    vec: Vec<u8>,
+}
+
+#[test]
+fn test_ser_de_with_modules() {
+    let value = Encoded {
+        hex: Bytes::from_static(b"\x01\x02\xff"),
+        base64: BytesMut::from(&b"hello"[..]),
+        vec: vec![0xde, 0xad],
+    };
+    let tokens = [
+        Token::Struct {
+            name: "Encoded",
+            len: 3,
+        },
+        Token::Str("hex"),
+        Token::Str("0102ff"),
+        Token::Str("base64"),
+        Token::Str("aGVsbG8="),
+        Token::Str("vec"),
+        Token::Str("dead"),
+        Token::StructEnd,
+    ];
+    // The text form does not depend on the format.
+    assert_tokens(&value.clone().readable(), &tokens);
+    assert_tokens(&value.clone().compact(), &tokens);
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"hex":"0102ff","base64":"aGVsbG8=","vec":"dead"}"#);
+    assert_eq!(serde_json::from_str::<Encoded>(&json).unwrap(), value);
+    let upper = r#"{"hex":"0102FF","base64":"aGVsbG8=","vec":"DEAD"}"#;
+    assert_eq!(serde_json::from_str::<Encoded>(upper).unwrap(), value);
+}
+
+#[test]
+fn test_de_with_modules_invalid() {
+    let err = serde_json::from_str::<Encoded>(r#"{"hex":"0g","base64":"","vec":""}"#)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid byte"), "{}", err);
+
+    let err = serde_json::from_str::<Encoded>(r#"{"hex":"","base64":"aGVsbG8","vec":""}"#)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid padding"), "{}", err);
+}
+
+#[test]
+fn test_json_default_is_byte_array() {
+    let b = Bytes::from_static(b"hi");
+    assert_eq!(serde_json::to_string(&b).unwrap(), "[104,105]");
+    assert_tokens(&b.clone().readable(), &[Token::Bytes(b"hi")]);
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Readable {
+    #[serde(with = "rcbytes::serde::human_readable")]
+    bytes: Bytes,
+    #[serde(with = "rcbytes::serde::human_readable")]
+    bytes_mut: BytesMut,
+}
+
+#[test]
+fn test_ser_de_human_readable() {
+    let value = Readable {
+        bytes: Bytes::from_static(b"hello"),
+        bytes_mut: BytesMut::from(&b"hi"[..]),
+    };
+    let tokens = |bytes, bytes_mut| {
+        [
+            Token::Struct {
+                name: "Readable",
+                len: 2,
+            },
+            Token::Str("bytes"),
+            bytes,
+            Token::Str("bytes_mut"),
+            bytes_mut,
+            Token::StructEnd,
+        ]
+    };
+    assert_tokens(
+        &value.clone().readable(),
+        &tokens(Token::Str("aGVsbG8="), Token::Str("aGk=")),
+    );
+    assert_tokens(
+        &value.clone().compact(),
+        &tokens(Token::Bytes(b"hello"), Token::Bytes(b"hi")),
+    );
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"bytes":"aGVsbG8=","bytes_mut":"aGk="}"#);
+    assert_eq!(serde_json::from_str::<Readable>(&json).unwrap(), value);
+    // Arrays written before the fields opted in still deserialize.
+    let old = r#"{"bytes":[104,101,108,108,111],"bytes_mut":[104,105]}"#;
+    assert_eq!(serde_json::from_str::<Readable>(old).unwrap(), value);
+
+    let bad = r#"{"bytes":"not base64!","bytes_mut":""}"#;
+    assert!(serde_json::from_str::<Readable>(bad).is_err());
+
+    // Fields without the attribute keep the default representation.
+    assert_eq!(
+        serde_json::to_string(&value.bytes).unwrap(),
+        "[104,101,108,108,111]"
+    );
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Rect { w: u16, h: u16 },
+    Pair(i8, char),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Message {
+    id: u64,
+    delta: i32,
+    ok: bool,
+    name: String,
+    tags: Vec<String>,
+    parent: Option<u32>,
+    shapes: Vec<Shape>,
+    map: std::collections::BTreeMap<u8, (f32, ())>,
+    payload: Bytes,
+    scratch: BytesMut,
+}
+
+fn message() -> Message {
+    Message {
+        id: 1 << 40,
+        delta: -3,
+        ok: true,
+        name: "héllo".into(),
+        tags: vec!["a".into(), "bc".into()],
+        parent: None,
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Rect { w: 300, h: 2 },
+            Shape::Pair(-1, 'é'),
+        ],
+        map: vec![(1, (0.25, ())), (2, (-0.5, ()))].into_iter().collect(),
+        payload: Bytes::from(vec![0xab; 100]),
+        scratch: BytesMut::from(&b"scratch"[..]),
+    }
+}
+
+#[test]
+fn test_binary_round_trip() {
+    let value = message();
+    let mut buf = BytesMut::new();
+    rcbytes::serde::to_buf(&value, &mut buf).unwrap();
+    rcbytes::serde::to_buf(&Some(7u16), &mut buf).unwrap();
+
+    let mut src = buf.freeze();
+    let decoded: Message = rcbytes::serde::from_bytes(&mut src).unwrap();
+    assert_eq!(decoded, value);
+    assert_eq!(src, &b"\x01\x07"[..]);
+    assert_eq!(
+        rcbytes::serde::from_bytes::<Option<u16>>(&mut src),
+        Ok(Some(7))
+    );
+    assert!(src.is_empty());
+}
+
+#[test]
+fn test_binary_encoding() {
+    let mut buf = BytesMut::new();
+    rcbytes::serde::to_buf(
+        &(
+            true,
+            -2i64,
+            300u32,
+            'a',
+            "hi",
+            [1u8, 2],
+            Shape::Rect { w: 1, h: 2 },
+        ),
+        &mut buf,
+    )
+    .unwrap();
+    assert_eq!(buf, &b"\x01\x03\xac\x02\x61\x02hi\x01\x02\x02\x01\x02"[..]);
+
+    buf.clear();
+    rcbytes::serde::to_buf(&vec![Some(()), None], &mut buf).unwrap();
+    assert_eq!(buf, &b"\x02\x01\x00"[..]);
+}
+
+#[test]
+fn test_binary_bytes_are_zero_copy() {
+    let value = message();
+    let mut buf = BytesMut::new();
+    rcbytes::serde::to_buf(&value, &mut buf).unwrap();
+
+    let mut src = buf.freeze();
+    let range = src.as_ptr() as usize..src.as_ptr() as usize + src.len();
+    let decoded: Message = rcbytes::serde::from_bytes(&mut src).unwrap();
+    assert!(range.contains(&(decoded.payload.as_ptr() as usize)));
+    // `BytesMut` owns its storage, so it is always copied.
+    assert!(!range.contains(&(decoded.scratch.as_ptr() as usize)));
+}
+
+#[test]
+fn test_binary_errors() {
+    use rcbytes::serde::{from_bytes, to_buf, Error};
+
+    let mut buf = BytesMut::new();
+    to_buf(&message(), &mut buf).unwrap();
+    let full = buf.freeze();
+    for len in 0..full.len() {
+        let mut src = full.slice(..len);
+        assert_eq!(
+            from_bytes::<Message>(&mut src),
+            Err(Error::UnexpectedEof),
+            "{}",
+            len
+        );
+        assert_eq!(src.len(), len);
+    }
+
+    let mut src = Bytes::from_static(b"\x02");
+    assert_eq!(from_bytes::<bool>(&mut src), Err(Error::InvalidBool(2)));
+    let mut src = Bytes::from_static(b"\x02");
+    assert_eq!(
+        from_bytes::<Option<u8>>(&mut src),
+        Err(Error::InvalidOption(2))
+    );
+    let mut src = Bytes::from_static(b"\x80\x80\x04");
+    assert_eq!(from_bytes::<u16>(&mut src), Err(Error::Overflow));
+    let mut src = Bytes::from_static(b"\x80\xb0\x03");
+    assert_eq!(
+        from_bytes::<char>(&mut src),
+        Err(Error::InvalidChar(0xd800))
+    );
+    let mut src = Bytes::from_static(b"\x01\xff");
+    assert!(matches!(
+        from_bytes::<String>(&mut src),
+        Err(Error::InvalidUtf8(_))
+    ));
+    let mut src = Bytes::from_static(b"\x04");
+    assert_eq!(
+        from_bytes::<Shape>(&mut src).unwrap_err().to_string(),
+        "invalid value: integer `4`, expected variant index 0 <= i < 4"
+    );
+    let mut src = Bytes::from_static(b"\x00");
+    assert_eq!(
+        from_bytes::<serde_json::Value>(&mut src),
+        Err(Error::NotSelfDescribing)
+    );
+
+    struct Unsized;
+
+    impl Serialize for Unsized {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq((0..3u8).filter(|n| n % 2 == 0))
+        }
+    }
+
+    assert_eq!(
+        to_buf(&Unsized, &mut BytesMut::new()),
+        Err(Error::UnknownLength)
+    );
+}
+
+#[test]
+fn test_source_guard_json() {
+    use rcbytes::serde::SourceGuard;
+    use rcbytes::ByteStr;
+
+    #[derive(Deserialize)]
+    struct Doc {
+        blob: Bytes,
+        text: ByteStr,
+        escaped: Bytes,
+        owned: BytesMut,
+    }
+
+    let src = Bytes::from(
+        r#"{"blob":"0123456789abcdefghijklmnop","text":"ünïcode text, long enough","escaped":"tab\tand more than enough","owned":"abcdefghijklmnopqrstuvwxyz"}"#,
+    );
+    let range = src.as_ptr() as usize..src.as_ptr() as usize + src.len();
+
+    let doc: Doc = {
+        let _guard = SourceGuard::new(&src);
+        serde_json::from_slice(&src).unwrap()
+    };
+    assert_eq!(doc.blob, "0123456789abcdefghijklmnop");
+    assert!(range.contains(&(doc.blob.as_ptr() as usize)));
+    assert_eq!(doc.text, "ünïcode text, long enough");
+    assert!(range.contains(&(doc.text.as_ptr() as usize)));
+    // Escapes have to be resolved into a scratch buffer, which is copied.
+    assert_eq!(doc.escaped, "tab\tand more than enough");
+    assert!(!range.contains(&(doc.escaped.as_ptr() as usize)));
+    assert!(!range.contains(&(doc.owned.as_ptr() as usize)));
+
+    // Without a guard everything is copied.
+    let doc: Doc = serde_json::from_slice(&src).unwrap();
+    assert!(!range.contains(&(doc.blob.as_ptr() as usize)));
+    assert!(!range.contains(&(doc.text.as_ptr() as usize)));
+}
+
+#[test]
+fn test_source_guard_nested() {
+    use rcbytes::serde::SourceGuard;
+
+    let outer = Bytes::from(r#""the outer source, not inlined""#);
+    let inner = Bytes::from(r#""the inner source, not inlined""#);
+    let within = |src: &Bytes, b: &Bytes| {
+        let start = src.as_ptr() as usize;
+        (start..start + src.len()).contains(&(b.as_ptr() as usize))
+    };
+
+    let _outer_guard = SourceGuard::new(&outer);
+    {
+        let _inner_guard = SourceGuard::new(&inner);
+        let b: Bytes = serde_json::from_slice(&inner).unwrap();
+        assert!(within(&inner, &b));
+        // Only the innermost source is used.
+        let b: Bytes = serde_json::from_slice(&outer).unwrap();
+        assert!(!within(&outer, &b));
+    }
+    let b: Bytes = serde_json::from_slice(&outer).unwrap();
+    assert!(within(&outer, &b));
+}
+
+#[test]
+fn test_bounded_bytes() {
+    use rcbytes::serde::{BoundedBytes, BoundedBytesMut};
+
+    type Small = BoundedBytes<4>;
+
+    let b = Small::new(Bytes::from_static(b"abcd")).unwrap();
+    assert_tokens(&b.clone().compact(), &[Token::Bytes(b"abcd")]);
+    assert_de_tokens(&b.clone().compact(), &[Token::BorrowedBytes(b"abcd")]);
+    assert_de_tokens(&b.clone().compact(), &[Token::ByteBuf(b"abcd")]);
+    assert_de_tokens(&b.clone().compact(), &[Token::Str("abcd")]);
+    assert_de_tokens(&b.clone().compact(), &[Token::String("abcd")]);
+    assert_eq!(
+        Small::new(Bytes::from_static(b"abcde")).unwrap_err(),
+        "abcde"
+    );
+
+    let msg = "invalid length 5, expected byte array of at most 4 bytes";
+    for token in &[
+        Token::Bytes(b"abcde"),
+        Token::BorrowedBytes(b"abcde"),
+        Token::ByteBuf(b"abcde"),
+        Token::Str("abcde"),
+        Token::BorrowedStr("abcde"),
+        Token::String("abcde"),
+    ] {
+        assert_de_tokens_error::<serde_test::Compact<Small>>(&[*token], msg);
+        assert_de_tokens_error::<serde_test::Compact<BoundedBytesMut<4>>>(&[*token], msg);
+    }
+
+    // A known length is rejected before any element is read.
+    assert_de_tokens_error::<serde_test::Compact<Small>>(
+        &[Token::Seq { len: Some(5) }],
+        "invalid length 5, expected byte array of at most 4 bytes",
+    );
+    // An unknown one as soon as it goes over.
+    assert_de_tokens_error::<serde_test::Compact<Small>>(
+        &[
+            Token::Seq { len: None },
+            Token::U8(1),
+            Token::U8(2),
+            Token::U8(3),
+            Token::U8(4),
+            Token::U8(5),
+        ],
+        "invalid length 5, expected byte array of at most 4 bytes",
+    );
+    assert_de_tokens(
+        &Small::new(Bytes::from_static(b"\x01\x02"))
+            .unwrap()
+            .compact(),
+        &[
+            Token::Seq { len: None },
+            Token::U8(1),
+            Token::U8(2),
+            Token::SeqEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_bounded_bytes_binary() {
+    use rcbytes::serde::{from_bytes, to_buf, BoundedBytes};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Frame {
+        body: BoundedBytes<64>,
+    }
+
+    let mut buf = BytesMut::new();
+    to_buf(&Bytes::from(vec![1; 64]), &mut buf).unwrap();
+    let frame: Frame = from_bytes(&mut buf.split().freeze()).unwrap();
+    assert_eq!(frame.body.len(), 64);
+
+    to_buf(&Bytes::from(vec![1; 65]), &mut buf).unwrap();
+    assert_eq!(
+        from_bytes::<Frame>(&mut buf.freeze()).unwrap_err().to_string(),
+        "invalid length 65, expected byte array of at most 64 bytes"
+    );
 }
@@ -39,7 +39,7 @@ fn long_take() {
 
 #[test]
 fn take_copy_to_bytes() {
-    let mut abcd = Bytes::copy_from_slice(b"abcd");
+    let mut abcd = Bytes::from_static(b"abcd");
     let abcd_ptr = abcd.as_ptr();
     let mut take = (&mut abcd).take(2);
     let a = take.copy_to_bytes(1);
@@ -0,0 +1,155 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+#![cfg(feature = "track")]
+#![warn(rust_2018_idioms)]
+
+use rcbytes::track::{live_allocations, Allocation};
+use rcbytes::{BufMut, Bytes, BytesMut, GrowthPolicy};
+
+fn find(size: usize) -> Option<Allocation> {
+    live_allocations().into_iter().find(|a| a.size() == size)
+}
+
+#[test]
+fn with_capacity_records_origin() {
+    let line = line!() + 1;
+    let buf = BytesMut::with_capacity(4001);
+
+    let alloc = find(4001).expect("tracked");
+    assert_eq!(alloc.ref_count(), 1);
+    assert_eq!(alloc.origin().file(), file!());
+    assert_eq!(alloc.origin().line(), line);
+
+    drop(buf);
+    assert!(find(4001).is_none());
+}
+
+#[test]
+fn slice_keeps_allocation_alive() {
+    let mut buf = BytesMut::with_capacity(4002);
+    buf.put_bytes(b'x', 4002);
+    let frozen = buf.freeze();
+
+    let line = line!() + 1;
+    let small = frozen.slice(10..110);
+
+    let alloc = find(4002).expect("tracked");
+    assert_eq!(alloc.ref_count(), 2);
+    // The allocation keeps the origin of `with_capacity`.
+    assert_ne!(alloc.origin().line(), line);
+
+    drop(frozen);
+    assert_eq!(find(4002).expect("still alive").ref_count(), 1);
+
+    drop(small);
+    assert!(find(4002).is_none());
+}
+
+#[test]
+fn promotion_records_caller() {
+    let bytes = Bytes::from(vec![0; 4003]);
+    assert!(find(4003).is_none());
+
+    let line = line!() + 1;
+    let other = bytes.slice(1..);
+
+    let alloc = find(4003).expect("tracked");
+    assert_eq!(alloc.ref_count(), 2);
+    assert_eq!(alloc.origin().file(), file!());
+    assert_eq!(alloc.origin().line(), line);
+
+    drop(bytes);
+    drop(other);
+    assert!(find(4003).is_none());
+}
+
+#[test]
+fn split_records_caller() {
+    let mut buf = BytesMut::from(&[0u8; 4004][..]);
+
+    let line = line!() + 1;
+    let head = buf.split_to(4);
+
+    let alloc = find(4004).expect("tracked");
+    assert_eq!(alloc.ref_count(), 2);
+    assert_eq!(alloc.origin().line(), line);
+
+    drop(buf);
+    drop(head);
+    assert!(find(4004).is_none());
+}
+
+#[test]
+fn reserve_follows_reallocation() {
+    let mut buf = BytesMut::with_capacity(4005);
+    buf.put_bytes(0, 4005);
+    buf.reserve(1);
+
+    assert!(find(4005).is_none());
+    let alloc = live_allocations()
+        .into_iter()
+        .find(|a| a.size() == buf.capacity())
+        .expect("tracked");
+    assert_eq!(alloc.ref_count(), 1);
+
+    drop(buf);
+    assert!(live_allocations().is_empty());
+}
+
+#[test]
+fn reserve_records_fresh_allocation() {
+    for &policy in &[GrowthPolicy::default(), GrowthPolicy::Exact] {
+        let mut buf = BytesMut::with_growth_policy(4007, policy);
+        buf.put_bytes(0, 4007);
+        let other = buf.split_to(7);
+
+        // `other` still holds the old buffer, so this allocates a new one.
+        let line = line!() + 1;
+        buf.reserve(5000);
+        let alloc = live_allocations()
+            .into_iter()
+            .find(|a| a.size() == buf.capacity())
+            .expect("tracked");
+        assert_eq!(alloc.ref_count(), 1);
+        assert_eq!(alloc.origin().line(), line);
+        assert_eq!(alloc.origin().file(), file!());
+
+        drop(other);
+        drop(buf);
+        assert!(live_allocations().is_empty());
+    }
+}
+
+#[test]
+fn into_vec_stops_tracking() {
+    let mut buf = BytesMut::with_capacity(4006);
+    buf.put_bytes(1, 16);
+    let other = buf.split_off(8);
+    drop(other);
+
+    let vec: Vec<u8> = buf.into();
+    assert_eq!(vec.len(), 8);
+    assert!(find(4006).is_none());
+}
@@ -0,0 +1,163 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::buf::{Tracked, TryGetError};
+use rcbytes::{Buf, BufMut, Bytes, BytesMut};
+
+#[test]
+fn position_counts_consumed_bytes() {
+    let mut buf = Bytes::from_static(b"\x01\x00\x02hello world").tracked();
+    assert_eq!(buf.position(), 0);
+
+    // Looking at the chunk consumes nothing.
+    assert_eq!(buf.chunk()[0], 1);
+    assert_eq!(buf.position(), 0);
+
+    assert_eq!(buf.get_u8(), 1);
+    assert_eq!(buf.get_u16(), 2);
+    assert_eq!(buf.position(), 3);
+
+    let mut dst = [0; 5];
+    buf.copy_to_slice(&mut dst);
+    assert_eq!(buf.position(), 8);
+
+    let rest = buf.copy_to_bytes(6);
+    assert_eq!(rest, &b" world"[..]);
+    assert_eq!(buf.position(), 14);
+    assert_eq!(buf.remaining(), 0);
+}
+
+#[test]
+fn position_across_chain_and_take() {
+    let chain = (&b"hello "[..]).chain(&b"world"[..]);
+    let mut buf = chain.take(9).tracked();
+
+    // Spans the boundary of the chain.
+    let mut dst = [0; 8];
+    buf.copy_to_slice(&mut dst);
+    assert_eq!(&dst, b"hello wo");
+    assert_eq!(buf.position(), 8);
+
+    let err = buf.try_get_u16().unwrap_err();
+    assert_eq!(err.requested, 2);
+    assert_eq!(err.available, 1);
+    assert_eq!(err.position, Some(8));
+
+    // The failed read consumed nothing.
+    assert_eq!(buf.position(), 8);
+    assert_eq!(buf.try_get_u8(), Ok(b'r'));
+    assert_eq!(buf.position(), 9);
+}
+
+#[test]
+fn mark_and_since() {
+    let mut buf = (&b"\x03abc\x02de"[..]).tracked();
+
+    let mut lens = vec![];
+    while buf.has_remaining() {
+        let len = buf.get_u8() as usize;
+        let mark = buf.mark();
+        assert_eq!(mark.position(), buf.position());
+        buf.advance(len);
+        lens.push(buf.since(mark));
+    }
+
+    assert_eq!(lens, [3, 2]);
+    assert_eq!(buf.position(), 7);
+}
+
+#[test]
+#[should_panic(expected = "mark is ahead")]
+fn since_foreign_mark() {
+    let mut ahead = (&b"abc"[..]).tracked();
+    ahead.advance(2);
+    let mark = ahead.mark();
+
+    let behind = (&b"abc"[..]).tracked();
+    behind.since(mark);
+}
+
+#[test]
+fn nested_error_position() {
+    fn read_header(buf: &mut impl Buf) -> Result<(u8, u32), TryGetError> {
+        Ok((buf.try_get_u8()?, buf.try_get_u32()?))
+    }
+
+    let mut buf = (&b"\x00\x00\x07\x00\x00"[..]).tracked();
+    buf.advance(2);
+
+    // The error is produced by the tracked adapter even when reading
+    // through a reference.
+    let err = read_header(&mut buf).unwrap_err();
+    assert_eq!(err.position, Some(3));
+    assert_eq!(
+        err.to_string(),
+        "not enough bytes remaining in buffer to read value \
+         (requested 4 but only 2 available) at offset 3"
+    );
+}
+
+#[test]
+fn untracked_error_has_no_position() {
+    let mut buf = &b"\x01"[..];
+    let err = buf.try_get_u32().unwrap_err();
+    assert_eq!(err.position, None);
+    assert_eq!(err.requested, 4);
+    assert_eq!(err.available, 1);
+    assert!(!err.to_string().contains("offset"));
+}
+
+#[test]
+fn position_counts_written_bytes() {
+    let mut buf: Tracked<BytesMut> = BytesMut::new().tracked_mut();
+
+    buf.put_u32(0);
+    let mark = buf.mark();
+    buf.put_slice(b"payload");
+    buf.put_bytes(0, 3);
+    buf.put(&b"xyz"[..]);
+    assert_eq!(buf.since(mark), 13);
+    assert_eq!(buf.position(), 17);
+
+    let mut inner = buf.into_inner();
+    assert_eq!(inner.len(), 17);
+    (&mut inner[..4]).put_u32(13);
+    assert_eq!(inner.get_u32(), 13);
+}
+
+#[test]
+fn position_counts_slice_writes() {
+    let mut storage = [0; 8];
+    let mut buf = (&mut storage[..]).tracked_mut();
+
+    let chunk = buf.chunk_mut();
+    chunk[..2].copy_from_slice(b"ab");
+    unsafe { buf.advance_mut(2) };
+    buf.put_u16_le(0x0403);
+
+    assert_eq!(buf.position(), 4);
+    assert_eq!(buf.remaining_mut(), 4);
+    assert_eq!(&storage[..4], b"ab\x03\x04");
+}
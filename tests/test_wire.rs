@@ -0,0 +1,202 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![warn(rust_2018_idioms)]
+
+use rcbytes::wire::{BufDecode, BufEncode, IntRepr, LittleEndian, Varint, WireError};
+use rcbytes::{Buf, ByteStr, Bytes, BytesMut};
+
+fn encode<T: BufEncode>(value: &T) -> Bytes {
+    let mut buf = BytesMut::new();
+    value.encode(&mut buf);
+    buf.freeze()
+}
+
+#[test]
+fn primitives_are_big_endian() {
+    assert_eq!(encode(&0x0102u16), &b"\x01\x02"[..]);
+    assert_eq!(encode(&-2i32), &b"\xff\xff\xff\xfe"[..]);
+    assert_eq!(encode(&1.0f32), &b"\x3f\x80\x00\x00"[..]);
+    assert_eq!(encode(&true), &b"\x01"[..]);
+
+    let mut src = &b"\x01\x02\x00\x00\x00\x03\x01"[..];
+    assert_eq!(u16::decode(&mut src), Ok(0x0102));
+    assert_eq!(u32::decode(&mut src), Ok(3));
+    assert_eq!(bool::decode(&mut src), Ok(true));
+    assert_eq!(u8::decode(&mut src), Err(WireError::UnexpectedEof));
+}
+
+#[test]
+fn invalid_bool() {
+    assert_eq!(
+        bool::decode(&mut &b"\x02"[..]),
+        Err(WireError::InvalidBool(2))
+    );
+}
+
+#[test]
+fn little_endian() {
+    let mut buf = BytesMut::new();
+    0x0102u16.encode_le(&mut buf);
+    (-1.5f64).encode_le(&mut buf);
+    assert_eq!(&buf[..2], b"\x02\x01");
+
+    let mut src = buf.freeze();
+    assert_eq!(u16::decode_le(&mut src), Ok(0x0102));
+    assert_eq!(f64::decode_le(&mut src), Ok(-1.5));
+}
+
+#[test]
+fn varints() {
+    let mut buf = BytesMut::new();
+    300u32.encode_varint(&mut buf);
+    (-2i16).encode_varint(&mut buf);
+    u64::MAX.encode_varint(&mut buf);
+    assert_eq!(&buf[..3], b"\xac\x02\x03");
+
+    let mut src = buf.freeze();
+    assert_eq!(u32::decode_varint(&mut src), Ok(300));
+    assert_eq!(i16::decode_varint(&mut src), Ok(-2));
+    assert_eq!(u16::decode_varint(&mut src), Err(WireError::Overflow));
+
+    assert_eq!(
+        u64::decode_varint(&mut &b"\x80"[..]),
+        Err(WireError::UnexpectedEof)
+    );
+}
+
+#[test]
+fn arrays() {
+    let value = [0x0102u16, 0x0304, 0x0506];
+    let bytes = encode(&value);
+    assert_eq!(bytes, &b"\x01\x02\x03\x04\x05\x06"[..]);
+    assert_eq!(<[u16; 3]>::decode(&mut bytes.clone()), Ok(value));
+
+    let mut short = bytes.slice(..5);
+    assert_eq!(
+        <[u16; 3]>::decode(&mut short),
+        Err(WireError::UnexpectedEof)
+    );
+
+    // Items that own memory are dropped when a later one fails.
+    let strings = encode(&[String::from("a"), String::from("b")]);
+    assert_eq!(
+        <[String; 3]>::decode(&mut strings.clone()),
+        Err(WireError::UnexpectedEof)
+    );
+    assert_eq!(
+        <[String; 2]>::decode(&mut strings.clone()),
+        Ok([String::from("a"), String::from("b")])
+    );
+}
+
+#[test]
+fn length_prefixed() {
+    let bytes = encode(&Bytes::from_static(b"hello"));
+    assert_eq!(bytes, &b"\x05hello"[..]);
+
+    let string = encode(&String::from("héllo"));
+    assert_eq!(string, &b"\x06h\xc3\xa9llo"[..]);
+    assert_eq!(String::decode(&mut string.clone()).unwrap(), "héllo");
+
+    let vec = encode(&vec![1u16, 2]);
+    assert_eq!(vec, &b"\x02\x00\x01\x00\x02"[..]);
+    assert_eq!(Vec::<u16>::decode(&mut vec.clone()), Ok(vec![1, 2]));
+}
+
+#[test]
+fn bytes_decode_is_zero_copy() {
+    let mut src = Bytes::from(b"\x20abcdefghijklmnopqrstuvwxyz012345-".to_vec());
+    let ptr = src.as_ptr();
+
+    let bytes = Bytes::decode(&mut src).unwrap();
+    assert_eq!(bytes.as_ptr(), unsafe { ptr.add(1) });
+    assert_eq!(bytes.len(), 32);
+
+    let mut src = Bytes::from(b"\x20abcdefghijklmnopqrstuvwxyz012345-".to_vec());
+    let s = ByteStr::decode(&mut src).unwrap();
+    assert_eq!(&s[..3], "abc");
+    assert_eq!(src, &b"-"[..]);
+}
+
+#[test]
+fn length_prefix_errors() {
+    // The prefix claims more than what is left.
+    assert_eq!(
+        Bytes::decode(&mut &b"\x05abc"[..]),
+        Err(WireError::UnexpectedEof)
+    );
+    assert_eq!(
+        Vec::<u32>::decode(&mut &b"\xff\xff\xff\xff\x0f"[..]),
+        Err(WireError::UnexpectedEof)
+    );
+    // Zero-sized elements consume nothing, so only the prefix bounds them.
+    assert_eq!(
+        Vec::<[u8; 0]>::decode(&mut &b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01"[..]),
+        Err(WireError::UnexpectedEof)
+    );
+    assert_eq!(
+        Vec::<[u8; 0]>::decode(&mut &b"\x02\x00\x00"[..]),
+        Ok(vec![[], []])
+    );
+
+    match String::decode(&mut &b"\x02\xc3\x28"[..]) {
+        Err(WireError::InvalidUtf8(e)) => assert_eq!(e.valid_up_to(), 0),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(
+        ByteStr::decode(&mut &b"\x01\xff"[..]),
+        Err(WireError::InvalidUtf8(_))
+    ));
+}
+
+#[test]
+fn int_repr() {
+    let mut buf = BytesMut::new();
+    IntRepr::U16Le.put(&mut buf, 0x0102);
+    IntRepr::U32.put_prefixed(&mut buf, &Bytes::from_static(b"ab"));
+    assert_eq!(buf, &b"\x02\x01\x00\x00\x00\x02ab"[..]);
+
+    let mut src = buf.freeze();
+    assert_eq!(IntRepr::U16Le.get(&mut src), Ok(0x0102));
+    assert_eq!(
+        IntRepr::U32.get_prefixed::<Bytes, _>(&mut src),
+        Ok(Bytes::from_static(b"ab"))
+    );
+    assert!(!src.has_remaining());
+}
+
+#[test]
+#[should_panic(expected = "does not fit")]
+fn int_repr_overflow() {
+    IntRepr::U8.put_prefixed(&mut BytesMut::new(), &vec![0u8; 256]);
+}
+
+#[test]
+fn error_display() {
+    assert_eq!(WireError::InvalidTag(9).to_string(), "invalid enum tag 9");
+    assert_eq!(
+        WireError::UnexpectedEof.to_string(),
+        "unexpected end of input"
+    );
+}
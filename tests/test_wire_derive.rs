@@ -0,0 +1,212 @@
+// MIT License
+//
+// Copyright (c) 2022 Al Liu (https://github.com/al8n/rcbytes)
+//
+// Copyright (c) 2018 Carl Lerche (https://github.com/tokio-rs/bytes)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![cfg(feature = "derive")]
+#![warn(rust_2018_idioms)]
+
+use rcbytes::wire::{BufDecode, BufEncode, WireError};
+use rcbytes::{ByteStr, Bytes, BytesMut};
+
+fn round_trip<T: BufEncode + BufDecode + PartialEq + std::fmt::Debug>(value: T, wire: &[u8]) {
+    let mut buf = BytesMut::new();
+    value.encode(&mut buf);
+    assert_eq!(&buf[..], wire);
+
+    let mut src = buf.freeze();
+    assert_eq!(T::decode(&mut src), Ok(value));
+    assert!(src.is_empty());
+}
+
+#[derive(Debug, PartialEq, BufEncode, BufDecode)]
+struct Header {
+    version: u8,
+    #[wire(le)]
+    flags: u16,
+    #[wire(varint)]
+    id: u64,
+    #[wire(varint)]
+    delta: i32,
+    #[wire(len = "u16")]
+    payload: Bytes,
+    #[wire(len = "u32", le)]
+    name: String,
+    #[wire(len = "u8")]
+    values: Vec<u16>,
+    tail: [u8; 2],
+    #[wire(skip)]
+    cached: Option<u32>,
+}
+
+#[test]
+fn struct_with_attributes() {
+    round_trip(
+        Header {
+            version: 1,
+            flags: 0x0102,
+            id: 300,
+            delta: -1,
+            payload: Bytes::from_static(b"hi"),
+            name: String::from("ab"),
+            values: vec![7, 8],
+            tail: [0xaa, 0xbb],
+            cached: None,
+        },
+        b"\x01\x02\x01\xac\x02\x01\x00\x02hi\x02\x00\x00\x00ab\x02\x00\x07\x00\x08\xaa\xbb",
+    );
+}
+
+#[test]
+fn skipped_fields_decode_as_default() {
+    let header = Header {
+        version: 1,
+        flags: 0,
+        id: 0,
+        delta: 0,
+        payload: Bytes::new(),
+        name: String::new(),
+        values: vec![],
+        tail: [0; 2],
+        cached: Some(5),
+    };
+    let mut buf = BytesMut::new();
+    header.encode(&mut buf);
+    assert_eq!(Header::decode(&mut buf.freeze()).unwrap().cached, None);
+}
+
+#[derive(Debug, PartialEq, BufEncode, BufDecode)]
+struct Pair(u16, #[wire(len = "varint")] ByteStr);
+
+#[derive(Debug, PartialEq, BufEncode, BufDecode)]
+struct Unit;
+
+#[derive(Debug, PartialEq, BufEncode, BufDecode)]
+struct Nested {
+    pair: Pair,
+    unit: Unit,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, BufEncode, BufDecode)]
+enum Kind {
+    A,
+    B = 5,
+    C,
+    #[wire(tag = 0x20)]
+    D,
+}
+
+#[test]
+fn tuple_unit_and_nested() {
+    round_trip(Unit, b"");
+    round_trip(
+        Nested {
+            pair: Pair(0x0102, ByteStr::from_static("x")),
+            unit: Unit,
+            kind: Kind::C,
+        },
+        b"\x01\x02\x01x\x06",
+    );
+}
+
+#[test]
+fn enum_tags() {
+    round_trip(Kind::A, b"\x00");
+    round_trip(Kind::B, b"\x05");
+    round_trip(Kind::C, b"\x06");
+    round_trip(Kind::D, b"\x20");
+
+    assert_eq!(
+        Kind::decode(&mut &b"\x01"[..]),
+        Err(WireError::InvalidTag(1))
+    );
+}
+
+#[derive(Debug, PartialEq, BufEncode, BufDecode)]
+#[wire(tag = "u16", le)]
+enum Message {
+    Ping,
+    Data {
+        #[wire(varint)]
+        stream: u32,
+        #[wire(len = "u16")]
+        body: Bytes,
+    },
+    #[wire(tag = 9)]
+    Close(u8, #[wire(skip)] u8),
+}
+
+#[test]
+fn enum_with_fields() {
+    round_trip(Message::Ping, b"\x00\x00");
+    round_trip(
+        Message::Data {
+            stream: 1,
+            body: Bytes::from_static(b"abc"),
+        },
+        b"\x01\x00\x01\x00\x03abc",
+    );
+    round_trip(Message::Close(4, 0), b"\x09\x00\x04");
+
+    assert_eq!(
+        Message::decode(&mut &b"\x01\x00\x01\x00\x05abc"[..]),
+        Err(WireError::UnexpectedEof)
+    );
+    assert_eq!(
+        Message::decode(&mut &b"\x00\x01"[..]),
+        Err(WireError::InvalidTag(256))
+    );
+}
+
+#[derive(Debug, PartialEq, BufEncode, BufDecode)]
+struct Generic<T> {
+    count: u8,
+    items: Vec<T>,
+}
+
+#[test]
+fn generic_struct() {
+    round_trip(
+        Generic {
+            count: 2,
+            items: vec![Kind::A, Kind::B],
+        },
+        b"\x02\x02\x00\x05",
+    );
+}
+
+#[test]
+fn bytes_fields_are_zero_copy() {
+    let mut buf = BytesMut::new();
+    Message::Data {
+        stream: 1,
+        body: Bytes::from(vec![b'x'; 64]),
+    }
+    .encode(&mut buf);
+
+    let src = buf.freeze();
+    let ptr = src.as_ptr();
+    match Message::decode(&mut src.clone()).unwrap() {
+        Message::Data { body, .. } => assert_eq!(body.as_ptr(), unsafe { ptr.add(5) }),
+        other => panic!("unexpected {:?}", other),
+    }
+}